@@ -72,6 +72,7 @@ pub enum ProofGenerationStatus {
     Completed,  // 已完成
     Failed,     // 生成失败
     Expired,    // 已过期
+    Cancelled,  // 已取消
 }
 
 /// 零知识证明数据
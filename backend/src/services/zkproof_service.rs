@@ -14,8 +14,10 @@ use crate::{
 pub struct ZKProofService {
     database: Arc<Database>,
     config: Arc<AppConfig>,
-    /// 正在处理的证明任务
-    pending_proofs: tokio::sync::RwLock<HashMap<String, ProofGenerationResponse>>,
+    /// 正在处理的证明任务（克隆的实例共享）
+    pending_proofs: Arc<tokio::sync::RwLock<HashMap<String, ProofGenerationResponse>>>,
+    /// 正在执行的证明任务句柄，用于中止
+    running_tasks: Arc<tokio::sync::Mutex<HashMap<String, tokio::task::AbortHandle>>>,
     /// 验证缓存
     verification_cache: tokio::sync::RwLock<HashMap<String, (bool, Instant)>>,
 }
@@ -26,7 +28,8 @@ impl ZKProofService {
         Ok(Self {
             database,
             config,
-            pending_proofs: tokio::sync::RwLock::new(HashMap::new()),
+            pending_proofs: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            running_tasks: Arc::new(tokio::sync::Mutex::new(HashMap::new())),
             verification_cache: tokio::sync::RwLock::new(HashMap::new()),
         })
     }
@@ -58,11 +61,14 @@ impl ZKProofService {
         // 异步处理证明生成
         let service_clone = Arc::new(self.clone());
         let proof_id_clone = proof_id.clone();
-        tokio::spawn(async move {
+        let mut running = self.running_tasks.lock().await;
+        let task = tokio::spawn(async move {
             if let Err(e) = service_clone.process_proof_generation(&proof_id_clone, request).await {
                 error!("证明生成失败: {}", e);
             }
+            service_clone.running_tasks.lock().await.remove(&proof_id_clone);
         });
+        running.insert(proof_id.clone(), task.abort_handle());
 
         Ok(response)
     }
@@ -189,11 +195,16 @@ impl ZKProofService {
 
     /// 取消证明生成
     pub async fn cancel_proof_generation(&self, proof_id: &str) -> Result<()> {
+        // 先中止任务，保证之后不会再写入完成状态
+        if let Some(task) = self.running_tasks.lock().await.remove(proof_id) {
+            task.abort();
+        }
+
         let mut pending = self.pending_proofs.write().await;
         
         if let Some(response) = pending.get_mut(proof_id) {
             if matches!(response.status, ProofGenerationStatus::Pending | ProofGenerationStatus::Processing) {
-                response.status = ProofGenerationStatus::Failed;
+                response.status = ProofGenerationStatus::Cancelled;
                 info!("证明生成已取消: {}", proof_id);
             }
         }
@@ -219,7 +230,8 @@ impl Clone for ZKProofService {
         Self {
             database: self.database.clone(),
             config: self.config.clone(),
-            pending_proofs: tokio::sync::RwLock::new(HashMap::new()),
+            pending_proofs: self.pending_proofs.clone(),
+            running_tasks: self.running_tasks.clone(),
             verification_cache: tokio::sync::RwLock::new(HashMap::new()),
         }
     }
@@ -0,0 +1,215 @@
+// 证明基准测试 - 默认电路的证明与验证、批量验证扩展性、证明封装序列化
+//
+// 运行：cargo bench --bench proof_benchmark
+// criterion 的逐项结果位于 target/criterion/<组>/<项>/new/estimates.json，
+// 运行结束后所有已有结果汇总写入 target/criterion/summary.json，便于跟踪性能回归。
+use anyhow::Result;
+use criterion::{criterion_group, BenchmarkId, Criterion, Throughput};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+use zkproof::cache::ProofCacheConfig;
+use zkproof::commitment::SampleOpening;
+use zkproof::envelope::ProofEnvelope;
+use zkproof::proof_system::{Groth16ProofSystem, ProofSystem};
+use zkproof::registry::KeyRegistry;
+use zkproof::signatures::SourcePublicKey;
+use zkproof::{DataSource, DataSourceType, MetricSubmission, ServiceConfig, ZKProof, ZKProofService};
+
+/// 默认网络指标电路对应的 (名称, 数据点数, 数据源数)
+const CIRCUIT_SIZES: [(&str, usize, usize); 3] = [("small", 10, 5), ("medium", 50, 20), ("large", 200, 100)];
+/// 批量验证的证明数量
+const BATCH_SIZES: [usize; 6] = [1, 2, 4, 8, 16, 32];
+
+/// 关闭证明缓存的服务实例，保证每次迭代都实际生成证明
+fn service() -> ZKProofService {
+    let config = ServiceConfig {
+        cache: ProofCacheConfig::new(0, Duration::ZERO),
+        ..ServiceConfig::default()
+    };
+    ZKProofService::with_config(KeyRegistry::new(), config).expect("service")
+}
+
+/// 恰好填满指定容量的指标提交，seed 区分提交者（空值符）
+fn submission(data_points: usize, sources: usize, seed: u8) -> MetricSubmission {
+    let window_start = 1_700_000_000;
+    let per_source = data_points / sources;
+    MetricSubmission {
+        metric_type: "block_time".to_string(),
+        private_data: vec![6000; per_source * sources],
+        data_sources: (0..sources)
+            .map(|i| {
+                let mut key = [0u8; 32];
+                key[..8].copy_from_slice(&(i as u64).to_le_bytes());
+                DataSource {
+                    source_type: DataSourceType::ValidatorNode,
+                    source_id: format!("validator_{:03}", i),
+                    timestamp: window_start + 1800,
+                    reliability_score: 90,
+                    public_key: SourcePublicKey::Ed25519(key),
+                }
+            })
+            .collect(),
+        public_metric: 6000,
+        quality_score: 90,
+        time_window_hours: 1,
+        window_start,
+        source_secret: [seed; 32],
+        sample_openings: (0..sources)
+            .map(|i| SampleOpening { sample_count: per_source, blinding: [i as u8; 32] })
+            .collect(),
+    }
+}
+
+fn generate(runtime: &Runtime, service: &mut ZKProofService, submission: MetricSubmission) -> ZKProof {
+    runtime.block_on(service.generate_metric_proof(submission)).expect("proof").0
+}
+
+/// 小、中、大三个网络指标电路的证明与验证
+fn bench_metric_circuits(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("runtime");
+
+    for (name, data_points, sources) in CIRCUIT_SIZES {
+        let mut service = service();
+        let submission = submission(data_points, sources, 1);
+        // 预先生成参数，不计入证明耗时
+        let proof = generate(&runtime, &mut service, submission.clone());
+
+        let mut group = c.benchmark_group("metric_circuit");
+        group.sample_size(10);
+        group.bench_function(BenchmarkId::new("prove", name), |b| {
+            b.iter(|| generate(&runtime, &mut service, submission.clone()))
+        });
+        // 直接调用后端，绕过验证器的结果缓存
+        group.bench_function(BenchmarkId::new("verify", name), |b| {
+            b.iter(|| {
+                let is_valid = Groth16ProofSystem
+                    .verify(&proof.verification_key, &proof.proof_value, &proof.public_inputs)
+                    .expect("verify");
+                assert!(is_valid);
+            })
+        });
+        group.finish();
+    }
+}
+
+/// 批量验证耗时随证明数量的变化
+fn bench_batch_verification(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("runtime");
+    let mut service = service();
+    let (_, data_points, sources) = CIRCUIT_SIZES[0];
+    let max_batch = BATCH_SIZES[BATCH_SIZES.len() - 1];
+    let proofs: Vec<ZKProof> = (0..max_batch)
+        .map(|seed| generate(&runtime, &mut service, submission(data_points, sources, seed as u8)))
+        .collect();
+
+    let verification_key = &proofs[0].verification_key;
+    let items: Vec<(&[u8], &[u128])> = proofs
+        .iter()
+        .map(|proof| (proof.proof_value.as_slice(), proof.public_inputs.as_slice()))
+        .collect();
+
+    let mut group = c.benchmark_group("batch_verify");
+    for batch_size in BATCH_SIZES {
+        group.throughput(Throughput::Elements(batch_size as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(batch_size), &items[..batch_size], |b, batch| {
+            b.iter(|| {
+                let results = Groth16ProofSystem.batch_verify(verification_key, batch).expect("verify");
+                assert!(results.iter().all(|is_valid| *is_valid));
+            })
+        });
+    }
+    group.finish();
+}
+
+/// 证明封装的 SCALE 与 JSON 编解码
+fn bench_serialization(c: &mut Criterion) {
+    let runtime = Runtime::new().expect("runtime");
+    let mut service = service();
+    let (_, data_points, sources) = CIRCUIT_SIZES[0];
+    let proof = generate(&runtime, &mut service, submission(data_points, sources, 1));
+    let envelope = service.export_envelope(&proof).expect("envelope");
+    let scale = envelope.to_scale();
+    let json = envelope.to_json().expect("json");
+
+    let mut group = c.benchmark_group("envelope");
+    group.bench_function("to_scale", |b| b.iter(|| envelope.to_scale()));
+    group.bench_function("from_scale", |b| b.iter(|| ProofEnvelope::from_scale(&scale).expect("decode")));
+    group.bench_function("to_json", |b| b.iter(|| envelope.to_json().expect("encode")));
+    group.bench_function("from_json", |b| b.iter(|| ProofEnvelope::from_json(&json).expect("decode")));
+    group.finish();
+}
+
+criterion_group!(benches, bench_metric_circuits, bench_batch_verification, bench_serialization);
+
+fn main() {
+    benches();
+    criterion::Criterion::default().configure_from_args().final_summary();
+
+    if let Some(dir) = criterion_dir() {
+        match write_summary(&dir) {
+            Ok(path) => println!("summary written to {}", path.display()),
+            Err(e) => eprintln!("failed to write benchmark summary: {}", e),
+        }
+    }
+}
+
+/// criterion 输出目录：CRITERION_HOME，或基准程序所在 target 目录下的 criterion
+fn criterion_dir() -> Option<PathBuf> {
+    if let Some(home) = std::env::var_os("CRITERION_HOME") {
+        return Some(PathBuf::from(home));
+    }
+    // 基准程序位于 target/<profile>/deps/
+    let exe = std::env::current_exe().ok()?;
+    Some(exe.ancestors().nth(3)?.join("criterion"))
+}
+
+/// 汇总各基准的最新结果（纳秒）
+fn write_summary(dir: &Path) -> Result<PathBuf> {
+    let mut benchmarks = Vec::new();
+    collect_estimates(dir, &mut benchmarks)?;
+    benchmarks.sort_by(|a, b| a["id"].as_str().cmp(&b["id"].as_str()));
+
+    let path = dir.join("summary.json");
+    let summary = json!({
+        "crate": "zkproof",
+        "version": env!("CARGO_PKG_VERSION"),
+        "unit": "ns",
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "benchmarks": benchmarks,
+    });
+    fs::write(&path, serde_json::to_string_pretty(&summary)?)?;
+    Ok(path)
+}
+
+/// 递归查找 <项>/new/benchmark.json 与 estimates.json，跳过中断运行留下的不完整结果
+fn collect_estimates(dir: &Path, benchmarks: &mut Vec<Value>) -> Result<()> {
+    let new = dir.join("new");
+    if new.join("estimates.json").exists() {
+        if !new.join("benchmark.json").exists() {
+            return Ok(());
+        }
+        let benchmark: Value = serde_json::from_slice(&fs::read(new.join("benchmark.json"))?)?;
+        let estimates: Value = serde_json::from_slice(&fs::read(new.join("estimates.json"))?)?;
+        let estimate = |name: &str| estimates[name]["point_estimate"].clone();
+        benchmarks.push(json!({
+            "id": benchmark["full_id"],
+            "mean": estimate("mean"),
+            "median": estimate("median"),
+            "std_dev": estimate("std_dev"),
+            "throughput": benchmark["throughput"],
+        }));
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && path.file_name().is_some_and(|name| name != "report") {
+            collect_estimates(&path, benchmarks)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,428 @@
+// zkproof 命令行工具
+use anyhow::Result;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use zkproof::ceremony::{Ceremony, CeremonyPhase};
+use zkproof::circuits::{CircuitManager, NetworkMetricCircuit};
+use zkproof::envelope::{ProofEnvelope, ENVELOPE_MAGIC};
+use zkproof::proof_system::ProofSystemKind;
+use zkproof::registry::KeyRegistry;
+use zkproof::{utils, MetricSubmission, ZKProofService};
+
+/// PolyVisor 零知识证明工具
+#[derive(Parser)]
+#[command(name = "zkproof", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 生成（或加载已有的）电路参数并导出到参数目录
+    Setup {
+        /// 参数目录
+        #[arg(long, default_value = "params")]
+        out: PathBuf,
+        /// 电路ID，可重复指定；缺省时处理全部已注册电路
+        #[arg(long = "circuit")]
+        circuits: Vec<u32>,
+        /// 证明系统后端（groth16 / mock）
+        #[arg(long, default_value = "groth16")]
+        backend: ProofSystemKind,
+    },
+    /// 由 JSON 格式的 MetricSubmission 生成指标证明并写出证明封装
+    Prove {
+        /// 提交文件（JSON）
+        submission: PathBuf,
+        /// 证明封装输出路径
+        #[arg(long, short)]
+        out: PathBuf,
+        /// 参数目录，需先以 `zkproof setup` 生成参数
+        #[arg(long, default_value = "params")]
+        params: PathBuf,
+        /// 证明系统后端（groth16 / mock）
+        #[arg(long, default_value = "groth16")]
+        backend: ProofSystemKind,
+        /// 封装格式
+        #[arg(long, value_enum, default_value_t = EnvelopeFormat::Json)]
+        format: EnvelopeFormat,
+    },
+    /// 使用参数目录中的验证密钥验证证明封装（JSON 或 SCALE）
+    Verify {
+        /// 证明封装文件
+        envelope: PathBuf,
+        /// 参数目录
+        #[arg(long, default_value = "params")]
+        params: PathBuf,
+        /// 期望的证明系统后端（groth16 / mock），封装声明的后端不同时拒绝
+        #[arg(long, default_value = "groth16")]
+        backend: ProofSystemKind,
+    },
+    /// 打印电路统计信息；指定电路时打印其公开输入规范与合成结果
+    Circuits {
+        /// 电路ID
+        #[arg(long = "circuit")]
+        circuit: Option<u32>,
+    },
+    /// 解码证明封装并按电路的公开输入规范打印各字段
+    Inspect {
+        /// 证明封装文件
+        envelope: PathBuf,
+    },
+    /// Groth16 可信设置仪式：各参与者依次贡献，任何人都可审计转录
+    Ceremony {
+        #[command(subcommand)]
+        command: CeremonyCommand,
+    },
+}
+
+/// 可信设置仪式子命令
+#[derive(Subcommand)]
+enum CeremonyCommand {
+    /// 为电路创建新的仪式转录
+    Init {
+        /// 电路ID
+        #[arg(long)]
+        circuit: u32,
+        /// 转录输出路径
+        #[arg(long, short)]
+        out: PathBuf,
+    },
+    /// 以本地熵向转录的当前阶段贡献随机因子
+    Contribute {
+        /// 输入转录
+        transcript: PathBuf,
+        /// 贡献后的转录输出路径
+        #[arg(long, short)]
+        out: PathBuf,
+        /// 附加熵，与系统随机数混合
+        #[arg(long)]
+        entropy: Option<String>,
+    },
+    /// 结束第一阶段，由电路计算第二阶段的初始参数
+    Phase2 {
+        /// 输入转录
+        transcript: PathBuf,
+        /// 输出转录
+        #[arg(long, short)]
+        out: PathBuf,
+    },
+    /// 验证转录并打印各贡献的哈希
+    Verify {
+        /// 转录文件
+        transcript: PathBuf,
+    },
+    /// 验证转录，将最终参数与验证密钥写入参数目录
+    Finalize {
+        /// 转录文件
+        transcript: PathBuf,
+        /// 参数目录
+        #[arg(long, default_value = "params")]
+        params: PathBuf,
+    },
+}
+
+/// 证明封装的编码格式
+#[derive(Clone, Copy, ValueEnum)]
+enum EnvelopeFormat {
+    /// JSON
+    Json,
+    /// SCALE 二进制
+    Scale,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    match Cli::parse().command {
+        Command::Setup { out, circuits, backend } => setup(out, circuits, backend),
+        Command::Prove { submission, out, params, backend, format } => {
+            prove(&submission, &out, params, backend, format).await
+        }
+        Command::Verify { envelope, params, backend } => verify(&envelope, params, backend).await,
+        Command::Circuits { circuit } => circuits(circuit),
+        Command::Inspect { envelope } => inspect(&envelope),
+        Command::Ceremony { command } => ceremony(command),
+    }
+}
+
+/// 为选定电路生成参数文件并打印验证密钥哈希
+fn setup(out: PathBuf, circuit_ids: Vec<u32>, backend: ProofSystemKind) -> Result<()> {
+    let mut manager = CircuitManager::new();
+    let registry = KeyRegistry::with_params_dir(&out);
+
+    let mut circuit_ids = if circuit_ids.is_empty() {
+        manager.circuit_ids()
+    } else {
+        circuit_ids
+    };
+    circuit_ids.sort_unstable();
+
+    for circuit_id in circuit_ids {
+        if !manager.set_proof_system(circuit_id, backend) {
+            return Err(anyhow::anyhow!("Unknown circuit id: {}", circuit_id));
+        }
+        let circuit = manager
+            .get_circuit(circuit_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown circuit id: {}", circuit_id))?;
+
+        registry.get_or_generate(circuit)?;
+        let entry = registry
+            .verification_key(circuit_id, backend)?
+            .ok_or_else(|| anyhow::anyhow!("Verification key missing for circuit {}", circuit_id))?;
+
+        println!(
+            "circuit {:>3}  {:<8} vk {}  ({})",
+            circuit_id,
+            backend.name(),
+            entry.vk_hash_hex(),
+            circuit.description,
+        );
+    }
+
+    println!("parameters written to {}", out.display());
+    Ok(())
+}
+
+/// 生成指标证明并写出封装
+async fn prove(
+    submission_path: &Path,
+    out: &Path,
+    params: PathBuf,
+    backend: ProofSystemKind,
+    format: EnvelopeFormat,
+) -> Result<()> {
+    let submission: MetricSubmission = serde_json::from_slice(&fs::read(submission_path)?)
+        .map_err(|e| anyhow::anyhow!("Invalid submission {}: {}", submission_path.display(), e))?;
+    let mut service = service(params, backend)?;
+
+    let (proof, metadata) = service.generate_metric_proof(submission).await?;
+    let envelope = service.export_envelope(&proof)?;
+    match format {
+        EnvelopeFormat::Json => fs::write(out, envelope.to_json()?)?,
+        EnvelopeFormat::Scale => fs::write(out, envelope.to_scale())?,
+    }
+
+    println!("circuit       {}", envelope.circuit_id);
+    println!("backend       {}", envelope.backend.name());
+    println!("vk hash       {}", hex::encode(envelope.vk_hash));
+    println!("generated in  {:?}", metadata.generation_time);
+    println!("envelope written to {}", out.display());
+    Ok(())
+}
+
+/// 验证证明封装，无效时以非零状态退出
+///
+/// 后端由调用方指定，不信任封装中声明的后端。
+async fn verify(path: &Path, params: PathBuf, backend: ProofSystemKind) -> Result<()> {
+    let envelope = read_envelope(path)?;
+    if envelope.backend != backend {
+        return Err(anyhow::anyhow!(
+            "envelope uses the {} backend, expected {}",
+            envelope.backend.name(),
+            backend.name()
+        ));
+    }
+    let mut service = service(params, backend)?;
+
+    let (is_valid, verification_time) = service.verify_envelope(&envelope).await?;
+    if !is_valid {
+        return Err(anyhow::anyhow!(
+            "proof for circuit {} is INVALID ({:?})",
+            envelope.circuit_id,
+            verification_time
+        ));
+    }
+    println!("proof for circuit {} is valid ({:?})", envelope.circuit_id, verification_time);
+    Ok(())
+}
+
+/// 打印电路统计信息或单个电路的详情
+fn circuits(circuit_id: Option<u32>) -> Result<()> {
+    let manager = CircuitManager::new();
+
+    let circuit_id = match circuit_id {
+        Some(circuit_id) => circuit_id,
+        None => {
+            println!("{}", serde_json::to_string_pretty(&manager.get_statistics())?);
+            for circuit_id in manager.circuit_ids() {
+                if let Some(circuit) = manager.get_circuit(circuit_id) {
+                    println!(
+                        "circuit {:>3}  {:<20} points {:>4}  sources {:>4}  ({})",
+                        circuit_id,
+                        format!("{:?}", circuit.circuit_type),
+                        circuit.max_data_points,
+                        circuit.max_data_sources,
+                        circuit.description,
+                    );
+                }
+            }
+            return Ok(());
+        }
+    };
+
+    let circuit = manager
+        .get_circuit(circuit_id)
+        .ok_or_else(|| anyhow::anyhow!("Unknown circuit id: {}", circuit_id))?;
+    let profile = manager.profiler().profile(circuit)?;
+
+    println!("circuit       {} ({:?}, {})", circuit_id, circuit.circuit_type, circuit.description);
+    println!("shape         {}", profile.shape);
+    println!("constraints   {}", profile.constraint_count);
+    println!("witnesses     {}", profile.witness_count);
+    println!("inputs        {}", profile.input_count);
+    println!("{}", serde_json::to_string_pretty(&circuit.get_public_input_spec())?);
+    Ok(())
+}
+
+/// 打印证明封装的解码字段
+fn inspect(path: &Path) -> Result<()> {
+    let envelope = read_envelope(path)?;
+    let manager = CircuitManager::new();
+    let spec = manager
+        .get_circuit(envelope.circuit_id)
+        .map(|circuit| circuit.get_public_input_spec());
+
+    println!("version       {}", envelope.version);
+    println!("backend       {}", envelope.backend.name());
+    println!("curve         {}", envelope.curve.name());
+    println!("circuit       {}", envelope.circuit_id);
+    println!("vk hash       {}", hex::encode(envelope.vk_hash));
+    println!("proof         {} bytes  {}", envelope.proof.len(), hex::encode(&envelope.proof));
+    if spec.is_none() {
+        println!("(circuit {} is not registered; inputs are unlabelled)", envelope.circuit_id);
+    }
+
+    if let Some(digest) = envelope.public_input_digest {
+        println!("public input digest  {}", hex::encode(digest));
+        if zkproof_verifier::digest_scalar(&digest).is_none() {
+            println!("warning: public input digest is not a canonical scalar");
+        }
+        return Ok(());
+    }
+
+    println!("public inputs");
+    let names: Vec<String> = match &spec {
+        Some(spec) => spec.inputs.iter().map(|input| input.name.clone()).collect(),
+        None => Vec::new(),
+    };
+    for (index, value) in envelope.public_inputs.iter().enumerate() {
+        let name = names.get(index).map_or("?", String::as_str);
+        println!("  [{:>2}] {:<24} {}", index, name, value);
+
+        // 低、高两个分量组成的标量另以规范字节表示打印
+        if let (Some(base), Some(low)) = (name.strip_suffix("_high"), index.checked_sub(1)) {
+            if names.get(low).map(String::as_str) == Some(&format!("{}_low", base)) {
+                let limbs = [envelope.public_inputs[low], *value];
+                match utils::scalar_from_u128_limbs(limbs) {
+                    Some(scalar) => println!("       {:<24} {}", base, hex::encode(scalar.to_bytes())),
+                    None => println!("       {:<24} (not a canonical scalar)", base),
+                }
+            }
+        }
+    }
+    if spec.as_ref().is_some_and(|spec| !spec.is_satisfied_by(&envelope.public_inputs)) {
+        println!("warning: public inputs do not satisfy the circuit's input specification");
+    }
+    Ok(())
+}
+
+/// 执行可信设置仪式的各步骤
+fn ceremony(command: CeremonyCommand) -> Result<()> {
+    match command {
+        CeremonyCommand::Init { circuit, out } => {
+            let ceremony = Ceremony::initialize(&ceremony_circuit(circuit)?)?;
+            write_transcript(&out, &ceremony)?;
+            println!("ceremony for circuit {} written to {}", circuit, out.display());
+        }
+        CeremonyCommand::Contribute { transcript, out, entropy } => {
+            let mut ceremony = read_transcript(&transcript)?;
+            let hash = ceremony.contribute(entropy.unwrap_or_default().as_bytes())?;
+            write_transcript(&out, &ceremony)?;
+            println!(
+                "{} contribution #{}  {}",
+                phase_name(ceremony.phase()),
+                ceremony.contributions(),
+                hex::encode(hash)
+            );
+            println!("transcript written to {}", out.display());
+        }
+        CeremonyCommand::Phase2 { transcript, out } => {
+            let mut ceremony = read_transcript(&transcript)?;
+            ceremony.begin_phase2(&ceremony_circuit(ceremony.circuit_id())?)?;
+            write_transcript(&out, &ceremony)?;
+            println!("phase 2 parameters written to {}", out.display());
+        }
+        CeremonyCommand::Verify { transcript } => {
+            let ceremony = read_transcript(&transcript)?;
+            let summary = ceremony.verify(&ceremony_circuit(ceremony.circuit_id())?)?;
+            println!("circuit       {}", summary.circuit_id);
+            println!("size          {}", summary.degree);
+            for (phase, hashes) in [("phase 1", &summary.phase1), ("phase 2", &summary.phase2)] {
+                for (index, hash) in hashes.iter().enumerate() {
+                    println!("{} #{:<4} {}", phase, index + 1, hex::encode(hash));
+                }
+            }
+            println!("transcript is valid");
+        }
+        CeremonyCommand::Finalize { transcript, params } => {
+            let ceremony = read_transcript(&transcript)?;
+            let keys = ceremony.finalize(&ceremony_circuit(ceremony.circuit_id())?)?;
+            let entry = KeyRegistry::with_params_dir(&params).import_keys(ceremony.circuit_id(), keys)?;
+            println!("circuit {:>3}  groth16  vk {}", entry.circuit_id, entry.vk_hash_hex());
+            println!("parameters written to {}", params.display());
+        }
+    }
+    Ok(())
+}
+
+/// 仪式对应的已注册电路
+fn ceremony_circuit(circuit_id: u32) -> Result<NetworkMetricCircuit> {
+    CircuitManager::new()
+        .get_circuit(circuit_id)
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("Unknown circuit id: {}", circuit_id))
+}
+
+fn phase_name(phase: CeremonyPhase) -> &'static str {
+    match phase {
+        CeremonyPhase::PowersOfTau => "phase 1",
+        CeremonyPhase::Circuit => "phase 2",
+    }
+}
+
+fn read_transcript(path: &Path) -> Result<Ceremony> {
+    Ceremony::read(BufReader::new(File::open(path)?))
+        .map_err(|e| anyhow::anyhow!("Invalid ceremony transcript {}: {}", path.display(), e))
+}
+
+fn write_transcript(path: &Path, ceremony: &Ceremony) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    ceremony.write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// 使用参数目录的服务实例，全部电路使用指定后端；参数只从目录加载，不会执行 setup
+fn service(params: PathBuf, backend: ProofSystemKind) -> Result<ZKProofService> {
+    let registry = KeyRegistry::with_params_dir(params).without_setup();
+    let mut service = ZKProofService::with_key_registry(registry)?;
+    for circuit_id in CircuitManager::new().circuit_ids() {
+        service.set_proof_system(circuit_id, backend)?;
+    }
+    Ok(service)
+}
+
+/// 读取证明封装，以魔数区分 SCALE 与 JSON 格式
+fn read_envelope(path: &Path) -> Result<ProofEnvelope> {
+    let bytes = fs::read(path)?;
+    let envelope = if bytes.starts_with(&ENVELOPE_MAGIC) {
+        ProofEnvelope::from_scale(&bytes)?
+    } else {
+        ProofEnvelope::from_json(std::str::from_utf8(&bytes)?)?
+    };
+    Ok(envelope)
+}
@@ -0,0 +1,364 @@
+// 证明缓存模块 - 带容量上限与过期时间的 LRU 证明缓存，可选磁盘存储
+//
+// 内存中的条目按最近使用顺序淘汰，超过 TTL 的条目在访问或清理时删除。
+// 配置了缓存目录时每个条目对应一个文件，磁盘内容与内存保持一致：
+// 写入、淘汰与过期同步反映到磁盘，重新打开缓存时加载未过期的条目。
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::{utils, ZKProof};
+
+/// 缓存文件扩展名
+const CACHE_FILE_EXTENSION: &str = "proof";
+
+/// 证明缓存配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofCacheConfig {
+    /// 最大条目数量，0 表示禁用缓存
+    pub max_entries: usize,
+    /// 条目的存活时间
+    pub ttl: Duration,
+    /// 磁盘存储目录，None 表示仅保存在内存中
+    pub cache_dir: Option<PathBuf>,
+}
+
+impl ProofCacheConfig {
+    /// 仅保存在内存中的缓存配置
+    pub fn new(max_entries: usize, ttl: Duration) -> Self {
+        Self {
+            max_entries,
+            ttl,
+            cache_dir: None,
+        }
+    }
+
+    /// 指定磁盘存储目录
+    pub fn with_cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+}
+
+impl Default for ProofCacheConfig {
+    fn default() -> Self {
+        Self::new(1000, Duration::from_secs(3600))
+    }
+}
+
+/// 缓存计数器
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheStats {
+    /// 命中次数
+    pub hits: u64,
+    /// 未命中次数（包括已过期的条目）
+    pub misses: u64,
+    /// 写入次数
+    pub insertions: u64,
+    /// 因容量上限淘汰的条目数
+    pub evictions: u64,
+    /// 因过期删除的条目数
+    pub expirations: u64,
+    /// 当前条目数
+    pub entries: usize,
+}
+
+impl CacheStats {
+    /// 命中率，尚无查询时为 0
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// 缓存条目
+struct CacheEntry {
+    proof: ZKProof,
+    cached_at: SystemTime,
+    /// 最近一次使用的序号
+    last_used: u64,
+}
+
+/// 磁盘上的缓存条目
+#[derive(Serialize, Deserialize)]
+struct StoredEntry {
+    key: String,
+    /// 写入时间（Unix 毫秒）
+    cached_at_ms: u64,
+    proof: ZKProof,
+}
+
+/// LRU + TTL 证明缓存
+pub struct ProofCache {
+    config: ProofCacheConfig,
+    entries: HashMap<String, CacheEntry>,
+    /// 使用序号 -> 键，序号最小的条目最先淘汰
+    recency: BTreeMap<u64, String>,
+    next_use: u64,
+    stats: CacheStats,
+}
+
+impl ProofCache {
+    /// 创建缓存；配置了磁盘目录时加载其中未过期的条目，并删除过期或超出容量的文件
+    pub fn open(config: ProofCacheConfig) -> Result<Self> {
+        let mut cache = Self {
+            config,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_use: 0,
+            stats: CacheStats::default(),
+        };
+
+        let dir = match cache.config.cache_dir.clone() {
+            Some(dir) => dir,
+            None => return Ok(cache),
+        };
+        fs::create_dir_all(&dir)?;
+
+        let mut stored = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|ext| ext != CACHE_FILE_EXTENSION) {
+                continue;
+            }
+            // 损坏的文件直接丢弃
+            match fs::read(&path).ok().and_then(|bytes| serde_json::from_slice::<StoredEntry>(&bytes).ok()) {
+                Some(entry) => stored.push(entry),
+                None => remove_file(&path),
+            }
+        }
+
+        // 按写入时间从旧到新插入，容量不足时淘汰最旧的条目
+        stored.sort_by_key(|entry| entry.cached_at_ms);
+        for entry in stored {
+            let cached_at = UNIX_EPOCH + Duration::from_millis(entry.cached_at_ms);
+            if cache.is_expired(cached_at) || cache.config.max_entries == 0 {
+                remove_file(&file_path(&dir, &entry.key));
+                continue;
+            }
+            cache.insert_entry(entry.key, entry.proof, cached_at);
+        }
+        cache.stats.insertions = 0;
+        cache.stats.evictions = 0;
+        Ok(cache)
+    }
+
+    /// 缓存配置
+    pub fn config(&self) -> &ProofCacheConfig {
+        &self.config
+    }
+
+    /// 查找证明，命中时将条目标记为最近使用
+    pub fn get(&mut self, key: &str) -> Option<ZKProof> {
+        let cached_at = match self.entries.get(key) {
+            Some(entry) => entry.cached_at,
+            None => {
+                self.stats.misses += 1;
+                return None;
+            }
+        };
+        if self.is_expired(cached_at) {
+            self.remove(key);
+            self.stats.expirations += 1;
+            self.stats.misses += 1;
+            return None;
+        }
+
+        let use_id = self.next_use();
+        let entry = self.entries.get_mut(key).expect("entry exists");
+        self.recency.remove(&entry.last_used);
+        self.recency.insert(use_id, key.to_string());
+        entry.last_used = use_id;
+        self.stats.hits += 1;
+        Some(entry.proof.clone())
+    }
+
+    /// 写入证明，超出容量时淘汰最久未使用的条目
+    pub fn insert(&mut self, key: String, proof: ZKProof) -> Result<()> {
+        if self.config.max_entries == 0 {
+            return Ok(());
+        }
+
+        let cached_at = SystemTime::now();
+        if let Some(dir) = &self.config.cache_dir {
+            let stored = StoredEntry {
+                key: key.clone(),
+                cached_at_ms: cached_at.duration_since(UNIX_EPOCH)?.as_millis() as u64,
+                proof: proof.clone(),
+            };
+            fs::write(file_path(dir, &key), serde_json::to_vec(&stored)?)?;
+        }
+        self.insert_entry(key, proof, cached_at);
+        Ok(())
+    }
+
+    /// 删除所有过期条目，返回删除的数量
+    pub fn remove_expired(&mut self) -> usize {
+        let expired: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| self.is_expired(entry.cached_at))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in &expired {
+            self.remove(key);
+        }
+        self.stats.expirations += expired.len() as u64;
+        expired.len()
+    }
+
+    /// 清空缓存（包括磁盘文件）
+    pub fn clear(&mut self) {
+        let keys: Vec<String> = self.entries.keys().cloned().collect();
+        for key in &keys {
+            self.remove(key);
+        }
+    }
+
+    /// 当前条目数
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 缓存是否为空
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 缓存计数器
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            entries: self.entries.len(),
+            ..self.stats
+        }
+    }
+
+    fn insert_entry(&mut self, key: String, proof: ZKProof, cached_at: SystemTime) {
+        self.remove_from_memory(&key);
+        let last_used = self.next_use();
+        self.recency.insert(last_used, key.clone());
+        self.entries.insert(key, CacheEntry { proof, cached_at, last_used });
+        self.stats.insertions += 1;
+
+        while self.entries.len() > self.config.max_entries {
+            let oldest = match self.recency.first_key_value() {
+                Some((_, key)) => key.clone(),
+                None => break,
+            };
+            self.remove(&oldest);
+            self.stats.evictions += 1;
+        }
+    }
+
+    /// 从内存与磁盘删除条目
+    ///
+    /// 磁盘文件删除失败时忽略，遗留的文件会在下次打开缓存时按过期或容量清理。
+    fn remove(&mut self, key: &str) {
+        self.remove_from_memory(key);
+        if let Some(dir) = &self.config.cache_dir {
+            remove_file(&file_path(dir, key));
+        }
+    }
+
+    fn remove_from_memory(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.recency.remove(&entry.last_used);
+        }
+    }
+
+    fn is_expired(&self, cached_at: SystemTime) -> bool {
+        SystemTime::now().duration_since(cached_at).unwrap_or(Duration::ZERO) >= self.config.ttl
+    }
+
+    fn next_use(&mut self) -> u64 {
+        self.next_use += 1;
+        self.next_use
+    }
+}
+
+/// 缓存文件路径，文件名取键的哈希，避免键中的字符影响路径
+fn file_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.{}", hex::encode(utils::hash_data(key.as_bytes())), CACHE_FILE_EXTENSION))
+}
+
+fn remove_file(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proof(circuit_id: u32) -> ZKProof {
+        ZKProof {
+            proof_value: vec![circuit_id as u8; 4],
+            public_inputs: vec![circuit_id as u128],
+            verification_key: vec![],
+            circuit_id,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_lru_eviction_and_expiry() {
+        let mut cache = ProofCache::open(ProofCacheConfig::new(2, Duration::from_secs(60))).unwrap();
+        cache.insert("a".to_string(), proof(1)).unwrap();
+        cache.insert("b".to_string(), proof(2)).unwrap();
+        assert_eq!(cache.get("a"), Some(proof(1)));
+
+        // b 最久未使用，被淘汰
+        cache.insert("c".to_string(), proof(3)).unwrap();
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("c"), Some(proof(3)));
+        assert_eq!(cache.len(), 2);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.evictions, stats.entries), (2, 1, 1, 2));
+        assert!((stats.hit_ratio() - 2.0 / 3.0).abs() < 1e-9);
+
+        let mut expiring = ProofCache::open(ProofCacheConfig::new(2, Duration::ZERO)).unwrap();
+        expiring.insert("a".to_string(), proof(1)).unwrap();
+        assert_eq!(expiring.get("a"), None);
+        assert_eq!(expiring.stats().expirations, 1);
+        assert!(expiring.is_empty());
+
+        let mut disabled = ProofCache::open(ProofCacheConfig::new(0, Duration::from_secs(60))).unwrap();
+        disabled.insert("a".to_string(), proof(1)).unwrap();
+        assert!(disabled.is_empty());
+    }
+
+    #[test]
+    fn test_disk_backed_cache_reload() {
+        let dir = std::env::temp_dir().join(format!("zkproof-cache-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let config = ProofCacheConfig::new(2, Duration::from_secs(60)).with_cache_dir(&dir);
+
+        let mut cache = ProofCache::open(config.clone()).unwrap();
+        cache.insert("a".to_string(), proof(1)).unwrap();
+        cache.insert("b".to_string(), proof(2)).unwrap();
+        cache.insert("c".to_string(), proof(3)).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        // 重新打开后加载未被淘汰的条目
+        let mut reloaded = ProofCache::open(config.clone()).unwrap();
+        assert_eq!(reloaded.len(), 2);
+        assert_eq!(reloaded.get("a"), None);
+        assert_eq!(reloaded.get("c"), Some(proof(3)));
+
+        // 容量缩小时删除多余的文件
+        let mut shrunk = ProofCache::open(ProofCacheConfig { max_entries: 1, ..config }).unwrap();
+        assert_eq!(shrunk.len(), 1);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        shrunk.clear();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,1337 @@
+// 可信设置仪式模块 - 由多个参与者依次贡献随机因子，生成任何人都可审计的 Groth16 参数
+//
+// 仪式分两个阶段，每份贡献都以本地熵生成随机因子，贡献后因子即被丢弃：
+//   第一阶段：τ、α、β 的幂累加器 [τ^i]₁ (i < 2m-1)、[τ^i]₂、[ατ^i]₁、[βτ^i]₁ (i < m) 与 [β]₂，
+//            m 为电路约束数（含每个公开输入的 x·0 = 0 约束）向上取整的 2 的幂，初始时 τ = α = β = 1；
+//   第二阶段：由最终累加器与电路的 QAP 确定性地计算参数（γ = δ = 1），之后每份贡献将 δ 乘以 d，
+//            同时将 H、L 查询乘以 d⁻¹。
+// 每份贡献附带对随机因子 x 的知识证明 (s, s·x, r·x)，其中 r 由转录哈希派生到 G2，
+// 验证者据此检查每一步都是在前一状态上乘以贡献者已知的因子。只要每个阶段至少有一位参与者
+// 诚实地丢弃了因子，就没有人掌握陷门。
+//
+// 转录文件格式（点均为非压缩编码，读取时检查子群）：
+//   magic "PVCE" | version (u8) | circuit_id (u32 LE) | shape (u32 LE 长度 + UTF-8) | m (u32 LE)
+//   | 累加器 | 第一阶段贡献 (u32 LE 数量 + 记录)
+//   | 第二阶段标志 (u8) [| bellman 参数 | 第二阶段贡献 (u32 LE 数量 + 记录)]
+use anyhow::Result;
+use bellman::domain::{EvaluationDomain, Group as DomainGroup, Scalar as DomainScalar};
+use bellman::groth16::{Parameters, VerifyingKey};
+use bellman::multicore::Worker;
+use bellman::multiexp::{multiexp, Exponent, FullDensity};
+use bellman::{Circuit, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+use bls12_381::{multi_miller_loop, Bls12, G1Affine, G1Projective, G2Affine, G2Prepared, G2Projective, Gt, Scalar};
+use ff::Field;
+use group::prime::PrimeCurveAffine;
+use group::{Curve, Group, UncompressedEncoding, Wnaf, WnafGroup};
+use rand::rngs::OsRng;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::circuits::NetworkMetricCircuit;
+use crate::proof_system::{CircuitInstance, CircuitKeys, Groth16ProofSystem, ProofSystemKind};
+use crate::ZKProofError;
+
+/// 转录文件魔数
+pub const TRANSCRIPT_MAGIC: [u8; 4] = *b"PVCE";
+/// 当前转录文件格式版本
+pub const TRANSCRIPT_VERSION: u8 = 1;
+/// 初始转录哈希的域分隔串
+const TRANSCRIPT_DOMAIN: &[u8] = b"polyvisor/groth16-ceremony/v1";
+/// 累加器规模的上限（2 的幂次）
+const MAX_DEGREE_LOG: u32 = 28;
+/// 电路形状描述的长度上限
+const MAX_SHAPE_LEN: usize = 1024;
+
+/// 仪式所处的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CeremonyPhase {
+    /// 第一阶段：τ、α、β 的幂累加器
+    PowersOfTau,
+    /// 第二阶段：电路相关的 δ
+    Circuit,
+}
+
+/// 转录验证结果：各贡献之后的转录哈希
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscriptSummary {
+    /// 电路ID
+    pub circuit_id: u32,
+    /// 累加器规模 m
+    pub degree: usize,
+    /// 第一阶段各贡献的哈希
+    pub phase1: Vec<[u8; 32]>,
+    /// 第二阶段各贡献的哈希
+    pub phase2: Vec<[u8; 32]>,
+}
+
+/// 可信设置仪式的转录
+#[derive(Clone, PartialEq)]
+pub struct Ceremony {
+    /// 电路ID
+    circuit_id: u32,
+    /// 电路形状，与参数绑定
+    shape: String,
+    /// 第一阶段累加器
+    accumulator: Accumulator,
+    /// 第一阶段贡献记录
+    phase1: Vec<Phase1Contribution>,
+    /// 第二阶段参数与贡献记录
+    phase2: Option<Phase2>,
+}
+
+impl Ceremony {
+    /// 为 Groth16 电路创建新的仪式
+    pub fn initialize(circuit: &NetworkMetricCircuit) -> Result<Self> {
+        if circuit.proof_system != ProofSystemKind::Groth16 {
+            return Err(ZKProofError::CryptographicError(format!(
+                "circuit {} does not use the Groth16 backend",
+                circuit.circuit_id
+            ))
+            .into());
+        }
+        let instance = CircuitInstance::from_circuit(circuit, None)?;
+        let shape = instance.shape();
+        let degree = Qap::synthesize(instance)?.degree();
+        if !(2..=1 << MAX_DEGREE_LOG).contains(&degree) {
+            return Err(ZKProofError::CryptographicError(format!(
+                "circuit {} needs an unsupported ceremony size {}",
+                circuit.circuit_id, degree
+            ))
+            .into());
+        }
+
+        Ok(Self {
+            circuit_id: circuit.circuit_id,
+            shape,
+            accumulator: Accumulator::new(degree),
+            phase1: Vec::new(),
+            phase2: None,
+        })
+    }
+
+    /// 电路ID
+    pub fn circuit_id(&self) -> u32 {
+        self.circuit_id
+    }
+
+    /// 当前阶段
+    pub fn phase(&self) -> CeremonyPhase {
+        match self.phase2 {
+            Some(_) => CeremonyPhase::Circuit,
+            None => CeremonyPhase::PowersOfTau,
+        }
+    }
+
+    /// 当前阶段的贡献数量
+    pub fn contributions(&self) -> usize {
+        match &self.phase2 {
+            Some(phase2) => phase2.contributions.len(),
+            None => self.phase1.len(),
+        }
+    }
+
+    /// 以本地熵向当前阶段贡献随机因子，返回贡献后的转录哈希
+    ///
+    /// 因子由系统随机数与 entropy 共同派生，贡献完成后即被丢弃。
+    pub fn contribute(&mut self, entropy: &[u8]) -> Result<[u8; 32]> {
+        let mut rng = contribution_rng(entropy);
+        let previous = self.transcript_hash();
+
+        let hash = match &mut self.phase2 {
+            None => {
+                let contribution = self.accumulator.contribute(&previous, &mut rng);
+                let hash = chain_hash(&previous, &contribution.to_bytes());
+                self.phase1.push(contribution);
+                hash
+            }
+            Some(phase2) => {
+                let contribution = phase2.contribute(&previous, &mut rng);
+                let hash = chain_hash(&previous, &contribution.to_bytes());
+                phase2.contributions.push(contribution);
+                hash
+            }
+        };
+        Ok(hash)
+    }
+
+    /// 结束第一阶段：验证累加器并由电路的 QAP 计算第二阶段的初始参数
+    pub fn begin_phase2(&mut self, circuit: &NetworkMetricCircuit) -> Result<()> {
+        if self.phase2.is_some() {
+            return Err(ZKProofError::CryptographicError("ceremony is already in phase 2".to_string()).into());
+        }
+        if self.phase1.is_empty() {
+            return Err(ZKProofError::CryptographicError(
+                "phase 1 needs at least one contribution".to_string(),
+            )
+            .into());
+        }
+        let qap = self.bind_circuit(circuit)?;
+        self.verify_phase1()?;
+
+        self.phase2 = Some(Phase2 {
+            params: self.accumulator.parameters(&qap)?,
+            contributions: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// 验证完整转录：每份贡献的知识证明、累加器结构以及第二阶段参数与电路的一致性
+    pub fn verify(&self, circuit: &NetworkMetricCircuit) -> Result<TranscriptSummary> {
+        let qap = self.bind_circuit(circuit)?;
+        let phase1 = self.verify_phase1()?;
+
+        let phase2 = match &self.phase2 {
+            Some(phase2) => {
+                let start = phase2_start(phase1.last().copied().unwrap_or_else(|| self.initial_hash()));
+                phase2.verify(&self.accumulator, &qap, start)?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(TranscriptSummary {
+            circuit_id: self.circuit_id,
+            degree: self.accumulator.degree(),
+            phase1,
+            phase2,
+        })
+    }
+
+    /// 验证转录并导出最终的证明/验证密钥，两个阶段都至少需要一份贡献
+    pub fn finalize(&self, circuit: &NetworkMetricCircuit) -> Result<CircuitKeys> {
+        let phase2 = match &self.phase2 {
+            Some(phase2) if !self.phase1.is_empty() && !phase2.contributions.is_empty() => phase2,
+            _ => {
+                return Err(ZKProofError::CryptographicError(
+                    "both ceremony phases need at least one contribution".to_string(),
+                )
+                .into())
+            }
+        };
+        self.verify(circuit)?;
+        Groth16ProofSystem::keys_from_parameters(phase2.params.clone())
+    }
+
+    /// 写出转录
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(&TRANSCRIPT_MAGIC)?;
+        writer.write_all(&[TRANSCRIPT_VERSION])?;
+        writer.write_all(&self.circuit_id.to_le_bytes())?;
+        writer.write_all(&(self.shape.len() as u32).to_le_bytes())?;
+        writer.write_all(self.shape.as_bytes())?;
+        writer.write_all(&(self.accumulator.degree() as u32).to_le_bytes())?;
+
+        self.accumulator.write(&mut writer)?;
+        writer.write_all(&(self.phase1.len() as u32).to_le_bytes())?;
+        for contribution in &self.phase1 {
+            writer.write_all(&contribution.to_bytes())?;
+        }
+
+        match &self.phase2 {
+            None => writer.write_all(&[0]),
+            Some(phase2) => {
+                writer.write_all(&[1])?;
+                phase2.params.write(&mut writer)?;
+                writer.write_all(&(phase2.contributions.len() as u32).to_le_bytes())?;
+                for contribution in &phase2.contributions {
+                    writer.write_all(&contribution.to_bytes())?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// 读取转录，拒绝无效的点与末尾多余的字节
+    ///
+    /// 读取只检查格式，贡献是否有效需调用 [`Ceremony::verify`]。
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != TRANSCRIPT_MAGIC {
+            return Err(invalid_data("invalid ceremony transcript magic"));
+        }
+        if read_array::<_, 1>(&mut reader)? != [TRANSCRIPT_VERSION] {
+            return Err(invalid_data("unsupported ceremony transcript version"));
+        }
+
+        let circuit_id = read_u32(&mut reader)?;
+        let shape_len = read_u32(&mut reader)? as usize;
+        if shape_len > MAX_SHAPE_LEN {
+            return Err(invalid_data("circuit shape is too long"));
+        }
+        let mut shape = vec![0u8; shape_len];
+        reader.read_exact(&mut shape)?;
+        let shape = String::from_utf8(shape).map_err(|_| invalid_data("circuit shape is not UTF-8"))?;
+
+        let degree = read_u32(&mut reader)? as usize;
+        if degree < 2 || !degree.is_power_of_two() || degree > 1 << MAX_DEGREE_LOG {
+            return Err(invalid_data("invalid ceremony size"));
+        }
+        let accumulator = Accumulator::read(&mut reader, degree)?;
+
+        // 记录数量来自不可信输入，逐条读取而不预先分配
+        let mut phase1 = Vec::new();
+        for _ in 0..read_u32(&mut reader)? {
+            phase1.push(Phase1Contribution::read(&mut reader)?);
+        }
+
+        let phase2 = match read_array::<_, 1>(&mut reader)? {
+            [0] => None,
+            [1] => {
+                let params = Parameters::<Bls12>::read(&mut reader, true)?;
+                let mut contributions = Vec::new();
+                for _ in 0..read_u32(&mut reader)? {
+                    contributions.push(Phase2Contribution::read(&mut reader)?);
+                }
+                Some(Phase2 { params, contributions })
+            }
+            _ => return Err(invalid_data("invalid ceremony phase flag")),
+        };
+
+        if reader.read(&mut [0u8; 1])? != 0 {
+            return Err(invalid_data("trailing bytes after ceremony transcript"));
+        }
+
+        Ok(Self {
+            circuit_id,
+            shape,
+            accumulator,
+            phase1,
+            phase2,
+        })
+    }
+
+    // 私有辅助方法
+
+    /// 检查电路与转录绑定的电路一致，返回其 QAP
+    fn bind_circuit(&self, circuit: &NetworkMetricCircuit) -> Result<Qap> {
+        let instance = CircuitInstance::from_circuit(circuit, None)?;
+        if circuit.circuit_id != self.circuit_id
+            || circuit.proof_system != ProofSystemKind::Groth16
+            || instance.shape() != self.shape
+        {
+            return Err(ZKProofError::CryptographicError(format!(
+                "ceremony is for circuit {} ({}), not circuit {} ({})",
+                self.circuit_id,
+                self.shape,
+                circuit.circuit_id,
+                instance.shape()
+            ))
+            .into());
+        }
+
+        let qap = Qap::synthesize(instance)?;
+        if qap.degree() != self.accumulator.degree() {
+            return Err(ZKProofError::CryptographicError(format!(
+                "circuit {} needs ceremony size {}, transcript has {}",
+                circuit.circuit_id,
+                qap.degree(),
+                self.accumulator.degree()
+            ))
+            .into());
+        }
+        Ok(qap)
+    }
+
+    /// 初始转录哈希，绑定电路ID、形状与累加器规模
+    fn initial_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(TRANSCRIPT_DOMAIN);
+        hasher.update(self.circuit_id.to_le_bytes());
+        hasher.update((self.shape.len() as u32).to_le_bytes());
+        hasher.update(self.shape.as_bytes());
+        hasher.update((self.accumulator.degree() as u32).to_le_bytes());
+        hasher.finalize().into()
+    }
+
+    /// 当前转录哈希，下一份贡献的知识证明绑定到该哈希
+    fn transcript_hash(&self) -> [u8; 32] {
+        let hash = self
+            .phase1
+            .iter()
+            .fold(self.initial_hash(), |hash, c| chain_hash(&hash, &c.to_bytes()));
+        match &self.phase2 {
+            Some(phase2) => phase2
+                .contributions
+                .iter()
+                .fold(phase2_start(hash), |hash, c| chain_hash(&hash, &c.to_bytes())),
+            None => hash,
+        }
+    }
+
+    /// 验证第一阶段的贡献链与累加器，返回各贡献的哈希
+    fn verify_phase1(&self) -> Result<Vec<[u8; 32]>> {
+        let mut hashes = Vec::with_capacity(self.phase1.len());
+        let mut hash = self.initial_hash();
+        let mut head = Phase1Head::generators();
+        for (i, contribution) in self.phase1.iter().enumerate() {
+            if !contribution.verify(&head, &hash) {
+                return Err(transcript_error(format!("phase 1 contribution {} is invalid", i + 1)));
+            }
+            head = contribution.head;
+            hash = chain_hash(&hash, &contribution.to_bytes());
+            hashes.push(hash);
+        }
+
+        if self.accumulator.head() != head {
+            return Err(transcript_error("accumulator does not match the last phase 1 contribution"));
+        }
+        if !self.accumulator.is_well_formed() {
+            return Err(transcript_error("accumulator is not a consistent set of powers"));
+        }
+        Ok(hashes)
+    }
+}
+
+/// 对随机因子 x 的知识证明：s 为随机 G1 点，r 由转录哈希与 (s, s·x) 派生到 G2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KnowledgeProof {
+    s: G1Affine,
+    s_x: G1Affine,
+    r_x: G2Affine,
+}
+
+impl KnowledgeProof {
+    /// 编码长度
+    const LEN: usize = 96 + 96 + 192;
+
+    fn new(x: Scalar, transcript: &[u8; 32], tag: &[u8], rng: &mut impl RngCore) -> Self {
+        let s = G1Projective::random(&mut *rng).to_affine();
+        let s_x = (s * x).to_affine();
+        let r = challenge(transcript, tag, &s, &s_x);
+        Self {
+            s,
+            s_x,
+            r_x: (r * x).to_affine(),
+        }
+    }
+
+    /// 检查证明并返回 (r, r·x)，调用方以此检查状态更新乘以的正是 x
+    fn verify(&self, transcript: &[u8; 32], tag: &[u8]) -> Option<(G2Affine, G2Affine)> {
+        if bool::from(self.s.is_identity() | self.s_x.is_identity() | self.r_x.is_identity()) {
+            return None;
+        }
+        let r = challenge(transcript, tag, &self.s, &self.s_x);
+        same_ratio((self.s, self.s_x), (r, self.r_x)).then_some((r, self.r_x))
+    }
+
+    fn write(&self, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&self.s.to_uncompressed());
+        bytes.extend_from_slice(&self.s_x.to_uncompressed());
+        bytes.extend_from_slice(&self.r_x.to_uncompressed());
+    }
+
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            s: read_point(reader)?,
+            s_x: read_point(reader)?,
+            r_x: read_point(reader)?,
+        })
+    }
+}
+
+/// 累加器的首项：[τ]₁、[τ]₂、[α]₁、[β]₁、[β]₂
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Phase1Head {
+    tau_g1: G1Affine,
+    tau_g2: G2Affine,
+    alpha_g1: G1Affine,
+    beta_g1: G1Affine,
+    beta_g2: G2Affine,
+}
+
+impl Phase1Head {
+    /// τ = α = β = 1 时的首项
+    fn generators() -> Self {
+        Self {
+            tau_g1: G1Affine::generator(),
+            tau_g2: G2Affine::generator(),
+            alpha_g1: G1Affine::generator(),
+            beta_g1: G1Affine::generator(),
+            beta_g2: G2Affine::generator(),
+        }
+    }
+}
+
+/// 第一阶段的一份贡献：贡献后的首项与三个因子的知识证明
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Phase1Contribution {
+    head: Phase1Head,
+    tau_proof: KnowledgeProof,
+    alpha_proof: KnowledgeProof,
+    beta_proof: KnowledgeProof,
+}
+
+impl Phase1Contribution {
+    /// 检查贡献是在前一首项上乘以贡献者已知的 τ、α、β 因子
+    fn verify(&self, previous: &Phase1Head, transcript: &[u8; 32]) -> bool {
+        let head = &self.head;
+        let (Some(tau), Some(alpha), Some(beta)) = (
+            self.tau_proof.verify(transcript, b"tau"),
+            self.alpha_proof.verify(transcript, b"alpha"),
+            self.beta_proof.verify(transcript, b"beta"),
+        ) else {
+            return false;
+        };
+        let has_identity = head.tau_g1.is_identity()
+            | head.tau_g2.is_identity()
+            | head.alpha_g1.is_identity()
+            | head.beta_g1.is_identity()
+            | head.beta_g2.is_identity();
+
+        !bool::from(has_identity)
+            && same_ratio((previous.tau_g1, head.tau_g1), tau)
+            && same_ratio((previous.alpha_g1, head.alpha_g1), alpha)
+            && same_ratio((previous.beta_g1, head.beta_g1), beta)
+            && same_ratio((G1Affine::generator(), head.tau_g1), (G2Affine::generator(), head.tau_g2))
+            && same_ratio((G1Affine::generator(), head.beta_g1), (G2Affine::generator(), head.beta_g2))
+    }
+
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(96 * 3 + 192 * 2 + KnowledgeProof::LEN * 3);
+        bytes.extend_from_slice(&self.head.tau_g1.to_uncompressed());
+        bytes.extend_from_slice(&self.head.tau_g2.to_uncompressed());
+        bytes.extend_from_slice(&self.head.alpha_g1.to_uncompressed());
+        bytes.extend_from_slice(&self.head.beta_g1.to_uncompressed());
+        bytes.extend_from_slice(&self.head.beta_g2.to_uncompressed());
+        self.tau_proof.write(&mut bytes);
+        self.alpha_proof.write(&mut bytes);
+        self.beta_proof.write(&mut bytes);
+        bytes
+    }
+
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            head: Phase1Head {
+                tau_g1: read_point(reader)?,
+                tau_g2: read_point(reader)?,
+                alpha_g1: read_point(reader)?,
+                beta_g1: read_point(reader)?,
+                beta_g2: read_point(reader)?,
+            },
+            tau_proof: KnowledgeProof::read(reader)?,
+            alpha_proof: KnowledgeProof::read(reader)?,
+            beta_proof: KnowledgeProof::read(reader)?,
+        })
+    }
+}
+
+/// 第一阶段累加器
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Accumulator {
+    /// [τ^i]₁，i < 2m - 1
+    tau_g1: Vec<G1Affine>,
+    /// [τ^i]₂，i < m
+    tau_g2: Vec<G2Affine>,
+    /// [ατ^i]₁，i < m
+    alpha_tau_g1: Vec<G1Affine>,
+    /// [βτ^i]₁，i < m
+    beta_tau_g1: Vec<G1Affine>,
+    /// [β]₂
+    beta_g2: G2Affine,
+}
+
+impl Accumulator {
+    /// τ = α = β = 1 的初始累加器
+    fn new(degree: usize) -> Self {
+        Self {
+            tau_g1: vec![G1Affine::generator(); 2 * degree - 1],
+            tau_g2: vec![G2Affine::generator(); degree],
+            alpha_tau_g1: vec![G1Affine::generator(); degree],
+            beta_tau_g1: vec![G1Affine::generator(); degree],
+            beta_g2: G2Affine::generator(),
+        }
+    }
+
+    /// 累加器规模 m
+    fn degree(&self) -> usize {
+        self.tau_g2.len()
+    }
+
+    fn head(&self) -> Phase1Head {
+        Phase1Head {
+            tau_g1: self.tau_g1[1],
+            tau_g2: self.tau_g2[1],
+            alpha_g1: self.alpha_tau_g1[0],
+            beta_g1: self.beta_tau_g1[0],
+            beta_g2: self.beta_g2,
+        }
+    }
+
+    /// 以随机的 τ、α、β 因子更新累加器
+    fn contribute(&mut self, transcript: &[u8; 32], rng: &mut impl RngCore) -> Phase1Contribution {
+        let tau = nonzero_scalar(rng);
+        let alpha = nonzero_scalar(rng);
+        let beta = nonzero_scalar(rng);
+
+        scale_powers(&mut self.tau_g1, tau, Scalar::ONE);
+        scale_powers(&mut self.tau_g2, tau, Scalar::ONE);
+        scale_powers(&mut self.alpha_tau_g1, tau, alpha);
+        scale_powers(&mut self.beta_tau_g1, tau, beta);
+        self.beta_g2 = (self.beta_g2 * beta).to_affine();
+
+        Phase1Contribution {
+            head: self.head(),
+            tau_proof: KnowledgeProof::new(tau, transcript, b"tau", rng),
+            alpha_proof: KnowledgeProof::new(alpha, transcript, b"alpha", rng),
+            beta_proof: KnowledgeProof::new(beta, transcript, b"beta", rng),
+        }
+    }
+
+    /// 检查各序列是同一 τ 的连续幂：以随机系数合并相邻项后做一次比值检查
+    fn is_well_formed(&self) -> bool {
+        let m = self.degree();
+        if self.tau_g1.len() != 2 * m - 1 || self.alpha_tau_g1.len() != m || self.beta_tau_g1.len() != m {
+            return false;
+        }
+        if self.tau_g1[0] != G1Affine::generator() || self.tau_g2[0] != G2Affine::generator() {
+            return false;
+        }
+
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        let tau_g2 = self.tau_g2[1];
+        same_ratio(merge_consecutive(&self.tau_g1), (g2, tau_g2))
+            && same_ratio((g1, self.tau_g1[1]), merge_consecutive(&self.tau_g2))
+            && same_ratio(merge_consecutive(&self.alpha_tau_g1), (g2, tau_g2))
+            && same_ratio(merge_consecutive(&self.beta_tau_g1), (g2, tau_g2))
+    }
+
+    /// 由累加器与 QAP 计算 γ = δ = 1 的参数，与 bellman 的参数生成过程逐项一致
+    ///
+    /// 需要在指数上做四次逆 FFT，只在进入第二阶段时执行一次；验证时改用随机线性组合检查。
+    fn parameters(&self, qap: &Qap) -> Result<Parameters<Bls12>> {
+        let m = self.degree();
+        let worker = Worker::new();
+
+        // 在指数上做逆 FFT，得到拉格朗日基 [L_j(τ)]
+        let lagrange_g1 = lagrange_basis(&self.tau_g1[..m], &worker)?;
+        let lagrange_g2 = lagrange_basis(&self.tau_g2, &worker)?;
+        let alpha_lagrange = lagrange_basis(&self.alpha_tau_g1, &worker)?;
+        let beta_lagrange = lagrange_basis(&self.beta_tau_g1, &worker)?;
+
+        // H 查询：τ^i · t(τ) = τ^(i+m) - τ^i，i < m - 1
+        let h: Vec<G1Projective> = (0..m - 1)
+            .map(|i| G1Projective::from(self.tau_g1[i + m]) - self.tau_g1[i])
+            .collect();
+
+        // 每个变量的 A、B 多项式与 β·u + α·v + w
+        fn evaluate<G: Group<Scalar = Scalar>>(basis: &[G], terms: &[(Scalar, usize)]) -> G {
+            terms.iter().fold(G::identity(), |acc, (coeff, j)| acc + mul_coeff(basis[*j], coeff))
+        }
+        let identity: Query = (G1Projective::identity(), G1Projective::identity(), G2Projective::identity(), G1Projective::identity());
+        let mut queries = vec![identity; qap.at.len()];
+        parallel_chunks(&mut queries, |offset, chunk| {
+            for (k, query) in chunk.iter_mut().enumerate() {
+                let (at, bt, ct) = (&qap.at[offset + k], &qap.bt[offset + k], &qap.ct[offset + k]);
+                *query = (
+                    evaluate(&lagrange_g1, at),
+                    evaluate(&lagrange_g1, bt),
+                    evaluate(&lagrange_g2, bt),
+                    evaluate(&beta_lagrange, at) + evaluate(&alpha_lagrange, bt) + evaluate(&lagrange_g1, ct),
+                );
+            }
+        });
+
+        let normalize_g1 = |points: Vec<G1Projective>| {
+            let mut affine = vec![G1Affine::identity(); points.len()];
+            G1Projective::batch_normalize(&points, &mut affine);
+            affine
+        };
+        // 与 bellman 一样省略零多项式对应的 A、B 查询项；L 查询不允许出现未约束的变量
+        let (a_mask, b_mask) = (nonzero_polynomials(&qap.at), nonzero_polynomials(&qap.bt));
+        let select = |mask: &[bool], point: fn(&Query) -> G1Projective| {
+            normalize_g1(queries.iter().zip(mask).filter(|(_, keep)| **keep).map(|(q, _)| point(q)).collect())
+        };
+        let a = select(&a_mask, |q| q.0);
+        let b_g1 = select(&b_mask, |q| q.1);
+        let b_g2_projective: Vec<G2Projective> =
+            queries.iter().zip(&b_mask).filter(|(_, keep)| **keep).map(|(q, _)| q.2).collect();
+        let mut b_g2 = vec![G2Affine::identity(); b_g2_projective.len()];
+        G2Projective::batch_normalize(&b_g2_projective, &mut b_g2);
+        let mut ic = normalize_g1(queries.iter().map(|q| q.3).collect());
+        let l = ic.split_off(qap.num_inputs);
+
+        let degenerate = a.iter().chain(&b_g1).chain(&ic).chain(&l).any(|p| bool::from(p.is_identity()))
+            || b_g2.iter().any(|p| bool::from(p.is_identity()));
+        if degenerate {
+            return Err(ZKProofError::CryptographicError(SynthesisError::UnconstrainedVariable.to_string()).into());
+        }
+
+        Ok(Parameters {
+            vk: VerifyingKey {
+                alpha_g1: self.alpha_tau_g1[0],
+                beta_g1: self.beta_tau_g1[0],
+                beta_g2: self.beta_g2,
+                gamma_g2: G2Affine::generator(),
+                delta_g1: G1Affine::generator(),
+                delta_g2: G2Affine::generator(),
+                ic,
+            },
+            h: Arc::new(normalize_g1(h)),
+            l: Arc::new(l),
+            a: Arc::new(a),
+            b_g1: Arc::new(b_g1),
+            b_g2: Arc::new(b_g2),
+        })
+    }
+
+    fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for point in self.tau_g1.iter().chain(&self.alpha_tau_g1).chain(&self.beta_tau_g1) {
+            writer.write_all(&point.to_uncompressed())?;
+        }
+        for point in &self.tau_g2 {
+            writer.write_all(&point.to_uncompressed())?;
+        }
+        writer.write_all(&self.beta_g2.to_uncompressed())
+    }
+
+    fn read<R: Read>(reader: &mut R, degree: usize) -> io::Result<Self> {
+        Ok(Self {
+            tau_g1: read_points(reader, 2 * degree - 1)?,
+            alpha_tau_g1: read_points(reader, degree)?,
+            beta_tau_g1: read_points(reader, degree)?,
+            tau_g2: read_points(reader, degree)?,
+            beta_g2: read_point(reader)?,
+        })
+    }
+}
+
+/// 第二阶段的一份贡献：贡献后的 [δ]₁ 与因子的知识证明
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Phase2Contribution {
+    delta_g1: G1Affine,
+    proof: KnowledgeProof,
+}
+
+impl Phase2Contribution {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(96 + KnowledgeProof::LEN);
+        bytes.extend_from_slice(&self.delta_g1.to_uncompressed());
+        self.proof.write(&mut bytes);
+        bytes
+    }
+
+    fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(Self {
+            delta_g1: read_point(reader)?,
+            proof: KnowledgeProof::read(reader)?,
+        })
+    }
+}
+
+/// 第二阶段：当前参数与贡献记录
+#[derive(Clone, PartialEq)]
+struct Phase2 {
+    params: Parameters<Bls12>,
+    contributions: Vec<Phase2Contribution>,
+}
+
+impl Phase2 {
+    /// 以随机的 δ 因子 d 更新参数：δ 乘以 d，H、L 查询乘以 d⁻¹
+    fn contribute(&mut self, transcript: &[u8; 32], rng: &mut impl RngCore) -> Phase2Contribution {
+        let delta = nonzero_scalar(rng);
+        let delta_inverse = delta.invert().unwrap();
+
+        let vk = &mut self.params.vk;
+        vk.delta_g1 = (vk.delta_g1 * delta).to_affine();
+        vk.delta_g2 = (vk.delta_g2 * delta).to_affine();
+        scale_powers(Arc::make_mut(&mut self.params.h).as_mut_slice(), Scalar::ONE, delta_inverse);
+        scale_powers(Arc::make_mut(&mut self.params.l).as_mut_slice(), Scalar::ONE, delta_inverse);
+
+        Phase2Contribution {
+            delta_g1: vk.delta_g1,
+            proof: KnowledgeProof::new(delta, transcript, b"delta", rng),
+        }
+    }
+
+    /// 验证 δ 的贡献链，并以随机线性组合检查参数与累加器、电路的 QAP 一致，返回各贡献的哈希
+    fn verify(&self, accumulator: &Accumulator, qap: &Qap, start: [u8; 32]) -> Result<Vec<[u8; 32]>> {
+        let mut hashes = Vec::with_capacity(self.contributions.len());
+        let mut hash = start;
+        let mut delta = G1Affine::generator();
+        for (i, contribution) in self.contributions.iter().enumerate() {
+            let valid = contribution.proof.verify(&hash, b"delta").is_some_and(|ratio| {
+                !bool::from(contribution.delta_g1.is_identity()) && same_ratio((delta, contribution.delta_g1), ratio)
+            });
+            if !valid {
+                return Err(transcript_error(format!("phase 2 contribution {} is invalid", i + 1)));
+            }
+            delta = contribution.delta_g1;
+            hash = chain_hash(&hash, &contribution.to_bytes());
+            hashes.push(hash);
+        }
+
+        let (params, vk) = (&self.params, &self.params.vk);
+        let g1 = G1Affine::generator();
+        let g2 = G2Affine::generator();
+        if vk.delta_g1 != delta || !same_ratio((g1, vk.delta_g1), (g2, vk.delta_g2)) {
+            return Err(transcript_error("parameters do not match the last phase 2 contribution"));
+        }
+
+        let m = accumulator.degree();
+        let (a_mask, b_mask) = (nonzero_polynomials(&qap.at), nonzero_polynomials(&qap.bt));
+        let count = |mask: &[bool]| mask.iter().filter(|keep| **keep).count();
+        let well_sized = vk.alpha_g1 == accumulator.alpha_tau_g1[0]
+            && vk.beta_g1 == accumulator.beta_tau_g1[0]
+            && vk.beta_g2 == accumulator.beta_g2
+            && vk.gamma_g2 == g2
+            && vk.ic.len() == qap.num_inputs
+            && params.l.len() == qap.at.len() - qap.num_inputs
+            && params.a.len() == count(&a_mask)
+            && params.b_g1.len() == count(&b_mask)
+            && params.b_g2.len() == count(&b_mask)
+            && params.h.len() == m - 1;
+        if !well_sized {
+            return Err(transcript_error("parameters do not match the circuit and accumulator"));
+        }
+
+        // Σ r_k·u_k(τ) 由 τ 的幂直接计算：先在标量域上把按约束展开的系数做逆 FFT
+        let worker = Worker::new();
+        let tau_g1 = &accumulator.tau_g1[..m];
+        let monomial = |column: &[Vec<(Scalar, usize)>], coeffs: &[Scalar]| {
+            monomial_coefficients(combine_rows(column, coeffs, m), &worker)
+        };
+        // β·u + α·v + w
+        let combined_query = |range: std::ops::Range<usize>, coeffs: &[Scalar]| -> Result<G1Projective> {
+            Ok(linear_combination(&accumulator.beta_tau_g1, &monomial(&qap.at[range.clone()], coeffs)?)
+                + linear_combination(&accumulator.alpha_tau_g1, &monomial(&qap.bt[range.clone()], coeffs)?)
+                + linear_combination(tau_g1, &monomial(&qap.ct[range], coeffs)?))
+        };
+
+        let (a_coeffs, a_selected) = random_coefficients(&a_mask);
+        let a_valid = linear_combination(&params.a, &a_selected) == linear_combination(tau_g1, &monomial(&qap.at, &a_coeffs)?);
+
+        let (b_coeffs, b_selected) = random_coefficients(&b_mask);
+        let b_monomial = monomial(&qap.bt, &b_coeffs)?;
+        let b_valid = linear_combination(&params.b_g1, &b_selected) == linear_combination(tau_g1, &b_monomial)
+            && linear_combination(&params.b_g2, &b_selected) == linear_combination(&accumulator.tau_g2, &b_monomial);
+
+        let (ic_coeffs, _) = random_coefficients(&vec![true; qap.num_inputs]);
+        let ic_valid = linear_combination(&vk.ic, &ic_coeffs) == combined_query(0..qap.num_inputs, &ic_coeffs)?;
+        if !(a_valid && b_valid && ic_valid) {
+            return Err(transcript_error("parameters were modified outside of delta contributions"));
+        }
+
+        // L 与 H 查询必须整体除以同一个 δ：L·δ = β·u + α·v + w，H_i·δ = τ^(i+m) - τ^i
+        let (l_coeffs, _) = random_coefficients(&vec![true; params.l.len()]);
+        let l_initial = combined_query(qap.num_inputs..qap.at.len(), &l_coeffs)?;
+        let (h_coeffs, _) = random_coefficients(&vec![true; m - 1]);
+        let mut h_powers = vec![Scalar::ZERO; 2 * m - 1];
+        for (i, r) in h_coeffs.iter().enumerate() {
+            h_powers[i] -= r;
+            h_powers[i + m] += r;
+        }
+        let h_initial = linear_combination(&accumulator.tau_g1, &h_powers);
+
+        let consistent = same_ratio(
+            (linear_combination(&params.l, &l_coeffs).to_affine(), l_initial.to_affine()),
+            (g2, vk.delta_g2),
+        ) && same_ratio(
+            (linear_combination(&params.h, &h_coeffs).to_affine(), h_initial.to_affine()),
+            (g2, vk.delta_g2),
+        );
+        if !consistent {
+            return Err(transcript_error("H and L queries are not consistent with delta"));
+        }
+        Ok(hashes)
+    }
+}
+
+/// 电路的 QAP：每个变量（输入在前）在 A、B、C 中的 (系数, 约束序号)
+struct Qap {
+    num_inputs: usize,
+    num_constraints: usize,
+    at: Column,
+    bt: Column,
+    ct: Column,
+}
+
+impl Qap {
+    /// 合成电路，与 bellman 一样先分配常量 1 并在末尾为每个输入追加 x·0 = 0 约束
+    fn synthesize<C: Circuit<Scalar>>(circuit: C) -> Result<Self, SynthesisError> {
+        let mut assembly = QapAssembly::default();
+        assembly.alloc_input(|| "one", || Ok(Scalar::ONE))?;
+        circuit.synthesize(&mut assembly)?;
+        for i in 0..assembly.inputs.0.len() {
+            assembly.enforce(
+                || "input",
+                |lc| lc + Variable::new_unchecked(Index::Input(i)),
+                |lc| lc,
+                |lc| lc,
+            );
+        }
+
+        let QapAssembly { inputs, aux, num_constraints } = assembly;
+        let num_inputs = inputs.0.len();
+        let (at, bt, ct) = (
+            [inputs.0, aux.0].concat(),
+            [inputs.1, aux.1].concat(),
+            [inputs.2, aux.2].concat(),
+        );
+        Ok(Self {
+            num_inputs,
+            num_constraints,
+            at,
+            bt,
+            ct,
+        })
+    }
+
+    /// 求值域大小：约束数向上取整的 2 的幂
+    fn degree(&self) -> usize {
+        self.num_constraints.next_power_of_two()
+    }
+}
+
+/// 每个变量出现的 (系数, 约束序号)
+type Column = Vec<Vec<(Scalar, usize)>>;
+/// 变量在 A、B、C 中的出现位置
+type Terms = (Column, Column, Column);
+/// 变量的 [u(τ)]₁、[v(τ)]₁、[v(τ)]₂ 与 [β·u(τ) + α·v(τ) + w(τ)]₁
+type Query = (G1Projective, G1Projective, G2Projective, G1Projective);
+
+/// 记录约束矩阵的约束系统
+#[derive(Default)]
+struct QapAssembly {
+    inputs: Terms,
+    aux: Terms,
+    num_constraints: usize,
+}
+
+impl QapAssembly {
+    fn record(&mut self, lc: LinearCombination<Scalar>, column: fn(&mut Terms) -> &mut Column) {
+        for (variable, coeff) in lc.as_ref() {
+            let (terms, index) = match variable.get_unchecked() {
+                Index::Input(i) => (&mut self.inputs, i),
+                Index::Aux(i) => (&mut self.aux, i),
+            };
+            column(terms)[index].push((*coeff, self.num_constraints));
+        }
+    }
+}
+
+impl ConstraintSystem<Scalar> for QapAssembly {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.aux.0.len();
+        self.aux.0.push(Vec::new());
+        self.aux.1.push(Vec::new());
+        self.aux.2.push(Vec::new());
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.inputs.0.len();
+        self.inputs.0.push(Vec::new());
+        self.inputs.1.push(Vec::new());
+        self.inputs.2.push(Vec::new());
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LB: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LC: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+    {
+        self.record(a(LinearCombination::zero()), |terms| &mut terms.0);
+        self.record(b(LinearCombination::zero()), |terms| &mut terms.1);
+        self.record(c(LinearCombination::zero()), |terms| &mut terms.2);
+        self.num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self) {}
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// 变量的多项式是否非零：同一约束中的重复项合并后仍有非零系数
+fn nonzero_polynomials(column: &Column) -> Vec<bool> {
+    column
+        .iter()
+        .map(|terms| {
+            terms
+                .chunk_by(|a, b| a.1 == b.1)
+                .any(|group| group.iter().map(|(coeff, _)| *coeff).sum::<Scalar>() != Scalar::ZERO)
+        })
+        .collect()
+}
+
+/// Σ_k coeffs_k · 第 k 个变量的多项式，按约束序号展开为长度 m 的向量
+fn combine_rows(column: &[Vec<(Scalar, usize)>], coeffs: &[Scalar], degree: usize) -> Vec<Scalar> {
+    let mut combined = vec![Scalar::ZERO; degree];
+    for (terms, r) in column.iter().zip(coeffs) {
+        for (coeff, j) in terms {
+            combined[*j] += *coeff * r;
+        }
+    }
+    combined
+}
+
+/// 拉格朗日基下的系数换算为 τ 的幂的系数：Σ y_j·L_j(τ) = Σ c_i·τ^i，c 为 y 的逆 FFT
+fn monomial_coefficients(values: Vec<Scalar>, worker: &Worker) -> Result<Vec<Scalar>, SynthesisError> {
+    let mut domain = EvaluationDomain::from_coeffs(values.into_iter().map(DomainScalar).collect())?;
+    domain.ifft(worker);
+    Ok(domain.into_coeffs().into_iter().map(|value| value.0).collect())
+}
+
+/// 为 mask 中选中的位置抽取随机系数，返回按全部位置展开的系数与仅含选中位置的系数
+fn random_coefficients(mask: &[bool]) -> (Vec<Scalar>, Vec<Scalar>) {
+    let coeffs: Vec<Scalar> = mask
+        .iter()
+        .map(|selected| if *selected { Scalar::random(OsRng) } else { Scalar::ZERO })
+        .collect();
+    let selected = coeffs.iter().zip(mask).filter(|(_, keep)| **keep).map(|(c, _)| *c).collect();
+    (coeffs, selected)
+}
+
+/// point · coeff：电路系数多为 ±2^k 等较小的整数，此时以加倍-加法代替完整的标量乘法
+fn mul_coeff<G: Group<Scalar = Scalar>>(point: G, coeff: &Scalar) -> G {
+    for (value, negate) in [(*coeff, false), (-coeff, true)] {
+        let bytes = value.to_bytes();
+        if bytes[16..].iter().all(|byte| *byte == 0) {
+            let small = u128::from_le_bytes(bytes[..16].try_into().expect("16 bytes"));
+            let mut acc = G::identity();
+            for bit in (0..u128::BITS - small.leading_zeros()).rev() {
+                acc = acc.double();
+                if (small >> bit) & 1 == 1 {
+                    acc += point;
+                }
+            }
+            return if negate { -acc } else { acc };
+        }
+    }
+    point * coeff
+}
+
+/// 由系统随机数与贡献者提供的熵派生随机数生成器
+fn contribution_rng(entropy: &[u8]) -> ChaCha20Rng {
+    let mut system = [0u8; 32];
+    OsRng.fill_bytes(&mut system);
+    let mut hasher = Sha256::new();
+    hasher.update(system);
+    hasher.update(entropy);
+    ChaCha20Rng::from_seed(hasher.finalize().into())
+}
+
+fn nonzero_scalar(rng: &mut impl RngCore) -> Scalar {
+    loop {
+        let scalar = Scalar::random(&mut *rng);
+        if !bool::from(scalar.is_zero()) {
+            return scalar;
+        }
+    }
+}
+
+/// 转录哈希链：SHA-256(前一哈希 ‖ 贡献记录)
+fn chain_hash(previous: &[u8; 32], record: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(previous);
+    hasher.update(record);
+    hasher.finalize().into()
+}
+
+/// 第二阶段的起始哈希
+fn phase2_start(phase1_hash: [u8; 32]) -> [u8; 32] {
+    chain_hash(&phase1_hash, b"phase2")
+}
+
+/// 由转录派生离散对数未知的 G2 点
+fn challenge(transcript: &[u8; 32], tag: &[u8], s: &G1Affine, s_x: &G1Affine) -> G2Affine {
+    let mut hasher = Sha256::new();
+    hasher.update(transcript);
+    hasher.update(tag);
+    hasher.update(s.to_compressed());
+    hasher.update(s_x.to_compressed());
+    G2Projective::random(ChaCha20Rng::from_seed(hasher.finalize().into())).to_affine()
+}
+
+/// 检查 G1 点对与 G2 点对的离散对数之比相同：e(a₁, b₂) = e(b₁, a₂)
+fn same_ratio(g1: (G1Affine, G1Affine), g2: (G2Affine, G2Affine)) -> bool {
+    multi_miller_loop(&[(&g1.0, &G2Prepared::from(g2.1)), (&-g1.1, &G2Prepared::from(g2.0))])
+        .final_exponentiation()
+        == Gt::identity()
+}
+
+/// 以随机系数合并相邻项：(Σ rᵢ·pᵢ, Σ rᵢ·pᵢ₊₁)
+fn merge_consecutive<C: PrimeCurveAffine<Scalar = Scalar>>(points: &[C]) -> (C, C) {
+    merge_pairs(&points[..points.len() - 1], &points[1..])
+}
+
+/// 以相同的随机系数合并两组等长的点：(Σ rᵢ·aᵢ, Σ rᵢ·bᵢ)
+fn merge_pairs<C: PrimeCurveAffine<Scalar = Scalar>>(a: &[C], b: &[C]) -> (C, C) {
+    let coeffs: Vec<Scalar> = (0..a.len()).map(|_| Scalar::random(OsRng)).collect();
+    (
+        linear_combination(a, &coeffs).to_affine(),
+        linear_combination(b, &coeffs).to_affine(),
+    )
+}
+
+/// 以多标量乘法计算 Σ coeffsᵢ·pointsᵢ
+fn linear_combination<C: PrimeCurveAffine<Scalar = Scalar>>(points: &[C], coeffs: &[Scalar]) -> C::Curve {
+    let exponents = Arc::new(coeffs.iter().map(Exponent::from).collect());
+    multiexp::<FullDensity, _, C::Curve, _>(&Worker::new(), (Arc::new(points.to_vec()), 0), FullDensity, exponents)
+        .wait()
+        .expect("bases and coefficients have the same length")
+}
+
+/// pointsᵢ ← pointsᵢ · factor · baseⁱ
+fn scale_powers<C: PrimeCurveAffine<Scalar = Scalar>>(points: &mut [C], base: Scalar, factor: Scalar) {
+    parallel_chunks(points, |offset, chunk| {
+        let mut coeff = factor * base.pow_vartime(&[offset as u64, 0, 0, 0]);
+        let scaled: Vec<C::Curve> = chunk
+            .iter()
+            .map(|point| {
+                let scaled = *point * coeff;
+                coeff *= base;
+                scaled
+            })
+            .collect();
+        C::Curve::batch_normalize(&scaled, chunk);
+    });
+}
+
+/// 在指数上做逆 FFT，由 [τ^i] 得到拉格朗日基 [L_j(τ)]
+fn lagrange_basis<C>(powers: &[C], worker: &Worker) -> Result<Vec<C::Curve>, SynthesisError>
+where
+    C: PrimeCurveAffine<Scalar = Scalar>,
+    C::Curve: WnafGroup,
+{
+    let mut domain = EvaluationDomain::<Scalar, DomainPoint<C::Curve>>::from_coeffs(
+        powers.iter().map(|point| DomainPoint(point.to_curve())).collect(),
+    )?;
+    domain.ifft(worker);
+    Ok(domain.into_coeffs().into_iter().map(|point| point.0).collect())
+}
+
+/// 供 bellman 求值域使用的群元素（bellman 自带的 Point 要求曲线实现 CofactorCurve）
+#[derive(Clone, Copy)]
+struct DomainPoint<G>(G);
+
+impl<G: WnafGroup<Scalar = Scalar>> DomainGroup<Scalar> for DomainPoint<G> {
+    fn group_zero() -> Self {
+        DomainPoint(G::identity())
+    }
+
+    fn group_mul_assign(&mut self, by: &Scalar) {
+        // FFT 的旋转因子是公开的，可以使用变时的 wNAF 乘法
+        if *by != Scalar::ONE {
+            self.0 = Wnaf::new().scalar(by).base(self.0);
+        }
+    }
+
+    fn group_add_assign(&mut self, other: &Self) {
+        self.0 += other.0;
+    }
+
+    fn group_sub_assign(&mut self, other: &Self) {
+        self.0 -= other.0;
+    }
+}
+
+/// 将切片分块交给各线程处理，回调的第一个参数为块的起始下标
+fn parallel_chunks<T: Send>(items: &mut [T], f: impl Fn(usize, &mut [T]) + Sync) {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk = items.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        for (i, part) in items.chunks_mut(chunk).enumerate() {
+            let f = &f;
+            scope.spawn(move || f(i * chunk, part));
+        }
+    });
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(reader)?))
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0u8; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_point<R: Read, C: UncompressedEncoding>(reader: &mut R) -> io::Result<C> {
+    let mut repr = C::Uncompressed::default();
+    reader.read_exact(repr.as_mut())?;
+    Option::from(C::from_uncompressed(&repr)).ok_or_else(|| invalid_data("invalid curve point"))
+}
+
+/// 读取 count 个非压缩点，并行检查曲线与子群
+fn read_points<R: Read, C: PrimeCurveAffine + UncompressedEncoding>(reader: &mut R, count: usize) -> io::Result<Vec<C>> {
+    let len = C::Uncompressed::default().as_ref().len();
+    // 按实际读到的数据增长缓冲区，不按文件中的规模预先分配
+    let mut bytes = Vec::new();
+    reader.by_ref().take((count * len) as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != count * len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+
+    let mut points = vec![C::identity(); count];
+    let invalid = AtomicBool::new(false);
+    parallel_chunks(&mut points, |offset, chunk| {
+        for (k, point) in chunk.iter_mut().enumerate() {
+            let start = (offset + k) * len;
+            let mut repr = C::Uncompressed::default();
+            repr.as_mut().copy_from_slice(&bytes[start..start + len]);
+            match Option::from(C::from_uncompressed(&repr)) {
+                Some(decoded) => *point = decoded,
+                None => invalid.store(true, Ordering::Relaxed),
+            }
+        }
+    });
+    if invalid.into_inner() {
+        return Err(invalid_data("invalid curve point"));
+    }
+    Ok(points)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn transcript_error(message: impl Into<String>) -> anyhow::Error {
+    ZKProofError::CryptographicError(format!("invalid ceremony transcript: {}", message.into())).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::PercentileWitness;
+    use crate::proof_system::{CircuitWitness, ProofSystem};
+
+    fn circuit() -> NetworkMetricCircuit {
+        NetworkMetricCircuit::percentile(7, 1, "Ceremony test".to_string())
+    }
+
+    fn round_trip(ceremony: &Ceremony) -> Ceremony {
+        let mut bytes = Vec::new();
+        ceremony.write(&mut bytes).unwrap();
+        Ceremony::read(&bytes[..]).unwrap()
+    }
+
+    /// 两个阶段各两份贡献后的仪式
+    fn completed_ceremony() -> Ceremony {
+        let circuit = circuit();
+        let mut ceremony = Ceremony::initialize(&circuit).unwrap();
+        ceremony.contribute(b"alice").unwrap();
+        ceremony = round_trip(&ceremony);
+        ceremony.contribute(b"bob").unwrap();
+        ceremony.begin_phase2(&circuit).unwrap();
+        ceremony.contribute(b"carol").unwrap();
+        ceremony = round_trip(&ceremony);
+        ceremony.contribute(b"dave").unwrap();
+        ceremony
+    }
+
+    #[test]
+    fn test_ceremony_produces_working_parameters() {
+        let circuit = circuit();
+        let ceremony = completed_ceremony();
+        assert_eq!(ceremony.phase(), CeremonyPhase::Circuit);
+        assert!(round_trip(&ceremony) == ceremony);
+
+        let summary = ceremony.verify(&circuit).unwrap();
+        assert_eq!((summary.phase1.len(), summary.phase2.len()), (2, 2));
+        assert_eq!(summary.phase2.last(), Some(&ceremony.transcript_hash()));
+
+        let keys = ceremony.finalize(&circuit).unwrap();
+        let witness = PercentileWitness {
+            samples: vec![42],
+            percentile: 50,
+            blinding: crate::commitment::blinding_scalar(&[1; 32]),
+        };
+        let instance = CircuitInstance::from_circuit(&circuit, Some(CircuitWitness::Percentile(witness))).unwrap();
+        let inputs = instance.public_inputs().unwrap();
+        let system = Groth16ProofSystem;
+        let proof = system.prove(&keys, instance).unwrap();
+        assert!(system.verify(&keys.verification_key, &proof, &inputs).unwrap());
+        let mut wrong = inputs.clone();
+        wrong[0] += 1;
+        assert!(!system.verify(&keys.verification_key, &proof, &wrong).unwrap());
+
+        // 其他电路无法使用该转录，未完成的仪式不能导出参数
+        let other = NetworkMetricCircuit::percentile(7, 2, "Other".to_string());
+        assert!(ceremony.verify(&other).is_err());
+        let mut unfinished = ceremony.clone();
+        unfinished.phase2.as_mut().unwrap().contributions.clear();
+        assert!(unfinished.finalize(&circuit).is_err());
+        assert!(unfinished.begin_phase2(&circuit).is_err());
+    }
+
+    #[test]
+    fn test_tampered_transcripts_are_rejected() {
+        let circuit = circuit();
+        let ceremony = completed_ceremony();
+        let qap = ceremony.bind_circuit(&circuit).unwrap();
+        let accumulator = &ceremony.accumulator;
+        let start = phase2_start(*ceremony.verify_phase1().unwrap().last().unwrap());
+        let phase2 = ceremony.phase2.as_ref().unwrap();
+        assert!(phase2.verify(accumulator, &qap, start).is_ok());
+
+        // 替换 δ 而不附带知识证明
+        let mut forged = phase2.clone();
+        let delta = Scalar::from(5u64);
+        forged.params.vk.delta_g1 = (forged.params.vk.delta_g1 * delta).to_affine();
+        forged.params.vk.delta_g2 = (forged.params.vk.delta_g2 * delta).to_affine();
+        assert!(forged.verify(accumulator, &qap, start).is_err());
+
+        // 只缩放部分 H 查询
+        let mut forged = phase2.clone();
+        let h = Arc::make_mut(&mut forged.params.h);
+        h[0] = (h[0] * Scalar::from(2u64)).to_affine();
+        assert!(forged.verify(accumulator, &qap, start).is_err());
+
+        // 替换公开输入或 A 查询中的点
+        let mut forged = phase2.clone();
+        forged.params.vk.ic[0] = G1Affine::generator();
+        assert!(forged.verify(accumulator, &qap, start).is_err());
+        let mut forged = phase2.clone();
+        Arc::make_mut(&mut forged.params.a).swap(0, 1);
+        assert!(forged.verify(accumulator, &qap, start).is_err());
+
+        // 贡献链必须从第一阶段的最终哈希开始
+        assert!(phase2.verify(accumulator, &qap, ceremony.initial_hash()).is_err());
+
+        // 篡改知识证明或累加器
+        let mut forged = ceremony.clone();
+        forged.phase1[0].tau_proof.r_x = G2Affine::generator();
+        assert!(forged.verify_phase1().is_err());
+        let mut forged = ceremony.clone();
+        forged.accumulator.tau_g1[3] = G1Affine::generator();
+        assert!(forged.verify_phase1().is_err());
+
+        // 末尾多余字节与截断
+        let mut bytes = Vec::new();
+        ceremony.write(&mut bytes).unwrap();
+        assert!(Ceremony::read(&bytes[..bytes.len() - 1]).is_err());
+        bytes.push(0);
+        assert!(Ceremony::read(&bytes[..]).is_err());
+    }
+}
@@ -1,5 +1,57 @@
+use bellman::{Circuit, ConstraintSystem, SynthesisError};
+use bls12_381::Scalar;
+use ff::Field;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::commitment;
+use crate::gadgets::{self, LinearExpr};
+use crate::merkle;
+use crate::nullifier;
+use crate::poseidon;
+use crate::privacy::{self, PrivacyParameters};
+use crate::profiler::CircuitProfiler;
+use crate::proof_system::{ProofSystemKind, Statement};
+use crate::rolling::{self, RollingState, WINDOW_COUNT_SHIFT};
+
+/// 单个样本值的比特上限（样本必须小于 2^64）
+pub const SAMPLE_BITS: usize = 64;
+/// 计数类变量（样本数、数据源数、余数）的比特上限
+pub const COUNT_BITS: usize = 16;
+/// 评分类变量（可靠性、质量评分）的比特上限
+pub const SCORE_BITS: usize = 8;
+/// 聚合值与公开指标之间差值的比特上限
+const SLACK_BITS: usize = 72;
+/// 质量评分允许超出平均可靠性的幅度
+const QUALITY_MARGIN: u64 = 10;
+/// 聚合误差容忍度的分母（1/20 即 5%）
+const TOLERANCE_DIVISOR: u64 = 20;
+/// 评分类变量的最大取值
+const MAX_SCORE: u128 = 100;
+/// 时间窗口的最大小时数
+const MAX_TIME_WINDOW_HOURS: u128 = 24;
+/// Unix 时间戳的比特上限
+const TIMESTAMP_BITS: usize = 64;
+/// 每小时的秒数
+const SECONDS_PER_HOUR: u128 = 3600;
+/// 数据源时间戳距窗口终点间隔的比特上限（窗口最长 24 小时，即 86400 秒 < 2^17）
+const STALENESS_BITS: usize = 17;
+/// Merkle 根高位分量的比特上限（标量域元素小于 2^255）
+const ROOT_HIGH_BITS: usize = 127;
+/// Merkle 树深度上限
+pub const MAX_TREE_DEPTH: usize = 32;
+/// 方差电路中单个样本的比特上限（平方和的中间量须能表示为 u128）
+pub const VARIANCE_SAMPLE_BITS: usize = 32;
+/// 百分位数的最大取值
+const MAX_PERCENTILE: u128 = 100;
+/// 加噪求和结果的比特上限（样本和加上单侧噪声上限）
+const NOISY_SUM_BITS: usize = SAMPLE_BITS + COUNT_BITS + 1;
+/// 区块序号偏移与心跳时隙序号的比特上限
+const INDEX_BITS: usize = 32;
+/// 滚动聚合中全部窗口样本总数的比特上限
+const ROLLING_COUNT_BITS: usize = 32;
 
 /// 电路类型枚举
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -8,14 +60,37 @@ pub enum CircuitType {
     NetworkMetric,
     /// 数据完整性电路
     DataIntegrity,
-    /// 聚合证明电路
+    /// 聚合证明电路（尚未实现，无法为其生成参数或证明）
     AggregationProof,
     /// 隐私保护电路
     PrivacyPreserving,
+    /// 百分位数电路
+    Percentile,
+    /// 方差电路
+    Variance,
+    /// 共识参与电路
+    ConsensusParticipation,
+    /// 节点可靠性（在线率）电路
+    NodeReliability,
+    /// 滚动聚合电路
+    RollingAggregation,
+}
+
+/// 电路公开输入的暴露方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum PublicInputMode {
+    /// 每个公开输入都是电路的公开输入，验证开销随输入个数增长
+    #[default]
+    Full,
+    /// 公开输入在电路内作为私有见证，电路只公开它们的 Poseidon 摘要（一个域元素），
+    /// 摘要见 [`crate::utils::public_input_digest`]
+    Digest,
 }
 
-/// 简化的网络指标电路
-/// 在实际应用中，这里应该使用bellman库实现复杂的零知识证明电路
+/// 电路描述
+///
+/// 描述电路的容量与用途，实际的约束由 [`MetricAggregationCircuit`]、
+/// [`DataIntegrityCircuit`] 等电路按电路类型生成。
 #[derive(Debug, Clone)]
 pub struct NetworkMetricCircuit {
     /// 电路ID
@@ -26,8 +101,18 @@ pub struct NetworkMetricCircuit {
     pub max_data_points: usize,
     /// 支持的最大数据源数量
     pub max_data_sources: usize,
+    /// Merkle 树深度（仅数据完整性电路使用）
+    pub tree_depth: usize,
+    /// 差分隐私参数（仅隐私保护电路使用）
+    pub privacy: Option<PrivacyParameters>,
+    /// 滚动窗口数量（仅滚动聚合电路使用）
+    pub rolling_windows: usize,
     /// 电路描述
     pub description: String,
+    /// 证明系统后端
+    pub proof_system: ProofSystemKind,
+    /// 公开输入的暴露方式
+    pub public_input_mode: PublicInputMode,
 }
 
 impl NetworkMetricCircuit {
@@ -43,11 +128,170 @@ impl NetworkMetricCircuit {
             circuit_type: CircuitType::NetworkMetric,
             max_data_points,
             max_data_sources,
+            tree_depth: 0,
+            privacy: None,
+            rolling_windows: 0,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建数据完整性电路：批次最多 max_batch_size 个数据点，Merkle 树深度为 tree_depth
+    pub fn data_integrity(
+        circuit_id: u32,
+        max_batch_size: usize,
+        tree_depth: usize,
+        description: String,
+    ) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::DataIntegrity,
+            max_data_points: max_batch_size,
+            max_data_sources: 0,
+            tree_depth,
+            privacy: None,
+            rolling_windows: 0,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建百分位数电路：最多 max_samples 个私有样本
+    pub fn percentile(circuit_id: u32, max_samples: usize, description: String) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::Percentile,
+            max_data_points: max_samples,
+            max_data_sources: 0,
+            tree_depth: 0,
+            privacy: None,
+            rolling_windows: 0,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建方差电路：最多 max_samples 个私有样本
+    pub fn variance(circuit_id: u32, max_samples: usize, description: String) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::Variance,
+            max_data_points: max_samples,
+            max_data_sources: 0,
+            tree_depth: 0,
+            privacy: None,
+            rolling_windows: 0,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建共识参与电路：最多证明 max_entries 个出块，出块者树深度为 tree_depth
+    pub fn consensus_participation(
+        circuit_id: u32,
+        max_entries: usize,
+        tree_depth: usize,
+        description: String,
+    ) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::ConsensusParticipation,
+            max_data_points: max_entries,
+            max_data_sources: 0,
+            tree_depth,
+            privacy: None,
+            rolling_windows: 0,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建节点可靠性电路：心跳日志最多 max_heartbeats 条
+    pub fn node_reliability(circuit_id: u32, max_heartbeats: usize, description: String) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::NodeReliability,
+            max_data_points: max_heartbeats,
+            max_data_sources: 0,
+            tree_depth: 0,
+            privacy: None,
+            rolling_windows: 0,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建滚动聚合电路：每个时间窗口最多 max_samples 个新样本，滚动平均覆盖最近 rolling_windows 个窗口
+    pub fn rolling_aggregation(
+        circuit_id: u32,
+        max_samples: usize,
+        rolling_windows: usize,
+        description: String,
+    ) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::RollingAggregation,
+            max_data_points: max_samples,
+            max_data_sources: 0,
+            tree_depth: 0,
+            privacy: None,
+            rolling_windows,
+            description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 创建差分隐私求和电路：最多 max_samples 个私有样本，按 parameters 对样本和加入离散拉普拉斯噪声
+    pub fn privacy_preserving(
+        circuit_id: u32,
+        max_samples: usize,
+        parameters: PrivacyParameters,
+        description: String,
+    ) -> Self {
+        Self {
+            circuit_id,
+            circuit_type: CircuitType::PrivacyPreserving,
+            max_data_points: max_samples,
+            max_data_sources: 0,
+            tree_depth: 0,
+            privacy: Some(parameters),
+            rolling_windows: 0,
             description,
+            proof_system: ProofSystemKind::Groth16,
+            public_input_mode: PublicInputMode::Full,
+        }
+    }
+
+    /// 指定证明系统后端
+    pub fn with_proof_system(mut self, proof_system: ProofSystemKind) -> Self {
+        self.proof_system = proof_system;
+        self
+    }
+
+    /// 指定公开输入的暴露方式；摘要模式的电路结构不同，需要使用新的电路ID注册
+    pub fn with_public_input_mode(mut self, mode: PublicInputMode) -> Self {
+        self.public_input_mode = mode;
+        self
+    }
+
+    /// 证明后端实际验证的公开语句：完整模式下为公开输入本身，摘要模式下为它们的摘要
+    pub fn statement(&self, public_inputs: &[u128]) -> Statement {
+        match self.public_input_mode {
+            PublicInputMode::Full => Statement::Inputs(public_inputs.to_vec()),
+            PublicInputMode::Digest => Statement::Digest(crate::utils::public_input_digest(public_inputs)),
         }
     }
     
     /// 验证电路约束
+    ///
+    /// 与 [`MetricAggregationCircuit`] 中的约束一一对应，用于在生成证明前快速排除无效输入。
     pub fn verify_constraints(
         &self,
         private_data: &[u128],
@@ -56,7 +300,12 @@ impl NetworkMetricCircuit {
         quality_score: u8,
     ) -> bool {
         // 检查数据点数量限制
-        if private_data.len() > self.max_data_points {
+        if private_data.is_empty() || private_data.len() > self.max_data_points {
+            return false;
+        }
+
+        // 检查样本取值范围
+        if private_data.iter().any(|v| *v >> SAMPLE_BITS != 0) {
             return false;
         }
         
@@ -70,39 +319,233 @@ impl NetworkMetricCircuit {
             return false;
         }
         
-        // 验证聚合正确性（简化实现）
-        if !private_data.is_empty() {
-            let calculated_avg = private_data.iter().sum::<u128>() / private_data.len() as u128;
-            // 允许5%的误差范围
-            let tolerance = public_metric / 20; // 5%
-            if calculated_avg.abs_diff(public_metric) > tolerance {
-                return false;
-            }
+        // 检查数据源可靠性范围
+        if data_sources.iter().any(|r| *r > 100) {
+            return false;
+        }
+
+        // 验证聚合正确性
+        let calculated_avg = private_data.iter().sum::<u128>() / private_data.len() as u128;
+        // 允许5%的误差范围
+        let tolerance = public_metric / TOLERANCE_DIVISOR as u128;
+        if calculated_avg.abs_diff(public_metric) > tolerance {
+            return false;
         }
         
-        // 验证质量评分合理性
-        if quality_score > 100 {
+        // 验证公开输入满足输入规范
+        let spec = self.get_public_input_spec();
+        if !spec.inputs[0].is_satisfied_by(public_metric) || !spec.inputs[1].is_satisfied_by(quality_score as u128) {
             return false;
         }
         
         // 验证数据源可靠性影响质量评分
         let avg_reliability = data_sources.iter().sum::<u32>() / data_sources.len() as u32;
-        if quality_score as u32 > avg_reliability + 10 { // 质量评分不应显著超过数据源可靠性
+        if quality_score as u32 > avg_reliability + QUALITY_MARGIN as u32 { // 质量评分不应显著超过数据源可靠性
             return false;
         }
         
         true
     }
+
+    /// 验证数据完整性电路约束
+    ///
+    /// 与 [`DataIntegrityCircuit`] 中的约束一一对应。
+    pub fn verify_integrity_constraints(&self, witness: &IntegrityWitness) -> bool {
+        if self.circuit_type != CircuitType::DataIntegrity {
+            return false;
+        }
+
+        // 检查批次大小与路径深度
+        if witness.batch.is_empty() || witness.batch.len() > self.max_data_points {
+            return false;
+        }
+        if witness.siblings.len() != self.tree_depth || witness.leaf_index >> self.tree_depth != 0 {
+            return false;
+        }
+
+        // 检查时间戳位于公开窗口内
+        if witness.timestamp < witness.window_start || witness.timestamp > witness.window_end {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
+    
+    /// 验证百分位数电路约束
+    ///
+    /// 与 [`PercentileCircuit`] 中的约束一一对应。
+    pub fn verify_percentile_constraints(&self, witness: &PercentileWitness) -> bool {
+        if self.circuit_type != CircuitType::Percentile {
+            return false;
+        }
+
+        if witness.samples.is_empty() || witness.samples.len() > self.max_data_points {
+            return false;
+        }
+        if witness.samples.iter().any(|v| *v >> SAMPLE_BITS != 0) {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
+
+    /// 验证方差电路约束
+    ///
+    /// 与 [`VarianceCircuit`] 中的约束一一对应。
+    pub fn verify_variance_constraints(&self, witness: &VarianceWitness) -> bool {
+        if self.circuit_type != CircuitType::Variance {
+            return false;
+        }
+
+        if witness.samples.is_empty() || witness.samples.len() > self.max_data_points {
+            return false;
+        }
+        if witness.samples.iter().any(|v| *v >> VARIANCE_SAMPLE_BITS != 0) {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
+
+    /// 验证差分隐私求和电路约束
+    ///
+    /// 与 [`PrivacyCircuit`] 中的约束一一对应。
+    pub fn verify_privacy_constraints(&self, witness: &PrivacyWitness) -> bool {
+        if self.circuit_type != CircuitType::PrivacyPreserving || self.privacy != Some(witness.parameters) {
+            return false;
+        }
+        if witness.parameters.validate().is_err() {
+            return false;
+        }
+
+        if witness.samples.is_empty() || witness.samples.len() > self.max_data_points {
+            return false;
+        }
+        if witness.samples.iter().any(|v| *v > witness.parameters.sensitivity as u128) {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
+
+    /// 验证共识参与电路约束
+    ///
+    /// 与 [`ParticipationCircuit`] 中的约束一一对应。
+    pub fn verify_participation_constraints(&self, witness: &ParticipationWitness) -> bool {
+        if self.circuit_type != CircuitType::ConsensusParticipation {
+            return false;
+        }
+
+        let blocks = &witness.signed_blocks;
+        if blocks.is_empty() || blocks.len() > self.max_data_points || witness.siblings.len() != blocks.len() {
+            return false;
+        }
+        // 出块按序排列且位于声明的区块范围内
+        let claim = &witness.claim;
+        let in_range = |block: &u64| {
+            block.checked_sub(claim.first_block).is_some_and(|offset| offset < claim.block_count as u64)
+        };
+        if !blocks.windows(2).all(|pair| pair[0] <= pair[1]) || !blocks.iter().all(in_range) {
+            return false;
+        }
+        // 每个出块都在公开的出块者树中记在该验证者名下
+        let authored = blocks.iter().zip(&witness.siblings).all(|(block, siblings)| {
+            let index = block - claim.first_block;
+            siblings.len() == self.tree_depth
+                && index >> self.tree_depth == 0
+                && merkle::compute_root(merkle::block_author_leaf(*block, &claim.validator), index, siblings)
+                    == witness.author_root
+        });
+        if !authored {
+            return false;
+        }
+        if witness.signed_count() < claim.min_signed as usize {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
+
+    /// 验证节点可靠性电路约束
+    ///
+    /// 与 [`UptimeCircuit`] 中的约束一一对应。
+    pub fn verify_uptime_constraints(&self, witness: &UptimeWitness) -> bool {
+        if self.circuit_type != CircuitType::NodeReliability {
+            return false;
+        }
+
+        let heartbeats = &witness.heartbeats;
+        if heartbeats.is_empty() || heartbeats.len() > self.max_data_points {
+            return false;
+        }
+        // 心跳按时间排列且都落在窗口的某个时隙内
+        let claim = &witness.claim;
+        if !heartbeats.windows(2).all(|pair| pair[0] <= pair[1]) {
+            return false;
+        }
+        if !witness.slots().iter().all(|slot| slot.is_some_and(|slot| slot < claim.slot_count as u64)) {
+            return false;
+        }
+        let covered = witness.covered_slots() as u128 * 100;
+        if covered < claim.min_uptime_percent as u128 * claim.slot_count as u128 {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
+
+    /// 验证滚动聚合电路约束
+    ///
+    /// 与 [`RollingCircuit`] 中的约束一一对应。
+    pub fn verify_rolling_constraints(&self, witness: &RollingWitness) -> bool {
+        if self.circuit_type != CircuitType::RollingAggregation {
+            return false;
+        }
+
+        let samples = &witness.samples;
+        if samples.is_empty() || samples.len() > self.max_data_points {
+            return false;
+        }
+        if samples.iter().any(|v| *v >> SAMPLE_BITS != 0) {
+            return false;
+        }
+        // 上一状态的窗口数量与电路一致，且各窗口取值在打包范围内
+        let previous = &witness.previous;
+        if self.rolling_windows == 0 || previous.windows.len() != self.rolling_windows {
+            return false;
+        }
+        if !previous
+            .windows
+            .iter()
+            .all(|w| fits_in_bits(w.sum, WINDOW_COUNT_SHIFT) && fits_in_bits(w.count as u128, COUNT_BITS))
+        {
+            return false;
+        }
+        if previous.next_window_start.checked_add(previous.window_length()).is_none() {
+            return false;
+        }
+
+        self.get_public_input_spec().is_satisfied_by(&witness.public_inputs())
+    }
     
-    /// 估算证明生成的计算复杂度
+    /// 按公式估算证明生成的计算复杂度
+    ///
+    /// 公式只是粗略近似；电路选择使用 [`crate::profiler::CircuitProfiler`] 由实际合成得到的数值，
+    /// 仅在合成失败时回退到该估算。
     pub fn estimate_complexity(&self, data_points: usize, sources: usize) -> CircuitComplexity {
-        let constraint_count = self.estimate_constraint_count(data_points, sources);
-        let witness_count = self.estimate_witness_count(data_points, sources);
+        let mut constraint_count = self.estimate_constraint_count(data_points, sources);
+        let mut witness_count = self.estimate_witness_count(data_points, sources);
+        // 摘要模式：每个公开输入一次 Poseidon 哈希（约 360 个约束）
+        if self.public_input_mode == PublicInputMode::Digest {
+            let digest_constraints = self.get_public_input_spec().inputs.len() * 360 + 1;
+            constraint_count += digest_constraints;
+            witness_count += digest_constraints;
+        }
         
         CircuitComplexity {
             constraint_count,
             witness_count,
-            estimated_generation_time_ms: constraint_count / 1000 + witness_count / 500,
+            estimated_generation_time_ms: constraint_count.div_ceil(1000) + witness_count.div_ceil(500),
             estimated_verification_time_ms: constraint_count / 5000 + 10,
             memory_usage_mb: (constraint_count + witness_count) / 10000,
         }
@@ -110,6 +553,17 @@ impl NetworkMetricCircuit {
     
     /// 获取电路的公开输入规范
     pub fn get_public_input_spec(&self) -> PublicInputSpec {
+        match self.circuit_type {
+            CircuitType::DataIntegrity => return Self::integrity_input_spec(),
+            CircuitType::Percentile => return Self::percentile_input_spec(),
+            CircuitType::Variance => return Self::variance_input_spec(),
+            CircuitType::PrivacyPreserving => return Self::privacy_input_spec(),
+            CircuitType::ConsensusParticipation => return Self::participation_input_spec(),
+            CircuitType::NodeReliability => return Self::uptime_input_spec(),
+            CircuitType::RollingAggregation => return Self::rolling_input_spec(),
+            _ => {}
+        }
+
         PublicInputSpec {
             inputs: vec![
                 InputSpec {
@@ -117,8 +571,8 @@ impl NetworkMetricCircuit {
                     description: "聚合后的网络指标值".to_string(),
                     data_type: InputDataType::U128,
                     constraints: vec![
-                        "value > 0".to_string(),
-                        "value < 2^64".to_string(),
+                        InputConstraint::Min(1),
+                        InputConstraint::BitLength(SAMPLE_BITS),
                     ],
                 },
                 InputSpec {
@@ -126,7 +580,7 @@ impl NetworkMetricCircuit {
                     description: "数据质量评分 (0-100)".to_string(),
                     data_type: InputDataType::U8,
                     constraints: vec![
-                        "value <= 100".to_string(),
+                        InputConstraint::Max(MAX_SCORE),
                     ],
                 },
                 InputSpec {
@@ -134,8 +588,62 @@ impl NetworkMetricCircuit {
                     description: "时间窗口（小时）".to_string(),
                     data_type: InputDataType::U8,
                     constraints: vec![
-                        "value > 0".to_string(),
-                        "value <= 24".to_string(),
+                        InputConstraint::Min(1),
+                        InputConstraint::Max(MAX_TIME_WINDOW_HOURS),
+                    ],
+                },
+                InputSpec {
+                    name: "metric_tag".to_string(),
+                    description: "指标类型标签".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "window_start".to_string(),
+                    description: "时间窗口起点（Unix 时间戳）".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "window_end".to_string(),
+                    description: "时间窗口终点（Unix 时间戳）".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "max_staleness".to_string(),
+                    description: "数据源时间戳距窗口终点的最大间隔（秒）".to_string(),
+                    data_type: InputDataType::U32,
+                    constraints: vec![
+                        InputConstraint::Max(MAX_TIME_WINDOW_HOURS * SECONDS_PER_HOUR),
+                    ],
+                },
+                InputSpec {
+                    name: "nullifier_low".to_string(),
+                    description: "空值符的低 128 位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "nullifier_high".to_string(),
+                    description: "空值符的高位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(ROOT_HIGH_BITS),
+                    ],
+                },
+                InputSpec {
+                    name: "commitment_low".to_string(),
+                    description: "数据源样本承诺摘要的低 128 位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "commitment_high".to_string(),
+                    description: "数据源样本承诺摘要的高位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(ROOT_HIGH_BITS),
                     ],
                 },
             ],
@@ -143,36 +651,2139 @@ impl NetworkMetricCircuit {
     }
     
     // 私有辅助方法
-    
-    /// 估算约束数量
-    fn estimate_constraint_count(&self, data_points: usize, sources: usize) -> usize {
-        // 基础约束：数据范围检查、聚合计算、质量评分验证
-        let base_constraints = 50;
-        
-        // 每个数据点的约束：范围检查 + 聚合计算参与
-        let data_constraints = data_points * 5;
-        
-        // 每个数据源的约束：可靠性检查 + 多样性验证
-        let source_constraints = sources * 3;
-        
-        // 额外约束：时间一致性、数据完整性等
-        let additional_constraints = 20;
-        
-        base_constraints + data_constraints + source_constraints + additional_constraints
+
+    /// 样本数量的公开输入规范
+    fn sample_count_spec() -> InputSpec {
+        InputSpec {
+            name: "sample_count".to_string(),
+            description: "私有样本数量".to_string(),
+            data_type: InputDataType::U32,
+            constraints: vec![
+                InputConstraint::Min(1),
+                InputConstraint::BitLength(COUNT_BITS),
+            ],
+        }
     }
-    
-    /// 估算见证变量数量
-    fn estimate_witness_count(&self, data_points: usize, sources: usize) -> usize {
-        // 私有数据见证
-        let private_witnesses = data_points;
-        
-        // 数据源信息见证
-        let source_witnesses = sources * 2;
-        
-        // 中间计算见证：聚合过程、质量计算等
-        let intermediate_witnesses = data_points + sources + 10;
-        
-        private_witnesses + source_witnesses + intermediate_witnesses
+
+    /// 百分位数电路的公开输入规范
+    fn percentile_input_spec() -> PublicInputSpec {
+        let mut spec = PublicInputSpec {
+            inputs: vec![
+                InputSpec {
+                    name: "percentile".to_string(),
+                    description: "百分位数 (1-100)，50 即中位数".to_string(),
+                    data_type: InputDataType::U8,
+                    constraints: vec![
+                        InputConstraint::Min(1),
+                        InputConstraint::Max(MAX_PERCENTILE),
+                    ],
+                },
+                InputSpec {
+                    name: "percentile_value".to_string(),
+                    description: "样本在该百分位数上的取值".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(SAMPLE_BITS),
+                    ],
+                },
+                Self::sample_count_spec(),
+            ],
+        };
+        spec.inputs.extend(Self::scalar_limb_specs("sample_commitment", "样本承诺"));
+        spec
+    }
+
+    /// 方差电路的公开输入规范
+    fn variance_input_spec() -> PublicInputSpec {
+        let mut spec = PublicInputSpec {
+            inputs: vec![
+                Self::sample_count_spec(),
+                InputSpec {
+                    name: "mean".to_string(),
+                    description: "样本的整数平均值".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![
+                        InputConstraint::BitLength(VARIANCE_SAMPLE_BITS),
+                    ],
+                },
+                InputSpec {
+                    name: "variance".to_string(),
+                    description: "样本总体方差（向下取整）".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![
+                        InputConstraint::BitLength(2 * VARIANCE_SAMPLE_BITS),
+                    ],
+                },
+            ],
+        };
+        spec.inputs.extend(Self::scalar_limb_specs("sample_commitment", "样本承诺"));
+        spec
+    }
+
+    /// 差分隐私求和电路的公开输入规范
+    fn privacy_input_spec() -> PublicInputSpec {
+        PublicInputSpec {
+            inputs: vec![
+                InputSpec {
+                    name: "noisy_sum".to_string(),
+                    description: "加入噪声后的样本和（截断到 0）".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(NOISY_SUM_BITS),
+                    ],
+                },
+                InputSpec {
+                    name: "epsilon_millis".to_string(),
+                    description: "隐私预算 ε（以千分之一为单位）".to_string(),
+                    data_type: InputDataType::U32,
+                    constraints: vec![
+                        InputConstraint::Min(1),
+                    ],
+                },
+                InputSpec {
+                    name: "sensitivity".to_string(),
+                    description: "单个样本的上限，即求和查询的敏感度".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![
+                        InputConstraint::Min(1),
+                    ],
+                },
+                InputSpec {
+                    name: "beacon_low".to_string(),
+                    description: "公开随机信标的低 128 位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "beacon_high".to_string(),
+                    description: "公开随机信标的高位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(ROOT_HIGH_BITS),
+                    ],
+                },
+                InputSpec {
+                    name: "secret_commitment_low".to_string(),
+                    description: "噪声秘密承诺的低 128 位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "secret_commitment_high".to_string(),
+                    description: "噪声秘密承诺的高位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(ROOT_HIGH_BITS),
+                    ],
+                },
+                InputSpec {
+                    name: "sample_commitment_low".to_string(),
+                    description: "样本承诺的低 128 位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "sample_commitment_high".to_string(),
+                    description: "样本承诺的高位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(ROOT_HIGH_BITS),
+                    ],
+                },
+            ],
+        }
+    }
+
+    /// 验证者公钥的公开输入规范（前、后 16 字节两个分量）
+    fn validator_key_specs() -> [InputSpec; 2] {
+        [
+            InputSpec {
+                name: "validator_key_low".to_string(),
+                description: "验证者公钥的前 16 字节（小端序）".to_string(),
+                data_type: InputDataType::U128,
+                constraints: vec![],
+            },
+            InputSpec {
+                name: "validator_key_high".to_string(),
+                description: "验证者公钥的后 16 字节（小端序）".to_string(),
+                data_type: InputDataType::U128,
+                constraints: vec![],
+            },
+        ]
+    }
+
+    /// 共识参与电路的公开输入规范
+    fn participation_input_spec() -> PublicInputSpec {
+        let mut inputs = vec![
+            InputSpec {
+                name: "first_block".to_string(),
+                description: "区块范围的起始高度".to_string(),
+                data_type: InputDataType::U64,
+                constraints: vec![],
+            },
+            InputSpec {
+                name: "block_count".to_string(),
+                description: "区块范围内的区块数量 N".to_string(),
+                data_type: InputDataType::U32,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                ],
+            },
+            InputSpec {
+                name: "min_signed".to_string(),
+                description: "至少产出的区块数量 K".to_string(),
+                data_type: InputDataType::U32,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                ],
+            },
+        ];
+        inputs.extend(Self::validator_key_specs());
+        inputs.extend(Self::scalar_limb_specs("author_root", "区块范围出块者树的根"));
+        PublicInputSpec { inputs }
+    }
+
+    /// 节点可靠性电路的公开输入规范
+    fn uptime_input_spec() -> PublicInputSpec {
+        let mut inputs = vec![
+            InputSpec {
+                name: "window_start".to_string(),
+                description: "时间窗口起点（Unix 时间戳）".to_string(),
+                data_type: InputDataType::U64,
+                constraints: vec![],
+            },
+            InputSpec {
+                name: "heartbeat_interval".to_string(),
+                description: "时隙长度（秒）".to_string(),
+                data_type: InputDataType::U32,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                ],
+            },
+            InputSpec {
+                name: "slot_count".to_string(),
+                description: "时间窗口内的时隙数量".to_string(),
+                data_type: InputDataType::U32,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                ],
+            },
+            InputSpec {
+                name: "min_uptime_percent".to_string(),
+                description: "有心跳的时隙至少占比（百分比）".to_string(),
+                data_type: InputDataType::U8,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                    InputConstraint::Max(MAX_SCORE),
+                ],
+            },
+        ];
+        inputs.extend(Self::validator_key_specs());
+        inputs.extend(Self::scalar_limb_specs("log_commitment", "绑定验证者身份的心跳日志承诺"));
+        PublicInputSpec { inputs }
+    }
+
+    /// 以低、高两个分量表示的域元素的公开输入规范
+    fn scalar_limb_specs(name: &str, description: &str) -> [InputSpec; 2] {
+        [
+            InputSpec {
+                name: format!("{}_low", name),
+                description: format!("{}的低 128 位", description),
+                data_type: InputDataType::U128,
+                constraints: vec![],
+            },
+            InputSpec {
+                name: format!("{}_high", name),
+                description: format!("{}的高位", description),
+                data_type: InputDataType::U128,
+                constraints: vec![
+                    InputConstraint::BitLength(ROOT_HIGH_BITS),
+                ],
+            },
+        ]
+    }
+
+    /// 滚动聚合电路的公开输入规范
+    fn rolling_input_spec() -> PublicInputSpec {
+        let mut inputs = vec![
+            InputSpec {
+                name: "metric_tag".to_string(),
+                description: "指标类型标签".to_string(),
+                data_type: InputDataType::U128,
+                constraints: vec![],
+            },
+            InputSpec {
+                name: "window_start".to_string(),
+                description: "新并入的时间窗口起点（Unix 时间戳）".to_string(),
+                data_type: InputDataType::U64,
+                constraints: vec![],
+            },
+            InputSpec {
+                name: "time_window".to_string(),
+                description: "每个时间窗口的小时数".to_string(),
+                data_type: InputDataType::U8,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                    InputConstraint::Max(MAX_TIME_WINDOW_HOURS),
+                ],
+            },
+            InputSpec {
+                name: "covered_windows".to_string(),
+                description: "滚动平均覆盖的时间窗口数量".to_string(),
+                data_type: InputDataType::U32,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                ],
+            },
+            InputSpec {
+                name: "sample_count".to_string(),
+                description: "滚动平均覆盖的样本数量".to_string(),
+                data_type: InputDataType::U32,
+                constraints: vec![
+                    InputConstraint::Min(1),
+                ],
+            },
+            InputSpec {
+                name: "rolling_average".to_string(),
+                description: "最近各时间窗口全部样本的整数平均值".to_string(),
+                data_type: InputDataType::U128,
+                constraints: vec![
+                    InputConstraint::BitLength(SAMPLE_BITS),
+                ],
+            },
+        ];
+        inputs.extend(Self::scalar_limb_specs("previous_state", "上一滚动状态承诺"));
+        inputs.extend(Self::scalar_limb_specs("state", "新滚动状态承诺"));
+        inputs.extend(Self::scalar_limb_specs("sample_commitment", "新窗口样本承诺"));
+        PublicInputSpec { inputs }
+    }
+
+    /// 数据完整性电路的公开输入规范
+    fn integrity_input_spec() -> PublicInputSpec {
+        PublicInputSpec {
+            inputs: vec![
+                InputSpec {
+                    name: "merkle_root_low".to_string(),
+                    description: "Merkle 根的低 128 位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "merkle_root_high".to_string(),
+                    description: "Merkle 根的高位".to_string(),
+                    data_type: InputDataType::U128,
+                    constraints: vec![
+                        InputConstraint::BitLength(ROOT_HIGH_BITS),
+                    ],
+                },
+                InputSpec {
+                    name: "window_start".to_string(),
+                    description: "时间窗口起点（Unix 时间戳）".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![],
+                },
+                InputSpec {
+                    name: "window_end".to_string(),
+                    description: "时间窗口终点（Unix 时间戳）".to_string(),
+                    data_type: InputDataType::U64,
+                    constraints: vec![],
+                },
+            ],
+        }
+    }
+    
+    /// 估算约束数量
+    fn estimate_constraint_count(&self, data_points: usize, sources: usize) -> usize {
+        match self.circuit_type {
+            // 排序网络：每个比较器一次 65 位比较与一次条件选择；
+            // 每个槽位一次范围检查、一次选择与一次 Poseidon 哈希（样本承诺）
+            CircuitType::Percentile => {
+                return gadgets::sorting_network(self.max_data_points).len() * (SAMPLE_BITS + 4)
+                    + self.max_data_points * (SAMPLE_BITS + 370)
+                    + 120;
+            }
+            // 每个槽位一次范围检查、一次平方与一次 Poseidon 哈希（样本承诺），另有两次带余除法
+            CircuitType::Variance => {
+                return self.max_data_points * (VARIANCE_SAMPLE_BITS + 370) + 260;
+            }
+            // 每个槽位一次范围检查与一次 Poseidon 哈希；两次查表、两次哈希与一次规范比特分解
+            CircuitType::PrivacyPreserving => {
+                let table = self.privacy.map_or(0, |p| p.noise_thresholds().len());
+                return self.max_data_points * (2 * SAMPLE_BITS + 370) + 2 * table + 1900;
+            }
+            // 每个槽位一次范围检查、三次序号比较、一次叶子哈希与沿出块者树路径的 tree_depth 次哈希；
+            // 另有一次哈希计算验证者身份
+            CircuitType::ConsensusParticipation => {
+                return self.max_data_points * (SAMPLE_BITS + 3 * INDEX_BITS + (self.tree_depth + 1) * 365) + 450;
+            }
+            // 每个槽位一次范围检查、一次 Poseidon 哈希、一次带余除法与三次序号比较；
+            // 另有两次哈希将验证者身份并入日志承诺
+            CircuitType::NodeReliability => {
+                return self.max_data_points * (SAMPLE_BITS + 6 * INDEX_BITS + 265) + 800;
+            }
+            // 每个新样本一次范围检查与一次 Poseidon 哈希（约 360 个约束）；
+            // 每个窗口在两个状态承诺中各一次哈希，另有样本和与样本数的范围检查
+            CircuitType::RollingAggregation => {
+                return self.max_data_points * (SAMPLE_BITS + 365)
+                    + self.rolling_windows * (WINDOW_COUNT_SHIFT + 2 * COUNT_BITS + 750)
+                    + 2800;
+            }
+            _ => {}
+        }
+        
+        // 基础约束：数据范围检查、聚合计算、质量评分验证
+        let base_constraints = 50;
+        
+        // 每个数据点的约束：范围检查 + 聚合计算参与
+        let data_constraints = data_points * 5;
+        
+        // 样本承诺：每个数据点两次 Poseidon 哈希（各约 360 个约束）
+        let commitment_constraints = data_points * 720;
+        
+        // 每个数据源的约束：可靠性检查 + 多样性验证
+        let source_constraints = sources * 3;
+        
+        // 额外约束：时间一致性、数据完整性等
+        let additional_constraints = 20;
+        
+        base_constraints + data_constraints + commitment_constraints + source_constraints + additional_constraints
+    }
+    
+    /// 估算见证变量数量
+    fn estimate_witness_count(&self, data_points: usize, sources: usize) -> usize {
+        // 统计电路的见证变量几乎全部来自比特分解，与约束数量相当
+        if matches!(
+            self.circuit_type,
+            CircuitType::Percentile
+                | CircuitType::Variance
+                | CircuitType::PrivacyPreserving
+                | CircuitType::ConsensusParticipation
+                | CircuitType::NodeReliability
+                | CircuitType::RollingAggregation
+        ) {
+            return self.estimate_constraint_count(data_points, sources);
+        }
+        
+        // 私有数据见证
+        let private_witnesses = data_points;
+        
+        // 数据源信息见证
+        let source_witnesses = sources * 2;
+        
+        // 中间计算见证：聚合过程、质量计算等
+        let intermediate_witnesses = data_points + sources + 10;
+        
+        private_witnesses + source_witnesses + intermediate_witnesses
+    }
+}
+
+/// 网络指标聚合电路的见证数据
+#[derive(Debug, Clone)]
+pub struct MetricWitness {
+    /// 私有样本数据
+    pub private_data: Vec<u128>,
+    /// 各数据源的可靠性评分
+    pub reliability_scores: Vec<u32>,
+    /// 公开的聚合指标值
+    pub public_metric: u128,
+    /// 数据质量评分
+    pub quality_score: u8,
+    /// 时间窗口（小时）
+    pub time_window_hours: u8,
+    /// 指标类型标签，见 [`nullifier::metric_tag`]
+    pub metric_tag: u128,
+    /// 时间窗口起点（Unix 时间戳）
+    pub window_start: u64,
+    /// 数据源时间戳距窗口终点的最大间隔（秒），不超过窗口时长
+    pub max_staleness: u64,
+    /// 提交者秘密，用于派生空值符
+    pub source_secret: Scalar,
+    /// 各数据源贡献的样本数量（按数据源顺序，private_data 为各数据源样本的拼接）
+    pub sample_counts: Vec<usize>,
+    /// 各数据源样本承诺的盲化因子
+    pub blindings: Vec<Scalar>,
+    /// 各数据源报告样本的时间戳（Unix 时间戳，绑定在样本承诺中）
+    pub source_timestamps: Vec<u64>,
+}
+
+impl MetricWitness {
+    /// 时间窗口终点（Unix 时间戳）
+    pub fn window_end(&self) -> u64 {
+        self.window_start + self.time_window_hours as u64 * SECONDS_PER_HOUR as u64
+    }
+
+    /// 本次提交的空值符
+    pub fn nullifier(&self) -> Scalar {
+        nullifier::derive(self.source_secret, self.metric_tag, self.window_start)
+    }
+
+    /// 各数据源的样本承诺，见 [`commitment::commit_at`]
+    pub fn sample_commitments(&self) -> Vec<Scalar> {
+        let mut offset = 0;
+        self.sample_counts
+            .iter()
+            .zip(&self.blindings)
+            .zip(&self.source_timestamps)
+            .map(|((count, blinding), timestamp)| {
+                let end = (offset + count).min(self.private_data.len());
+                let samples = &self.private_data[offset.min(end)..end];
+                offset = end;
+                commitment::commit_at(*blinding, *timestamp, samples)
+            })
+            .collect()
+    }
+
+    /// 各数据源样本承诺的链式摘要
+    pub fn commitment_digest(&self) -> Scalar {
+        commitment::digest(&self.sample_commitments())
+    }
+
+    /// 各数据源首个样本所在槽位及其盲化因子与时间戳
+    fn source_starts(&self) -> HashMap<usize, (Scalar, u64)> {
+        let mut offset = 0;
+        self.sample_counts
+            .iter()
+            .zip(&self.blindings)
+            .zip(&self.source_timestamps)
+            .map(|((count, blinding), timestamp)| {
+                let start = offset;
+                offset += count;
+                (start, (*blinding, *timestamp))
+            })
+            .collect()
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        let [nullifier_low, nullifier_high] = crate::utils::scalar_to_u128_limbs(&self.nullifier());
+        let [commitment_low, commitment_high] = crate::utils::scalar_to_u128_limbs(&self.commitment_digest());
+        vec![
+            self.public_metric,
+            self.quality_score as u128,
+            self.time_window_hours as u128,
+            self.metric_tag,
+            self.window_start as u128,
+            self.window_end() as u128,
+            self.max_staleness as u128,
+            nullifier_low,
+            nullifier_high,
+            commitment_low,
+            commitment_high,
+        ]
+    }
+}
+
+/// 网络指标聚合的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在 n 个私有样本（1 <= n <= max_data_points，每个小于 2^64）以及
+/// k 个数据源可靠性评分（2 <= k <= max_data_sources，每个不超过 100），使得
+/// - 样本整数平均值与公开指标之差不超过公开指标的 5%；
+/// - 公开质量评分不超过 100，且不超过平均可靠性 + 10；
+/// - 公开空值符由私有的提交者秘密、公开的指标类型标签与时间窗口起点派生，见 [`nullifier::derive`]；
+/// - 样本按数据源顺序划分为 k 段非空的连续样本，公开的承诺摘要由各段的样本承诺链式哈希得到，
+///   见 [`commitment::commit_at`] 与 [`commitment::digest`]；
+/// - 窗口终点等于起点加上 time_window 小时，最大间隔不超过窗口时长，且每段样本承诺中绑定的
+///   数据源时间戳 t 满足 window_end - max_staleness <= t <= window_end，因此 t 落在窗口之内。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[aggregated_metric, quality_score, time_window, metric_tag, window_start, window_end, max_staleness,
+/// nullifier_low, nullifier_high, commitment_low, commitment_high]`，
+/// 其取值范围也由该规范生成约束，
+/// 因此规范与电路不会出现偏差。
+/// 电路按容量填充，未使用的槽位由激活位标记并强制为零，激活位必须构成前缀，
+/// 因此同一组参数可用于该容量以内的任意数据规模。
+#[derive(Debug, Clone)]
+pub struct MetricAggregationCircuit {
+    /// 最大数据点数量
+    pub max_data_points: usize,
+    /// 最大数据源数量
+    pub max_data_sources: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<MetricWitness>,
+}
+
+impl MetricAggregationCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_data_points: circuit.max_data_points,
+            max_data_sources: circuit.max_data_sources,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: MetricWitness) -> Self {
+        Self {
+            max_data_points: circuit.max_data_points,
+            max_data_sources: circuit.max_data_sources,
+            input_spec: circuit.get_public_input_spec(),
+            witness: Some(witness),
+        }
+    }
+}
+
+impl Circuit<Scalar> for MetricAggregationCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_data_points >= 1 << COUNT_BITS || self.max_data_sources >= 1 << COUNT_BITS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (public_metric, quality_score, metric_tag, nullifier, commitment_limbs) = match inputs.as_slice() {
+            [public_metric, quality_score, _, tag, _, _, _, n_low, n_high, c_low, c_high] => (
+                public_metric.clone(),
+                quality_score.clone(),
+                tag.clone(),
+                (n_low.clone(), n_high.clone()),
+                (c_low.clone(), c_high.clone()),
+            ),
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+        let [_, _, time_window, _, window_start, window_end, max_staleness, ..] = inputs.as_slice() else {
+            return Err(SynthesisError::Unsatisfiable);
+        };
+
+        // window_end = window_start + 3600 * time_window，max_staleness <= 3600 * time_window
+        let window_length = time_window.scale(gadgets::scalar_from_u128(SECONDS_PER_HOUR));
+        gadgets::enforce_equal(cs, "window end", window_end, &window_start.add(&window_length));
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "staleness within window"),
+            &window_length.sub(max_staleness),
+            STALENESS_BITS,
+        )?;
+
+        // 私有样本：求和与计数；每个槽位另有数据源起点标记与该数据源的盲化因子、时间戳
+        let source_starts = witness.map(|w| w.source_starts());
+        let mut slots: Vec<SampleSlot> = Vec::with_capacity(self.max_data_points);
+        let mut sample_sum = LinearExpr::zero();
+        let mut sample_count = LinearExpr::zero();
+        let mut segment_count = LinearExpr::zero();
+        for i in 0..self.max_data_points {
+            let mut cs = cs.namespace(|| format!("sample {}", i));
+            let value = witness.map(|w| w.private_data.get(i).copied().unwrap_or(0));
+            let (sample, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.private_data.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "sample range"), &sample, SAMPLE_BITS)?;
+
+            let start = gadgets::alloc_bit(
+                cs.namespace(|| "source start"),
+                source_starts.as_ref().map(|starts| starts.contains_key(&i)),
+            )?;
+            let start = LinearExpr::from_bit(&start);
+            let opening = source_starts.as_ref().map(|starts| starts.get(&i).copied().unwrap_or((Scalar::ZERO, 0)));
+            let blinding = gadgets::alloc_witness(cs.namespace(|| "blinding"), opening.map(|(blinding, _)| blinding))?;
+            let timestamp = gadgets::alloc_witness(
+                cs.namespace(|| "timestamp"),
+                opening.map(|(_, timestamp)| Scalar::from(timestamp)),
+            )?;
+
+            // 只有激活槽位可以作为数据源起点：start * (1 - active) = 0
+            let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(&active);
+            gadgets::enforce_product(&mut cs, "start is active", &start, &inactive, &LinearExpr::zero());
+
+            // 时间戳只出现在数据源起点：timestamp * (1 - start) = 0；
+            // 起点处 0 <= window_end - timestamp <= max_staleness，非起点处两侧均为零
+            let not_start = LinearExpr::constant::<CS>(Scalar::ONE).sub(&start);
+            gadgets::enforce_product(&mut cs, "timestamp only at start", &timestamp, &not_start, &LinearExpr::zero());
+            let end = gadgets::mul(cs.namespace(|| "selected window end"), &start, window_end)?;
+            let staleness = gadgets::mul(cs.namespace(|| "selected staleness"), &start, max_staleness)?;
+            let age = end.sub(&timestamp);
+            gadgets::enforce_bit_length(cs.namespace(|| "timestamp before window end"), &age, STALENESS_BITS)?;
+            gadgets::enforce_bit_length(
+                cs.namespace(|| "timestamp fresh"),
+                &staleness.sub(&age),
+                STALENESS_BITS,
+            )?;
+            match slots.last() {
+                // active * (1 - previous) = 0
+                Some(previous) => {
+                    let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(&previous.active);
+                    gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+                }
+                // 第一个样本必然开始一个数据源
+                None => gadgets::enforce_equal(&mut cs, "first sample starts a source", &start, &active),
+            }
+
+            sample_sum = sample_sum.add(&sample);
+            sample_count = sample_count.add(&active);
+            segment_count = segment_count.add(&start);
+            slots.push(SampleSlot { sample, active, start, blinding, timestamp });
+        }
+
+        // 至少一个样本
+        gadgets::enforce_at_least(cs.namespace(|| "sample count >= 1"), &sample_count, 1, COUNT_BITS)?;
+        let (average, _) = gadgets::div_rem(
+            cs.namespace(|| "sample average"),
+            &sample_sum,
+            &sample_count,
+            SAMPLE_BITS,
+            COUNT_BITS,
+        )?;
+
+        // tolerance = floor(public_metric / 20)
+        let divisor = LinearExpr::constant::<CS>(Scalar::from(TOLERANCE_DIVISOR));
+        let (tolerance, _) = gadgets::div_rem(
+            cs.namespace(|| "tolerance"),
+            &public_metric,
+            &divisor,
+            SLACK_BITS,
+            SCORE_BITS,
+        )?;
+
+        // |average - public_metric| <= tolerance
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "average above lower bound"),
+            &average.add(&tolerance).sub(&public_metric),
+            SLACK_BITS,
+        )?;
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "average below upper bound"),
+            &public_metric.add(&tolerance).sub(&average),
+            SLACK_BITS,
+        )?;
+
+        // 数据源可靠性：求和与计数
+        let mut reliability_sum = LinearExpr::zero();
+        let mut source_count = LinearExpr::zero();
+        for j in 0..self.max_data_sources {
+            let mut cs = cs.namespace(|| format!("source {}", j));
+            let value = witness.map(|w| w.reliability_scores.get(j).copied().unwrap_or(0) as u128);
+            let (reliability, active) =
+                alloc_slot(&mut cs, value, witness.map(|w| j < w.reliability_scores.len()))?;
+            gadgets::enforce_range(cs.namespace(|| "reliability range"), &reliability, 0, MAX_SCORE, SCORE_BITS)?;
+            reliability_sum = reliability_sum.add(&reliability);
+            source_count = source_count.add(&active);
+        }
+
+        // 至少两个数据源，且每个数据源恰好对应一段样本
+        gadgets::enforce_at_least(cs.namespace(|| "source count >= 2"), &source_count, 2, COUNT_BITS)?;
+        gadgets::enforce_equal(cs, "one sample segment per source", &segment_count, &source_count);
+        let (average_reliability, _) = gadgets::div_rem(
+            cs.namespace(|| "average reliability"),
+            &reliability_sum,
+            &source_count,
+            SCORE_BITS,
+            COUNT_BITS,
+        )?;
+
+        // quality_score <= average_reliability + 10（质量评分的取值范围已由输入规范约束）
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "quality within reliability margin"),
+            &average_reliability
+                .add_constant::<CS>(Scalar::from(QUALITY_MARGIN))
+                .sub(&quality_score),
+            SCORE_BITS,
+        )?;
+
+        // 空值符 = H(H(secret, metric_tag), window_start)
+        let secret = gadgets::alloc_witness(cs.namespace(|| "source secret"), witness.map(|w| w.source_secret))?;
+        let derived = nullifier::derive_gadget(cs.namespace(|| "nullifier"), &secret, &metric_tag, window_start)?;
+        enforce_limbs_equal(cs, "nullifier limbs", &nullifier.0, &nullifier.1, &derived);
+
+        // 样本承诺：数据源起点处以盲化因子重置状态，段末将承诺并入摘要
+        let mut state = LinearExpr::zero();
+        let mut digest = LinearExpr::zero();
+        for (i, slot) in slots.iter().enumerate() {
+            let mut cs = cs.namespace(|| format!("commitment {}", i));
+
+            // 激活位构成前缀且起点只在激活槽位上，因此段末标记 end 为布尔值
+            let end = match slots.get(i + 1) {
+                Some(next) => slot.active.sub(&next.active).add(&next.start),
+                None => slot.active.clone(),
+            };
+            let absorbed = slot
+                .sample
+                .add(&end.scale(gadgets::scalar_from_u128(commitment::LAST_SAMPLE_FLAG)))
+                .add(&slot.start.scale(gadgets::scalar_from_u128(commitment::FIRST_SAMPLE_FLAG)))
+                .add(&slot.timestamp.scale(gadgets::scalar_from_u128(1 << commitment::TIMESTAMP_SHIFT)));
+
+            let base = gadgets::select(cs.namespace(|| "base"), &slot.start, &slot.blinding, &state)?;
+            state = poseidon::hash2_gadget(cs.namespace(|| "absorb"), &base, &absorbed)?;
+            let emitted = poseidon::hash2_gadget(cs.namespace(|| "emit"), &digest, &state)?;
+            let selected = gadgets::select(cs.namespace(|| "digest"), &end, &emitted, &digest)?;
+            digest = gadgets::materialize(cs.namespace(|| "digest value"), &selected)?;
+        }
+        enforce_limbs_equal(cs, "commitment limbs", &commitment_limbs.0, &commitment_limbs.1, &digest);
+
+        Ok(())
+    }
+}
+
+/// 网络指标电路中的一个样本槽位
+struct SampleSlot {
+    /// 样本取值
+    sample: LinearExpr,
+    /// 激活位
+    active: LinearExpr,
+    /// 数据源起点标记
+    start: LinearExpr,
+    /// 数据源起点处的盲化因子
+    blinding: LinearExpr,
+    /// 数据源起点处的时间戳，其余槽位为零
+    timestamp: LinearExpr,
+}
+
+/// 分配一个可填充槽位：返回 (取值, 激活位)，并约束未激活的槽位取值为零
+fn alloc_slot<CS: ConstraintSystem<Scalar>>(
+    cs: &mut CS,
+    value: Option<u128>,
+    active: Option<bool>,
+) -> Result<(LinearExpr, LinearExpr), SynthesisError> {
+    let active = gadgets::alloc_bit(cs.namespace(|| "active"), active)?;
+    let active = LinearExpr::from_bit(&active);
+    let value = gadgets::alloc_witness(cs.namespace(|| "value"), value.map(gadgets::scalar_from_u128))?;
+
+    // value * (1 - active) = 0
+    let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(&active);
+    gadgets::enforce_product(cs, "inactive slot is zero", &value, &inactive, &LinearExpr::zero());
+
+    Ok((value, active))
+}
+
+/// 分配按容量填充的私有批次并在电路内计算 [`merkle::batch_leaf`]`(header, batch)`
+///
+/// 激活位必须构成前缀且至少有一个激活槽位，未使用的槽位不参与哈希。
+/// 返回 (各槽位取值, 批次长度, 叶子哈希)。
+fn commit_batch<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    capacity: usize,
+    batch: Option<&[u128]>,
+    header: &LinearExpr,
+) -> Result<(Vec<LinearExpr>, LinearExpr, LinearExpr), SynthesisError> {
+    let mut slots: Vec<(LinearExpr, LinearExpr)> = Vec::with_capacity(capacity);
+    let mut batch_len = LinearExpr::zero();
+    for i in 0..capacity {
+        let mut cs = cs.namespace(|| format!("slot {}", i));
+        let value = batch.map(|b| b.get(i).copied().unwrap_or(0));
+        let (value, active) = alloc_slot(&mut cs, value, batch.map(|b| i < b.len()))?;
+        if let Some((_, previous)) = slots.last() {
+            // active * (1 - previous) = 0
+            let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+            gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+        }
+        batch_len = batch_len.add(&active);
+        slots.push((value, active));
+    }
+    gadgets::enforce_at_least(cs.namespace(|| "batch size >= 1"), &batch_len, 1, COUNT_BITS)?;
+
+    // h_0 = H(header, len)，激活槽位依次并入
+    let mut leaf = poseidon::hash2_gadget(cs.namespace(|| "leaf header"), header, &batch_len)?;
+    for (i, (value, active)) in slots.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("leaf absorb {}", i));
+        let absorbed = poseidon::hash2_gadget(cs.namespace(|| "hash"), &leaf, value)?;
+        leaf = gadgets::select(cs.namespace(|| "select"), active, &absorbed, &leaf)?;
+    }
+
+    let values = slots.into_iter().map(|(value, _)| value).collect();
+    Ok((values, batch_len, leaf))
+}
+
+/// 由两个 u128 公开输入表示的域元素 low + high * 2^128
+fn limbs_value(low: &LinearExpr, high: &LinearExpr) -> LinearExpr {
+    let shift = gadgets::scalar_from_u128(u128::MAX) + Scalar::ONE;
+    low.add(&high.scale(shift))
+}
+
+/// 约束 root_low + root_high * 2^128 = value，用两个 u128 公开输入表示一个域元素
+fn enforce_limbs_equal<CS: ConstraintSystem<Scalar>>(
+    cs: &mut CS,
+    annotation: &'static str,
+    low: &LinearExpr,
+    high: &LinearExpr,
+    value: &LinearExpr,
+) {
+    gadgets::enforce_equal(cs, annotation, &limbs_value(low, high), value);
+}
+
+/// 在电路内计算 [`commitment::commit`]`(blinding, 激活槽位的取值)`
+///
+/// slots 为 (取值, 激活位)，激活位必须已被约束为构成非空前缀。
+fn commit_prefix<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    blinding: &LinearExpr,
+    slots: &[(LinearExpr, LinearExpr)],
+) -> Result<LinearExpr, SynthesisError> {
+    let mut state = blinding.clone();
+    for (i, (value, active)) in slots.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("absorb {}", i));
+        let end = match slots.get(i + 1) {
+            Some((_, next)) => active.sub(next),
+            None => active.clone(),
+        };
+        let mut absorbed = value.add(&end.scale(gadgets::scalar_from_u128(commitment::LAST_SAMPLE_FLAG)));
+        if i == 0 {
+            absorbed = absorbed.add_constant::<CS>(gadgets::scalar_from_u128(commitment::FIRST_SAMPLE_FLAG));
+        }
+        let hashed = poseidon::hash2_gadget(cs.namespace(|| "absorb"), &state, &absorbed)?;
+        let selected = gadgets::select(cs.namespace(|| "state"), active, &hashed, &state)?;
+        state = gadgets::materialize(cs.namespace(|| "state value"), &selected)?;
+    }
+    Ok(state)
+}
+
+/// 统计激活槽位中不同序号的数量
+///
+/// slots 为 (序号, 激活位)，序号须已被约束在 [0, 2^INDEX_BITS) 内，未激活槽位的序号为零。
+/// 约束激活槽位的序号小于 bound 且不递减，返回不同序号的数量。
+fn count_distinct_indices<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    slots: &[(LinearExpr, LinearExpr)],
+    bound: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    let mut distinct = LinearExpr::zero();
+    for (i, (index, active)) in slots.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("index {}", i));
+        // bound - 1 - index >= 0
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "index below bound"),
+            &bound.sub(index).add_constant::<CS>(-Scalar::ONE),
+            INDEX_BITS,
+        )?;
+
+        let previous = match i.checked_sub(1) {
+            Some(previous) => &slots[previous].0,
+            None => {
+                distinct = distinct.add(active);
+                continue;
+            }
+        };
+        // step = active * (index - previous) >= 0；new 为 1 当且仅当 step > 0
+        let step = gadgets::mul(cs.namespace(|| "step"), active, &index.sub(previous))?;
+        gadgets::enforce_bit_length(cs.namespace(|| "non-decreasing"), &step, INDEX_BITS)?;
+        let is_new = gadgets::alloc_bit(
+            cs.namespace(|| "new index"),
+            step.value().map(|step| !bool::from(step.is_zero())),
+        )?;
+        let is_new = LinearExpr::from_bit(&is_new);
+        gadgets::enforce_bit_length(cs.namespace(|| "new index has step"), &step.sub(&is_new), INDEX_BITS)?;
+        let repeated = LinearExpr::constant::<CS>(Scalar::ONE).sub(&is_new);
+        gadgets::enforce_product(&mut cs, "repeated index has no step", &repeated, &step, &LinearExpr::zero());
+        distinct = distinct.add(&is_new);
+    }
+    Ok(distinct)
+}
+
+/// 数据完整性电路的见证数据
+#[derive(Debug, Clone)]
+pub struct IntegrityWitness {
+    /// 私有数据批次
+    pub batch: Vec<u128>,
+    /// 批次时间戳
+    pub timestamp: u64,
+    /// 批次叶子在 Merkle 树中的序号
+    pub leaf_index: u64,
+    /// 自底向上的兄弟节点
+    pub siblings: Vec<Scalar>,
+    /// 公开时间窗口起点
+    pub window_start: u64,
+    /// 公开时间窗口终点
+    pub window_end: u64,
+}
+
+impl IntegrityWitness {
+    /// 由见证计算 Merkle 根
+    pub fn merkle_root(&self) -> Scalar {
+        merkle::compute_root(
+            merkle::batch_leaf(self.timestamp, &self.batch),
+            self.leaf_index,
+            &self.siblings,
+        )
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        let [root_low, root_high] = crate::utils::scalar_to_u128_limbs(&self.merkle_root());
+        vec![
+            root_low,
+            root_high,
+            self.window_start as u128,
+            self.window_end as u128,
+        ]
+    }
+}
+
+/// 数据完整性的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在一个 n 个数据点的私有批次（1 <= n <= max_batch_size）、时间戳 t 与 Merkle 路径，使得
+/// - 批次叶子 [`merkle::batch_leaf`]`(t, batch)` 沿路径哈希（Poseidon）得到公开的 Merkle 根；
+/// - window_start <= t <= window_end。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[merkle_root_low, merkle_root_high, window_start, window_end]`。
+/// 批次按容量填充，激活位必须构成前缀，未使用的槽位不参与哈希。
+#[derive(Debug, Clone)]
+pub struct DataIntegrityCircuit {
+    /// 最大批次大小
+    pub max_batch_size: usize,
+    /// Merkle 树深度
+    pub tree_depth: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<IntegrityWitness>,
+}
+
+impl DataIntegrityCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_batch_size: circuit.max_data_points,
+            tree_depth: circuit.tree_depth,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: IntegrityWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for DataIntegrityCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_batch_size >= 1 << COUNT_BITS || self.tree_depth > MAX_TREE_DEPTH {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+        if witness.is_some_and(|w| w.siblings.len() != self.tree_depth) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (root_low, root_high, window_start, window_end) = match inputs.as_slice() {
+            [low, high, start, end] => (low.clone(), high.clone(), start.clone(), end.clone()),
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // window_start <= timestamp <= window_end
+        let timestamp = gadgets::alloc_witness(
+            cs.namespace(|| "timestamp"),
+            witness.map(|w| Scalar::from(w.timestamp)),
+        )?;
+        gadgets::enforce_bit_length(cs.namespace(|| "timestamp range"), &timestamp, TIMESTAMP_BITS)?;
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "timestamp after window start"),
+            &timestamp.sub(&window_start),
+            TIMESTAMP_BITS,
+        )?;
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "timestamp before window end"),
+            &window_end.sub(&timestamp),
+            TIMESTAMP_BITS,
+        )?;
+
+        // 私有批次及其叶子哈希
+        let batch = witness.map(|w| w.batch.as_slice());
+        let (_, _, leaf) = commit_batch(cs.namespace(|| "batch"), self.max_batch_size, batch, &timestamp)?;
+
+        // Merkle 路径：位为 1 时当前节点是右子节点
+        let mut node = leaf;
+        for level in 0..self.tree_depth {
+            let mut cs = cs.namespace(|| format!("level {}", level));
+            let is_right = gadgets::alloc_bit(
+                cs.namespace(|| "position"),
+                witness.map(|w| (w.leaf_index >> level) & 1 == 1),
+            )?;
+            let is_right = LinearExpr::from_bit(&is_right);
+            let sibling = gadgets::alloc_witness(cs.namespace(|| "sibling"), witness.map(|w| w.siblings[level]))?;
+
+            let left = gadgets::select(cs.namespace(|| "left"), &is_right, &sibling, &node)?;
+            let right = node.add(&sibling).sub(&left);
+            node = poseidon::hash2_gadget(cs.namespace(|| "parent"), &left, &right)?;
+        }
+
+        // root = root_low + root_high * 2^128
+        enforce_limbs_equal(cs, "merkle root", &root_low, &root_high, &node);
+
+        Ok(())
+    }
+}
+
+/// 百分位数电路的见证数据
+#[derive(Debug, Clone)]
+pub struct PercentileWitness {
+    /// 私有样本数据
+    pub samples: Vec<u128>,
+    /// 公开的百分位数 (1-100)
+    pub percentile: u8,
+    /// 样本承诺的盲化因子
+    pub blinding: Scalar,
+}
+
+impl PercentileWitness {
+    /// 最近秩法的秩：ceil(percentile * n / 100)，从 1 开始
+    pub fn rank(&self) -> usize {
+        (self.percentile as usize * self.samples.len()).div_ceil(MAX_PERCENTILE as usize)
+    }
+
+    /// 排序后第 rank 个样本
+    pub fn value(&self) -> u128 {
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        sorted.get(self.rank().saturating_sub(1)).copied().unwrap_or(0)
+    }
+
+    /// 样本承诺，见 [`commitment::commit`]
+    pub fn sample_commitment(&self) -> Scalar {
+        commitment::commit(self.blinding, &self.samples)
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        let [commitment_low, commitment_high] = crate::utils::scalar_to_u128_limbs(&self.sample_commitment());
+        vec![
+            self.percentile as u128,
+            self.value(),
+            self.samples.len() as u128,
+            commitment_low,
+            commitment_high,
+        ]
+    }
+}
+
+/// 百分位数的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在 n 个已承诺的私有样本（1 <= n <= max_samples，每个小于 2^64）与盲化因子 r，
+/// 使得公开的样本承诺等于 [`commitment::commit`]`(r, 样本)`，
+/// 且其升序排列中第 ceil(p * n / 100) 个样本等于公开取值（最近秩法，p = 50 即中位数）。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[percentile, percentile_value, sample_count, sample_commitment_low, sample_commitment_high]`。
+/// 样本在电路内经 Batcher 奇偶归并排序网络排序；未使用的槽位取 2^64，排在所有样本之后。
+#[derive(Debug, Clone)]
+pub struct PercentileCircuit {
+    /// 最大样本数量
+    pub max_samples: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<PercentileWitness>,
+}
+
+impl PercentileCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_samples: circuit.max_data_points,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: PercentileWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for PercentileCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_samples >= 1 << COUNT_BITS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (percentile, percentile_value, sample_count, sample_commitment) = match inputs.as_slice() {
+            [percentile, value, count, c_low, c_high] => {
+                (percentile.clone(), value.clone(), count.clone(), (c_low.clone(), c_high.clone()))
+            }
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // 私有样本：激活位构成前缀，未使用的槽位以 2^64 作为排序键
+        let padding = gadgets::scalar_from_u128(1 << SAMPLE_BITS);
+        let mut slots: Vec<(LinearExpr, LinearExpr)> = Vec::with_capacity(self.max_samples);
+        let mut keys = Vec::with_capacity(self.max_samples);
+        let mut count = LinearExpr::zero();
+        for i in 0..self.max_samples {
+            let mut cs = cs.namespace(|| format!("sample {}", i));
+            let value = witness.map(|w| w.samples.get(i).copied().unwrap_or(0));
+            let (sample, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.samples.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "sample range"), &sample, SAMPLE_BITS)?;
+            let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(&active);
+            if let Some((_, previous)) = slots.last() {
+                // active * (1 - previous) = 0
+                let gap = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+                gadgets::enforce_product(&mut cs, "active prefix", &active, &gap, &LinearExpr::zero());
+            }
+            keys.push(sample.add(&inactive.scale(padding)));
+            count = count.add(&active);
+            slots.push((sample, active));
+        }
+        gadgets::enforce_equal(cs, "sample count", &count, &sample_count);
+
+        // 样本承诺
+        let blinding = gadgets::alloc_witness(cs.namespace(|| "blinding"), witness.map(|w| w.blinding))?;
+        let state = commit_prefix(cs.namespace(|| "commitment"), &blinding, &slots)?;
+        enforce_limbs_equal(cs, "sample commitment limbs", &sample_commitment.0, &sample_commitment.1, &state);
+
+        let sorted = gadgets::sort(cs.namespace(|| "sort"), &keys, SAMPLE_BITS + 1)?;
+
+        // rank = ceil(percentile * n / 100)；percentile 与 n 的下界已由输入规范约束，因此 1 <= rank <= n
+        let scaled = gadgets::mul(cs.namespace(|| "percentile * count"), &percentile, &count)?;
+        let hundred = LinearExpr::constant::<CS>(gadgets::scalar_from_u128(MAX_PERCENTILE));
+        let (rank, _) = gadgets::div_rem(
+            cs.namespace(|| "rank"),
+            &scaled.add_constant::<CS>(gadgets::scalar_from_u128(MAX_PERCENTILE - 1)),
+            &hundred,
+            COUNT_BITS,
+            SCORE_BITS,
+        )?;
+
+        // 独热选择排序后第 rank 个元素，该位置必然是已激活的样本
+        let mut selected = LinearExpr::zero();
+        let mut selector_sum = LinearExpr::zero();
+        let mut position = LinearExpr::zero();
+        for (i, key) in sorted.iter().enumerate() {
+            let mut cs = cs.namespace(|| format!("select {}", i));
+            let is_rank = gadgets::alloc_bit(cs.namespace(|| "is rank"), witness.map(|w| i + 1 == w.rank()))?;
+            let is_rank = LinearExpr::from_bit(&is_rank);
+            selected = selected.add(&gadgets::mul(cs.namespace(|| "pick"), &is_rank, key)?);
+            selector_sum = selector_sum.add(&is_rank);
+            position = position.add(&is_rank.scale(Scalar::from(i as u64 + 1)));
+        }
+        gadgets::enforce_equal(cs, "one-hot selector", &selector_sum, &LinearExpr::constant::<CS>(Scalar::ONE));
+        gadgets::enforce_equal(cs, "selector position", &position, &rank);
+        gadgets::enforce_equal(cs, "percentile value", &selected, &percentile_value);
+
+        Ok(())
+    }
+}
+
+/// 方差电路的见证数据
+#[derive(Debug, Clone)]
+pub struct VarianceWitness {
+    /// 私有样本数据
+    pub samples: Vec<u128>,
+    /// 样本承诺的盲化因子
+    pub blinding: Scalar,
+}
+
+impl VarianceWitness {
+    /// 样本整数平均值
+    pub fn mean(&self) -> u128 {
+        self.samples.iter().sum::<u128>() / self.samples.len().max(1) as u128
+    }
+
+    /// 总体方差 floor((n * Σx² - (Σx)²) / n²)
+    pub fn variance(&self) -> u128 {
+        let n = self.samples.len() as u128;
+        if n == 0 {
+            return 0;
+        }
+        let sum: u128 = self.samples.iter().sum();
+        let sum_of_squares: u128 = self.samples.iter().map(|v| v * v).sum();
+        (n * sum_of_squares - sum * sum) / (n * n)
+    }
+
+    /// 样本承诺，见 [`commitment::commit`]
+    pub fn sample_commitment(&self) -> Scalar {
+        commitment::commit(self.blinding, &self.samples)
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        let [commitment_low, commitment_high] = crate::utils::scalar_to_u128_limbs(&self.sample_commitment());
+        vec![
+            self.samples.len() as u128,
+            self.mean(),
+            self.variance(),
+            commitment_low,
+            commitment_high,
+        ]
+    }
+}
+
+/// 方差的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在 n 个已承诺的私有样本（1 <= n <= max_samples，每个小于 2^32）与盲化因子 r，
+/// 使得公开的样本承诺等于 [`commitment::commit`]`(r, 样本)`，公开平均值为 floor(Σx / n)，
+/// 公开方差为 floor((n * Σx² - (Σx)²) / n²)。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[sample_count, mean, variance, sample_commitment_low, sample_commitment_high]`。
+/// 样本限制在 32 位以内，使 n * Σx² 不超过 2^96，整数除法的见证可在本地精确计算。
+#[derive(Debug, Clone)]
+pub struct VarianceCircuit {
+    /// 最大样本数量
+    pub max_samples: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<VarianceWitness>,
+}
+
+impl VarianceCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_samples: circuit.max_data_points,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: VarianceWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for VarianceCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_samples >= 1 << COUNT_BITS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (sample_count, mean, variance, sample_commitment) = match inputs.as_slice() {
+            [count, mean, variance, c_low, c_high] => {
+                (count.clone(), mean.clone(), variance.clone(), (c_low.clone(), c_high.clone()))
+            }
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // 私有样本：激活位构成前缀，求和、平方和与计数（未使用的槽位为零，不影响求和）
+        let mut slots: Vec<(LinearExpr, LinearExpr)> = Vec::with_capacity(self.max_samples);
+        let mut sum = LinearExpr::zero();
+        let mut sum_of_squares = LinearExpr::zero();
+        let mut count = LinearExpr::zero();
+        for i in 0..self.max_samples {
+            let mut cs = cs.namespace(|| format!("sample {}", i));
+            let value = witness.map(|w| w.samples.get(i).copied().unwrap_or(0));
+            let (sample, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.samples.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "sample range"), &sample, VARIANCE_SAMPLE_BITS)?;
+            if let Some((_, previous)) = slots.last() {
+                // active * (1 - previous) = 0
+                let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+                gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+            }
+            sum = sum.add(&sample);
+            sum_of_squares = sum_of_squares.add(&gadgets::mul(cs.namespace(|| "square"), &sample, &sample)?);
+            count = count.add(&active);
+            slots.push((sample, active));
+        }
+        gadgets::enforce_equal(cs, "sample count", &count, &sample_count);
+
+        // 样本承诺
+        let blinding = gadgets::alloc_witness(cs.namespace(|| "blinding"), witness.map(|w| w.blinding))?;
+        let state = commit_prefix(cs.namespace(|| "commitment"), &blinding, &slots)?;
+        enforce_limbs_equal(cs, "sample commitment limbs", &sample_commitment.0, &sample_commitment.1, &state);
+
+        // mean = floor(Σx / n)
+        let (average, _) = gadgets::div_rem(cs.namespace(|| "mean division"), &sum, &count, VARIANCE_SAMPLE_BITS, COUNT_BITS)?;
+        gadgets::enforce_equal(cs, "mean matches input", &average, &mean);
+
+        // variance = floor((n * Σx² - (Σx)²) / n²)
+        let scaled = gadgets::mul(cs.namespace(|| "n * sum of squares"), &count, &sum_of_squares)?;
+        let squared_sum = gadgets::mul(cs.namespace(|| "sum squared"), &sum, &sum)?;
+        let count_squared = gadgets::mul(cs.namespace(|| "n squared"), &count, &count)?;
+        let (quotient, _) = gadgets::div_rem(
+            cs.namespace(|| "variance division"),
+            &scaled.sub(&squared_sum),
+            &count_squared,
+            2 * VARIANCE_SAMPLE_BITS,
+            2 * COUNT_BITS,
+        )?;
+        gadgets::enforce_equal(cs, "variance matches input", &quotient, &variance);
+
+        Ok(())
+    }
+}
+
+/// 差分隐私求和电路的见证数据
+#[derive(Debug, Clone)]
+pub struct PrivacyWitness {
+    /// 私有样本数据
+    pub samples: Vec<u128>,
+    /// 样本承诺的盲化因子
+    pub blinding: Scalar,
+    /// 噪声秘密
+    pub noise_secret: Scalar,
+    /// 公开随机信标
+    pub beacon: Scalar,
+    /// 差分隐私参数
+    pub parameters: PrivacyParameters,
+}
+
+impl PrivacyWitness {
+    /// 样本和
+    pub fn sum(&self) -> u128 {
+        self.samples.iter().sum()
+    }
+
+    /// 由噪声秘密与信标确定的噪声
+    pub fn noise(&self) -> i64 {
+        self.parameters.sample_noise(&privacy::noise_seed(self.noise_secret, self.beacon))
+    }
+
+    /// 加噪后的样本和
+    pub fn noisy_sum(&self) -> u128 {
+        privacy::apply_noise(self.sum(), self.noise())
+    }
+
+    /// 样本承诺，见 [`commitment::commit`]
+    pub fn sample_commitment(&self) -> Scalar {
+        commitment::commit(self.blinding, &self.samples)
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        let [beacon_low, beacon_high] = crate::utils::scalar_to_u128_limbs(&self.beacon);
+        let [secret_low, secret_high] =
+            crate::utils::scalar_to_u128_limbs(&privacy::secret_commitment(self.noise_secret));
+        let [commitment_low, commitment_high] = crate::utils::scalar_to_u128_limbs(&self.sample_commitment());
+        vec![
+            self.noisy_sum(),
+            self.parameters.epsilon_millis as u128,
+            self.parameters.sensitivity as u128,
+            beacon_low,
+            beacon_high,
+            secret_low,
+            secret_high,
+            commitment_low,
+            commitment_high,
+        ]
+    }
+}
+
+/// 差分隐私求和的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在 n 个已承诺的私有样本（1 <= n <= max_samples，每个不超过敏感度 Δ）与噪声秘密 s，
+/// 使得 H(s, tag) 等于公开的秘密承诺，且公开结果为 max(0, Σx + G1 - G2)，
+/// 其中 G1、G2 由 r = H(s, beacon) 的低 64 位按 [`PrivacyParameters::noise_thresholds`] 查表得到。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[noisy_sum, epsilon_millis, sensitivity, beacon_low, beacon_high,
+///   secret_commitment_low, secret_commitment_high, sample_commitment_low, sample_commitment_high]`。
+/// ε 与 Δ 是电路常量，公开输入必须与之相等，验证者因此可以直接读出证明所保证的隐私参数。
+#[derive(Debug, Clone)]
+pub struct PrivacyCircuit {
+    /// 最大样本数量
+    pub max_samples: usize,
+    /// 差分隐私参数
+    pub parameters: Option<PrivacyParameters>,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<PrivacyWitness>,
+}
+
+impl PrivacyCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_samples: circuit.max_data_points,
+            parameters: circuit.privacy,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: PrivacyWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for PrivacyCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let parameters = match self.parameters {
+            Some(parameters) if parameters.validate().is_ok() => parameters,
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+        if self.max_samples >= 1 << COUNT_BITS || self.witness.as_ref().is_some_and(|w| w.parameters != parameters) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (noisy_sum, epsilon, sensitivity, beacon, secret_commitment, sample_commitment) = match inputs.as_slice() {
+            [noisy, epsilon, sensitivity, b_low, b_high, s_low, s_high, c_low, c_high] => (
+                noisy.clone(),
+                epsilon.clone(),
+                sensitivity.clone(),
+                (b_low.clone(), b_high.clone()),
+                (s_low.clone(), s_high.clone()),
+                (c_low.clone(), c_high.clone()),
+            ),
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // 隐私参数是电路常量
+        let epsilon_constant = LinearExpr::constant::<CS>(Scalar::from(parameters.epsilon_millis as u64));
+        gadgets::enforce_equal(cs, "epsilon is fixed", &epsilon, &epsilon_constant);
+        let sensitivity_constant = LinearExpr::constant::<CS>(Scalar::from(parameters.sensitivity));
+        gadgets::enforce_equal(cs, "sensitivity is fixed", &sensitivity, &sensitivity_constant);
+
+        // 私有样本：每个不超过敏感度，激活位构成前缀
+        let mut slots: Vec<(LinearExpr, LinearExpr)> = Vec::with_capacity(self.max_samples);
+        let mut sum = LinearExpr::zero();
+        let mut count = LinearExpr::zero();
+        for i in 0..self.max_samples {
+            let mut cs = cs.namespace(|| format!("sample {}", i));
+            let value = witness.map(|w| w.samples.get(i).copied().unwrap_or(0));
+            let (sample, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.samples.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "sample range"), &sample, SAMPLE_BITS)?;
+            gadgets::enforce_at_most(
+                cs.namespace(|| "sample clipped"),
+                &sample,
+                parameters.sensitivity as u128,
+                SAMPLE_BITS,
+            )?;
+            if let Some((_, previous)) = slots.last() {
+                // active * (1 - previous) = 0
+                let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+                gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+            }
+            sum = sum.add(&sample);
+            count = count.add(&active);
+            slots.push((sample, active));
+        }
+        gadgets::enforce_at_least(cs.namespace(|| "sample count >= 1"), &count, 1, COUNT_BITS)?;
+
+        // 样本承诺：以盲化因子为初始状态依次吸收激活样本
+        let blinding = gadgets::alloc_witness(cs.namespace(|| "blinding"), witness.map(|w| w.blinding))?;
+        let state = commit_prefix(cs.namespace(|| "commitment"), &blinding, &slots)?;
+        enforce_limbs_equal(cs, "sample commitment limbs", &sample_commitment.0, &sample_commitment.1, &state);
+
+        // 噪声秘密与其承诺
+        let secret = gadgets::alloc_witness(cs.namespace(|| "noise secret"), witness.map(|w| w.noise_secret))?;
+        let tag = LinearExpr::constant::<CS>(privacy::secret_commitment_tag());
+        let committed = poseidon::hash2_gadget(cs.namespace(|| "secret commitment"), &secret, &tag)?;
+        enforce_limbs_equal(cs, "secret commitment limbs", &secret_commitment.0, &secret_commitment.1, &committed);
+
+        // r = H(s, beacon)，规范比特分解使证明者无法另选 r 的比特
+        let shift = gadgets::scalar_from_u128(u128::MAX) + Scalar::ONE;
+        let beacon = beacon.0.add(&beacon.1.scale(shift));
+        let seed = poseidon::hash2_gadget(cs.namespace(|| "noise seed"), &secret, &beacon)?;
+        let bits = gadgets::to_bits_le_strict(cs.namespace(|| "seed bits"), &seed)?;
+        let first = gadgets::pack_bits(&bits[..privacy::UNIFORM_BITS]);
+        let second = gadgets::pack_bits(&bits[privacy::UNIFORM_BITS..2 * privacy::UNIFORM_BITS]);
+
+        // 逆累积分布查表得到两个几何分布样本
+        let thresholds = parameters.noise_thresholds();
+        let g1 = gadgets::bucket(cs.namespace(|| "first geometric"), &first, &thresholds, privacy::UNIFORM_BITS)?;
+        let g2 = gadgets::bucket(cs.namespace(|| "second geometric"), &second, &thresholds, privacy::UNIFORM_BITS)?;
+
+        // noisy = max(0, Σx + g1 - g2)：平移 K = 噪声上限后比较，避免负数
+        let offset = Scalar::from(thresholds.len() as u64);
+        let shifted = sum.add(&g1).sub(&g2).add_constant::<CS>(offset);
+        let offset = LinearExpr::constant::<CS>(offset);
+        let below = gadgets::less_than(cs.namespace(|| "noise below zero"), &shifted, &offset, NOISY_SUM_BITS)?;
+        let clamped = gadgets::select(cs.namespace(|| "clamp"), &below, &LinearExpr::zero(), &shifted.sub(&offset))?;
+        gadgets::enforce_equal(cs, "noisy sum matches input", &clamped, &noisy_sum);
+
+        Ok(())
+    }
+}
+
+/// 共识参与声明：验证者在区块 [first_block, first_block + block_count) 中至少产出了 min_signed 个区块
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParticipationClaim {
+    /// 验证者公钥
+    pub validator: [u8; 32],
+    /// 区块范围的起始高度
+    pub first_block: u64,
+    /// 区块范围内的区块数量
+    pub block_count: u32,
+    /// 至少产出的区块数量
+    pub min_signed: u32,
+}
+
+impl ParticipationClaim {
+    /// 声明与区块范围出块者树的根对应的公开输入
+    pub fn public_inputs(&self, author_root: &Scalar) -> Vec<u128> {
+        let [key_low, key_high] = merkle::validator_key_limbs(&self.validator);
+        let [root_low, root_high] = crate::utils::scalar_to_u128_limbs(author_root);
+        vec![
+            self.first_block as u128,
+            self.block_count as u128,
+            self.min_signed as u128,
+            key_low,
+            key_high,
+            root_low,
+            root_high,
+        ]
+    }
+}
+
+/// 共识参与电路的见证数据
+#[derive(Debug, Clone)]
+pub struct ParticipationWitness {
+    /// 公开声明
+    pub claim: ParticipationClaim,
+    /// 区块范围出块者树的根，见 [`merkle::block_author_tree`]
+    pub author_root: Scalar,
+    /// 验证者产出的区块高度（升序）
+    pub signed_blocks: Vec<u64>,
+    /// 各区块叶子在出块者树中自底向上的兄弟节点
+    pub siblings: Vec<Vec<Scalar>>,
+}
+
+impl ParticipationWitness {
+    /// 产出的不同区块数量
+    pub fn signed_count(&self) -> usize {
+        count_distinct(self.signed_blocks.iter().copied())
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        self.claim.public_inputs(&self.author_root)
+    }
+}
+
+/// 共识参与的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在按升序排列的 n 个区块高度（1 <= n <= max_entries）及其出块者树路径，使得
+/// - 每个高度 b 满足 first_block <= b < first_block + block_count；
+/// - 叶子 [`merkle::block_author_leaf`]`(b, validator)` 位于序号 b - first_block，沿路径哈希得到公开的出块者树根；
+/// - 其中不同高度的数量不少于 min_signed。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[first_block, block_count, min_signed, validator_key_low, validator_key_high, author_root_low, author_root_high]`。
+/// 出块者树由链上数据公开计算，证明因而绑定到验证者公钥，不能转让给其他验证者；
+/// 验证者只知道产出的区块数量达到阈值，不知道具体是哪些区块或确切数量。
+#[derive(Debug, Clone)]
+pub struct ParticipationCircuit {
+    /// 可证明的最大出块数量
+    pub max_entries: usize,
+    /// 出块者树深度
+    pub tree_depth: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<ParticipationWitness>,
+}
+
+impl ParticipationCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_entries: circuit.max_data_points,
+            tree_depth: circuit.tree_depth,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: ParticipationWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for ParticipationCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_entries >= 1 << COUNT_BITS || self.tree_depth > MAX_TREE_DEPTH {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+        if witness.is_some_and(|w| {
+            w.siblings.len() != w.signed_blocks.len() || w.siblings.iter().any(|path| path.len() != self.tree_depth)
+        }) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (first_block, block_count, min_signed, validator_key, author_root) = match inputs.as_slice() {
+            [first, count, min, key_low, key_high, root_low, root_high] => (
+                first.clone(),
+                count.clone(),
+                min.clone(),
+                (key_low.clone(), key_high.clone()),
+                limbs_value(root_low, root_high),
+            ),
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+        let validator_id = poseidon::hash2_gadget(cs.namespace(|| "validator id"), &validator_key.0, &validator_key.1)?;
+
+        // 出块列表：激活位构成前缀，区块序号 = 高度 - first_block（未激活槽位为零）
+        let mut previous: Option<LinearExpr> = None;
+        let mut indices = Vec::with_capacity(self.max_entries);
+        for i in 0..self.max_entries {
+            let mut cs = cs.namespace(|| format!("entry {}", i));
+            let value = witness.map(|w| w.signed_blocks.get(i).map_or(0, |block| *block as u128));
+            let (block, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.signed_blocks.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "block range"), &block, SAMPLE_BITS)?;
+            if let Some(previous) = &previous {
+                // active * (1 - previous) = 0
+                let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+                gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+            }
+
+            // 序号的比特即叶子在出块者树中的位置，位为 1 时当前节点是右子节点
+            let offset = gadgets::mul(cs.namespace(|| "range start"), &active, &first_block)?;
+            let index = block.sub(&offset);
+            let position = gadgets::enforce_bit_length(cs.namespace(|| "leaf index"), &index, self.tree_depth)?;
+            let mut node = poseidon::hash2_gadget(cs.namespace(|| "leaf"), &block, &validator_id)?;
+            for (level, is_right) in position.iter().enumerate() {
+                let mut cs = cs.namespace(|| format!("level {}", level));
+                let is_right = LinearExpr::from_bit(is_right);
+                let sibling = gadgets::alloc_witness(
+                    cs.namespace(|| "sibling"),
+                    witness.map(|w| w.siblings.get(i).map_or(Scalar::ZERO, |path| path[level])),
+                )?;
+                let left = gadgets::select(cs.namespace(|| "left"), &is_right, &sibling, &node)?;
+                let right = node.add(&sibling).sub(&left);
+                node = poseidon::hash2_gadget(cs.namespace(|| "parent"), &left, &right)?;
+            }
+            // 激活槽位的路径必须到达公开的根：active * (node - root) = 0
+            gadgets::enforce_product(&mut cs, "author membership", &active, &node.sub(&author_root), &LinearExpr::zero());
+
+            indices.push((index, active.clone()));
+            previous = Some(active);
+        }
+
+        // 不同区块的数量不少于 K
+        let signed = count_distinct_indices(cs.namespace(|| "distinct blocks"), &indices, &block_count)?;
+        gadgets::enforce_bit_length(cs.namespace(|| "signed >= min_signed"), &signed.sub(&min_signed), COUNT_BITS)?;
+
+        Ok(())
+    }
+}
+
+/// 节点在线率声明：自 window_start 起 slot_count 个长度为 heartbeat_interval 秒的时隙中，
+/// 验证者至少在 min_uptime_percent% 的时隙内有心跳
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UptimeClaim {
+    /// 验证者公钥
+    pub validator: [u8; 32],
+    /// 时间窗口起点（Unix 时间戳）
+    pub window_start: u64,
+    /// 时隙长度（秒）
+    pub heartbeat_interval: u32,
+    /// 时间窗口内的时隙数量
+    pub slot_count: u32,
+    /// 有心跳的时隙至少占比（百分比）
+    pub min_uptime_percent: u8,
+}
+
+impl UptimeClaim {
+    /// 声明与心跳日志承诺对应的公开输入
+    pub fn public_inputs(&self, log_commitment: &Scalar) -> Vec<u128> {
+        let [key_low, key_high] = merkle::validator_key_limbs(&self.validator);
+        let [commitment_low, commitment_high] = crate::utils::scalar_to_u128_limbs(log_commitment);
+        vec![
+            self.window_start as u128,
+            self.heartbeat_interval as u128,
+            self.slot_count as u128,
+            self.min_uptime_percent as u128,
+            key_low,
+            key_high,
+            commitment_low,
+            commitment_high,
+        ]
+    }
+}
+
+/// 节点可靠性电路的见证数据
+#[derive(Debug, Clone)]
+pub struct UptimeWitness {
+    /// 公开声明
+    pub claim: UptimeClaim,
+    /// 心跳时间戳（升序）
+    pub heartbeats: Vec<u64>,
+    /// 心跳日志承诺的盲化因子
+    pub blinding: Scalar,
+}
+
+impl UptimeWitness {
+    /// 各心跳所在的时隙序号，早于窗口起点或时隙长度为零时为 None
+    pub fn slots(&self) -> Vec<Option<u64>> {
+        self.heartbeats
+            .iter()
+            .map(|timestamp| {
+                timestamp
+                    .checked_sub(self.claim.window_start)?
+                    .checked_div(self.claim.heartbeat_interval as u64)
+            })
+            .collect()
+    }
+
+    /// 有心跳的不同时隙数量
+    pub fn covered_slots(&self) -> usize {
+        count_distinct(self.slots().into_iter().flatten())
+    }
+
+    /// 绑定验证者身份的心跳日志承诺 `H(`[`commitment::commit`]`(r, 心跳时间戳), validator_id)`
+    pub fn log_commitment(&self) -> Scalar {
+        let entries: Vec<u128> = self.heartbeats.iter().map(|timestamp| *timestamp as u128).collect();
+        poseidon::hash2(commitment::commit(self.blinding, &entries), merkle::validator_id(&self.claim.validator))
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        self.claim.public_inputs(&self.log_commitment())
+    }
+}
+
+/// 节点在线率的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在按时间排列的 n 个心跳时间戳（1 <= n <= max_heartbeats）与盲化因子 r，使得
+/// - 每个心跳落在某个时隙 floor((t - window_start) / heartbeat_interval) < slot_count 内；
+/// - 有心跳的不同时隙数量 c 满足 100 · c >= min_uptime_percent · slot_count；
+/// - 公开的日志承诺等于 `H(`[`commitment::commit`]`(r, 心跳时间戳), H(validator_key_low, validator_key_high))`。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[window_start, heartbeat_interval, slot_count, min_uptime_percent, validator_key_low, validator_key_high,
+/// log_commitment_low, log_commitment_high]`。
+/// 承诺绑定验证者公钥，已公布的心跳日志不能被其他验证者用来证明在线率；
+/// 验证者只知道在线率达到阈值，不知道心跳的确切时间或缺失的时隙。
+#[derive(Debug, Clone)]
+pub struct UptimeCircuit {
+    /// 心跳日志的最大条数
+    pub max_heartbeats: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<UptimeWitness>,
+}
+
+impl UptimeCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_heartbeats: circuit.max_data_points,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: UptimeWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for UptimeCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_heartbeats >= 1 << COUNT_BITS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let (window_start, interval, slot_count, min_percent, validator_key, log_commitment) = match inputs.as_slice() {
+            [start, interval, slots, percent, key_low, key_high, c_low, c_high] => (
+                start.clone(),
+                interval.clone(),
+                slots.clone(),
+                percent.clone(),
+                (key_low.clone(), key_high.clone()),
+                (c_low.clone(), c_high.clone()),
+            ),
+            _ => return Err(SynthesisError::Unsatisfiable),
+        };
+
+        // 心跳日志：激活位构成前缀，时隙序号 = floor((t - window_start) / interval)（未激活槽位为零）
+        let mut entries: Vec<(LinearExpr, LinearExpr)> = Vec::with_capacity(self.max_heartbeats);
+        let mut slots = Vec::with_capacity(self.max_heartbeats);
+        for i in 0..self.max_heartbeats {
+            let mut cs = cs.namespace(|| format!("heartbeat {}", i));
+            let value = witness.map(|w| w.heartbeats.get(i).map_or(0, |timestamp| *timestamp as u128));
+            let (timestamp, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.heartbeats.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "timestamp range"), &timestamp, TIMESTAMP_BITS)?;
+            if let Some((_, previous)) = entries.last() {
+                // active * (1 - previous) = 0
+                let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+                gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+            }
+
+            // 商与余数的范围检查保证 t - window_start 非负
+            let offset = gadgets::mul(cs.namespace(|| "window start"), &active, &window_start)?;
+            let (slot, _) = gadgets::div_rem(
+                cs.namespace(|| "slot"),
+                &timestamp.sub(&offset),
+                &interval,
+                INDEX_BITS,
+                INDEX_BITS,
+            )?;
+            slots.push((slot, active.clone()));
+            entries.push((timestamp, active));
+        }
+
+        // 100 · 有心跳的时隙数 >= min_uptime_percent · slot_count
+        let covered = count_distinct_indices(cs.namespace(|| "distinct slots"), &slots, &slot_count)?;
+        let required = gadgets::mul(cs.namespace(|| "required slots"), &min_percent, &slot_count)?;
+        gadgets::enforce_bit_length(
+            cs.namespace(|| "uptime above threshold"),
+            &covered.scale(Scalar::from(MAX_SCORE as u64)).sub(&required),
+            COUNT_BITS + SCORE_BITS,
+        )?;
+
+        // 心跳日志承诺，并入验证者身份
+        let blinding = gadgets::alloc_witness(cs.namespace(|| "blinding"), witness.map(|w| w.blinding))?;
+        let state = commit_prefix(cs.namespace(|| "log commitment"), &blinding, &entries)?;
+        let validator_id = poseidon::hash2_gadget(cs.namespace(|| "validator id"), &validator_key.0, &validator_key.1)?;
+        let bound = poseidon::hash2_gadget(cs.namespace(|| "bind validator"), &state, &validator_id)?;
+        enforce_limbs_equal(cs, "log commitment limbs", &log_commitment.0, &log_commitment.1, &bound);
+
+        Ok(())
+    }
+}
+
+/// 升序序列中不同取值的数量
+fn count_distinct(sorted: impl Iterator<Item = u64>) -> usize {
+    let mut previous = None;
+    sorted.filter(|value| previous.replace(*value) != Some(*value)).count()
+}
+
+/// 滚动聚合电路的见证数据
+#[derive(Debug, Clone)]
+pub struct RollingWitness {
+    /// 上一滚动状态
+    pub previous: RollingState,
+    /// 新时间窗口的私有样本
+    pub samples: Vec<u128>,
+    /// 样本承诺的盲化因子
+    pub sample_blinding: Scalar,
+    /// 新状态承诺的盲化因子
+    pub state_blinding: [u8; 32],
+}
+
+impl RollingWitness {
+    /// 并入新窗口后的滚动状态
+    pub fn state(&self) -> RollingState {
+        self.previous.advance(&self.samples, self.state_blinding)
+    }
+
+    /// 新窗口的样本承诺，见 [`commitment::commit`]
+    pub fn sample_commitment(&self) -> Scalar {
+        commitment::commit(self.sample_blinding, &self.samples)
+    }
+
+    /// 按电路公开输入的顺序返回公开输入
+    pub fn public_inputs(&self) -> Vec<u128> {
+        let state = self.state();
+        let [previous_low, previous_high] = crate::utils::scalar_to_u128_limbs(&self.previous.commitment());
+        let [state_low, state_high] = crate::utils::scalar_to_u128_limbs(&state.commitment());
+        let [commitment_low, commitment_high] = crate::utils::scalar_to_u128_limbs(&self.sample_commitment());
+        vec![
+            self.previous.metric_tag,
+            self.previous.next_window_start as u128,
+            self.previous.window_hours as u128,
+            state.covered_windows() as u128,
+            state.sample_count(),
+            state.average(),
+            previous_low,
+            previous_high,
+            state_low,
+            state_high,
+            commitment_low,
+            commitment_high,
+        ]
+    }
+}
+
+/// 滚动聚合的 bellman 电路（BLS12-381）
+///
+/// 证明语句：存在上一滚动状态（W 个窗口的样本和与样本数、盲化因子）、
+/// n 个已承诺的新样本（1 <= n <= max_samples，每个小于 2^64）与新的盲化因子，使得
+/// - 上一状态的承诺等于公开的上一状态承诺，其下一窗口起点等于公开的窗口起点；
+/// - 新状态移出最旧的窗口并入新样本的和与数量，下一窗口起点后移 time_window 小时，
+///   其承诺等于公开的新状态承诺，见 [`rolling::state_commitment`]；
+/// - 公开的样本承诺等于 [`commitment::commit`]`(r, 新样本)`；
+/// - 公开的覆盖窗口数、样本数与滚动平均值由新状态的 W 个窗口计算得到。
+///
+/// 公开输入按 [`NetworkMetricCircuit::get_public_input_spec`] 的顺序分配：
+/// `[metric_tag, window_start, time_window, covered_windows, sample_count, rolling_average,
+///   previous_state_low, previous_state_high, state_low, state_high, sample_commitment_low, sample_commitment_high]`。
+/// 电路只处理新窗口的样本，历史窗口以状态承诺的形式传递，见 [`crate::rolling`]。
+#[derive(Debug, Clone)]
+pub struct RollingCircuit {
+    /// 每个窗口的最大样本数量
+    pub max_samples: usize,
+    /// 滚动窗口数量
+    pub rolling_windows: usize,
+    /// 公开输入规范
+    pub input_spec: PublicInputSpec,
+    /// 见证数据（参数生成时为 None）
+    pub witness: Option<RollingWitness>,
+}
+
+impl RollingCircuit {
+    /// 创建不含见证的电路实例，用于参数生成
+    pub fn blank(circuit: &NetworkMetricCircuit) -> Self {
+        Self {
+            max_samples: circuit.max_data_points,
+            rolling_windows: circuit.rolling_windows,
+            input_spec: circuit.get_public_input_spec(),
+            witness: None,
+        }
+    }
+
+    /// 创建带见证的电路实例，用于证明生成
+    pub fn with_witness(circuit: &NetworkMetricCircuit, witness: RollingWitness) -> Self {
+        Self {
+            witness: Some(witness),
+            ..Self::blank(circuit)
+        }
+    }
+}
+
+impl Circuit<Scalar> for RollingCircuit {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        if self.max_samples >= 1 << COUNT_BITS || self.rolling_windows == 0 || self.rolling_windows >= 1 << COUNT_BITS {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        if self.witness.as_ref().is_some_and(|w| w.previous.windows.len() != self.rolling_windows) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let witness = self.witness.as_ref();
+
+        // 公开输入：按规范分配并约束取值范围
+        let public_inputs = witness.map(|w| w.public_inputs());
+        let inputs = self.input_spec.alloc_inputs(cs, public_inputs.as_deref())?;
+        let [tag, window_start, time_window, covered_windows, sample_count, average, rest @ ..] = inputs.as_slice() else {
+            return Err(SynthesisError::Unsatisfiable);
+        };
+        let [p_low, p_high, s_low, s_high, c_low, c_high] = rest else {
+            return Err(SynthesisError::Unsatisfiable);
+        };
+
+        // 上一状态：各窗口的样本和与样本数须在打包范围内，下一窗口起点即公开的窗口起点
+        let mut previous = Vec::with_capacity(self.rolling_windows);
+        for i in 0..self.rolling_windows {
+            let mut cs = cs.namespace(|| format!("previous window {}", i));
+            let window = witness.map(|w| w.previous.windows[i]);
+            let sum = gadgets::alloc_witness(cs.namespace(|| "sum"), window.map(|w| gadgets::scalar_from_u128(w.sum)))?;
+            let count = gadgets::alloc_witness(cs.namespace(|| "count"), window.map(|w| Scalar::from(w.count as u64)))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "sum range"), &sum, WINDOW_COUNT_SHIFT)?;
+            gadgets::enforce_bit_length(cs.namespace(|| "count range"), &count, COUNT_BITS)?;
+            previous.push((sum, count));
+        }
+        let previous_blinding = gadgets::alloc_witness(
+            cs.namespace(|| "previous blinding"),
+            witness.map(|w| commitment::blinding_scalar(&w.previous.blinding)),
+        )?;
+        let previous_state = rolling::state_commitment_gadget(
+            cs.namespace(|| "previous state"),
+            &previous_blinding,
+            tag,
+            time_window,
+            window_start,
+            &previous,
+        )?;
+        enforce_limbs_equal(cs, "previous state limbs", p_low, p_high, &previous_state);
+
+        // 新窗口的样本：激活位构成前缀，至少一个样本
+        let mut slots: Vec<(LinearExpr, LinearExpr)> = Vec::with_capacity(self.max_samples);
+        let mut window_sum = LinearExpr::zero();
+        let mut window_count = LinearExpr::zero();
+        for i in 0..self.max_samples {
+            let mut cs = cs.namespace(|| format!("sample {}", i));
+            let value = witness.map(|w| w.samples.get(i).copied().unwrap_or(0));
+            let (sample, active) = alloc_slot(&mut cs, value, witness.map(|w| i < w.samples.len()))?;
+            gadgets::enforce_bit_length(cs.namespace(|| "sample range"), &sample, SAMPLE_BITS)?;
+            if let Some((_, previous)) = slots.last() {
+                // active * (1 - previous) = 0
+                let inactive = LinearExpr::constant::<CS>(Scalar::ONE).sub(previous);
+                gadgets::enforce_product(&mut cs, "active prefix", &active, &inactive, &LinearExpr::zero());
+            }
+            window_sum = window_sum.add(&sample);
+            window_count = window_count.add(&active);
+            slots.push((sample, active));
+        }
+        gadgets::enforce_at_least(cs.namespace(|| "sample count >= 1"), &window_count, 1, COUNT_BITS)?;
+
+        // 样本承诺
+        let sample_blinding =
+            gadgets::alloc_witness(cs.namespace(|| "sample blinding"), witness.map(|w| w.sample_blinding))?;
+        let committed = commit_prefix(cs.namespace(|| "sample commitment"), &sample_blinding, &slots)?;
+        enforce_limbs_equal(cs, "sample commitment limbs", c_low, c_high, &committed);
+
+        // 新状态：移出最旧的窗口并入新窗口，每个保留的窗口样本数非零时计为已覆盖
+        let mut windows = previous[1..].to_vec();
+        let mut covered = LinearExpr::constant::<CS>(Scalar::ONE);
+        for (i, (_, count)) in windows.iter().enumerate() {
+            let mut cs = cs.namespace(|| format!("covered window {}", i));
+            let occupied = gadgets::alloc_bit(
+                cs.namespace(|| "occupied"),
+                count.value().map(|count| !bool::from(count.is_zero())),
+            )?;
+            let occupied = LinearExpr::from_bit(&occupied);
+            // count * (1 - occupied) = 0，count >= occupied
+            let empty = LinearExpr::constant::<CS>(Scalar::ONE).sub(&occupied);
+            gadgets::enforce_product(&mut cs, "empty window has no samples", count, &empty, &LinearExpr::zero());
+            gadgets::enforce_bit_length(
+                cs.namespace(|| "occupied window has samples"),
+                &count.sub(&occupied),
+                COUNT_BITS,
+            )?;
+            covered = covered.add(&occupied);
+        }
+        gadgets::enforce_equal(cs, "covered windows", &covered, covered_windows);
+        windows.push((window_sum, window_count));
+
+        let window_length = time_window.scale(gadgets::scalar_from_u128(SECONDS_PER_HOUR));
+        let state_blinding = gadgets::alloc_witness(
+            cs.namespace(|| "state blinding"),
+            witness.map(|w| commitment::blinding_scalar(&w.state_blinding)),
+        )?;
+        let state = rolling::state_commitment_gadget(
+            cs.namespace(|| "state"),
+            &state_blinding,
+            tag,
+            time_window,
+            &window_start.add(&window_length),
+            &windows,
+        )?;
+        enforce_limbs_equal(cs, "state limbs", s_low, s_high, &state);
+
+        // rolling_average = floor(Σ sum / Σ count)
+        let total_sum = windows.iter().fold(LinearExpr::zero(), |total, (sum, _)| total.add(sum));
+        let total_count = windows.iter().fold(LinearExpr::zero(), |total, (_, count)| total.add(count));
+        gadgets::enforce_equal(cs, "sample count", &total_count, sample_count);
+        let (quotient, _) = gadgets::div_rem(
+            cs.namespace(|| "rolling average"),
+            &total_sum,
+            &total_count,
+            SAMPLE_BITS,
+            ROLLING_COUNT_BITS,
+        )?;
+        gadgets::enforce_equal(cs, "rolling average matches input", &quotient, average);
+
+        Ok(())
     }
 }
 
@@ -208,7 +2819,124 @@ pub struct InputSpec {
     /// 数据类型
     pub data_type: InputDataType,
     /// 约束条件
-    pub constraints: Vec<String>,
+    pub constraints: Vec<InputConstraint>,
+}
+
+impl PublicInputSpec {
+    /// 检查一组公开输入是否满足规范（数量与顺序必须一致）
+    pub fn is_satisfied_by(&self, values: &[u128]) -> bool {
+        values.len() == self.inputs.len()
+            && self.inputs.iter().zip(values).all(|(spec, value)| spec.is_satisfied_by(*value))
+    }
+
+    /// 按规范顺序分配公开输入，并为每个输入生成取值范围约束
+    pub fn alloc_inputs<CS: ConstraintSystem<Scalar>>(
+        &self,
+        cs: &mut CS,
+        values: Option<&[u128]>,
+    ) -> Result<Vec<LinearExpr>, SynthesisError> {
+        if values.is_some_and(|v| v.len() != self.inputs.len()) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        let mut allocated = Vec::with_capacity(self.inputs.len());
+        for (i, spec) in self.inputs.iter().enumerate() {
+            let mut cs = cs.namespace(|| spec.name.clone());
+            let input = gadgets::alloc_input(
+                cs.namespace(|| "value"),
+                values.map(|v| gadgets::scalar_from_u128(v[i])),
+            )?;
+            spec.enforce(cs.namespace(|| "constraints"), &input)?;
+            allocated.push(input);
+        }
+        Ok(allocated)
+    }
+}
+
+impl InputSpec {
+    /// 输入取值的比特宽度：数据类型宽度与比特长度约束中的较小者
+    pub fn bit_width(&self) -> Option<usize> {
+        let type_width = self.data_type.bit_width()?;
+        Some(
+            self.constraints
+                .iter()
+                .filter_map(|c| match c {
+                    InputConstraint::BitLength(bits) => Some(*bits),
+                    _ => None,
+                })
+                .fold(type_width, usize::min),
+        )
+    }
+
+    /// 检查取值是否满足数据类型与全部约束条件
+    pub fn is_satisfied_by(&self, value: u128) -> bool {
+        match self.bit_width() {
+            Some(bits) => {
+                fits_in_bits(value, bits) && self.constraints.iter().all(|c| c.is_satisfied_by(value))
+            }
+            None => false,
+        }
+    }
+
+    /// 在电路中约束表达式满足数据类型与全部约束条件
+    ///
+    /// 先按比特宽度做比特分解，再为每个上下界生成一次比较约束。
+    /// 字节类型没有固定宽度，不能作为域元素输入。
+    pub fn enforce<CS: ConstraintSystem<Scalar>>(
+        &self,
+        mut cs: CS,
+        expr: &LinearExpr,
+    ) -> Result<(), SynthesisError> {
+        let bits = self.bit_width().ok_or(SynthesisError::Unsatisfiable)?;
+        gadgets::enforce_bit_length(cs.namespace(|| "bit length"), expr, bits)?;
+
+        for (i, constraint) in self.constraints.iter().enumerate() {
+            match *constraint {
+                InputConstraint::Min(0) | InputConstraint::BitLength(_) => {}
+                InputConstraint::Min(min) => {
+                    gadgets::enforce_at_least(cs.namespace(|| format!("constraint {}", i)), expr, min, bits)?
+                }
+                // 不小于 2^bits - 1 的上界已由比特分解保证
+                InputConstraint::Max(max) if !fits_in_bits(max.saturating_add(1), bits) => {}
+                InputConstraint::Max(max) => {
+                    gadgets::enforce_at_most(cs.namespace(|| format!("constraint {}", i)), expr, max, bits)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 输入约束条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InputConstraint {
+    /// value >= 下界
+    Min(u128),
+    /// value <= 上界
+    Max(u128),
+    /// value < 2^bits
+    BitLength(usize),
+}
+
+impl InputConstraint {
+    /// 检查取值是否满足该约束
+    pub fn is_satisfied_by(&self, value: u128) -> bool {
+        match *self {
+            InputConstraint::Min(min) => value >= min,
+            InputConstraint::Max(max) => value <= max,
+            InputConstraint::BitLength(bits) => fits_in_bits(value, bits),
+        }
+    }
+}
+
+impl fmt::Display for InputConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputConstraint::Min(min) => write!(f, "value >= {}", min),
+            InputConstraint::Max(max) => write!(f, "value <= {}", max),
+            InputConstraint::BitLength(bits) => write!(f, "value < 2^{}", bits),
+        }
+    }
 }
 
 /// 输入数据类型
@@ -221,20 +2949,46 @@ pub enum InputDataType {
     Bytes,
 }
 
+impl InputDataType {
+    /// 该类型取值的比特宽度（字节类型没有固定宽度）
+    pub fn bit_width(&self) -> Option<usize> {
+        match self {
+            InputDataType::U8 => Some(8),
+            InputDataType::U32 => Some(32),
+            InputDataType::U64 => Some(64),
+            InputDataType::U128 => Some(128),
+            InputDataType::Bytes => None,
+        }
+    }
+}
+
+/// 取值是否小于 2^bits
+fn fits_in_bits(value: u128, bits: usize) -> bool {
+    bits >= 128 || value >> bits == 0
+}
+
 /// 电路管理器
 pub struct CircuitManager {
     /// 已注册的电路
     circuits: HashMap<u32, NetworkMetricCircuit>,
     /// 电路类型映射
     type_mapping: HashMap<CircuitType, Vec<u32>>,
+    /// 电路分析器，提供实际的约束数量与实测证明耗时
+    profiler: Arc<CircuitProfiler>,
 }
 
 impl CircuitManager {
     /// 创建新的电路管理器
     pub fn new() -> Self {
+        Self::with_profiler(Arc::new(CircuitProfiler::new()))
+    }
+    
+    /// 使用共享的电路分析器创建电路管理器
+    pub fn with_profiler(profiler: Arc<CircuitProfiler>) -> Self {
         let mut manager = Self {
             circuits: HashMap::new(),
             type_mapping: HashMap::new(),
+            profiler,
         };
         
         // 注册默认电路
@@ -252,7 +3006,7 @@ impl CircuitManager {
         
         self.type_mapping
             .entry(circuit_type)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(circuit_id);
     }
     
@@ -261,6 +3015,17 @@ impl CircuitManager {
         self.circuits.get(&circuit_id)
     }
     
+    /// 设置电路使用的证明系统后端，电路不存在时返回 false
+    pub fn set_proof_system(&mut self, circuit_id: u32, proof_system: ProofSystemKind) -> bool {
+        match self.circuits.get_mut(&circuit_id) {
+            Some(circuit) => {
+                circuit.proof_system = proof_system;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// 根据类型获取电路
     pub fn get_circuits_by_type(&self, circuit_type: &CircuitType) -> Vec<&NetworkMetricCircuit> {
         if let Some(circuit_ids) = self.type_mapping.get(circuit_type) {
@@ -273,6 +3038,23 @@ impl CircuitManager {
         }
     }
     
+    /// 电路分析器
+    pub fn profiler(&self) -> &Arc<CircuitProfiler> {
+        &self.profiler
+    }
+    
+    /// 电路的复杂度：优先使用实际合成与实测耗时，合成失败时回退到公式估算
+    pub fn circuit_complexity(
+        &self,
+        circuit: &NetworkMetricCircuit,
+        data_points: usize,
+        sources: usize,
+    ) -> CircuitComplexity {
+        self.profiler
+            .complexity(circuit)
+            .unwrap_or_else(|_| circuit.estimate_complexity(data_points, sources))
+    }
+    
     /// 选择最适合的电路
     pub fn select_optimal_circuit(
         &self,
@@ -289,11 +3071,50 @@ impl CircuitManager {
                 circuit.max_data_points >= data_points && circuit.max_data_sources >= sources
             })
             .min_by_key(|circuit| {
-                let complexity = circuit.estimate_complexity(data_points, sources);
-                complexity.estimated_generation_time_ms
+                let complexity = self.circuit_complexity(circuit, data_points, sources);
+                (complexity.estimated_generation_time_ms, complexity.constraint_count)
             })
     }
     
+    /// 选择能容纳指定批次大小且树深度一致的最小数据完整性电路
+    pub fn select_integrity_circuit(&self, batch_size: usize, tree_depth: usize) -> Option<&NetworkMetricCircuit> {
+        self.get_circuits_by_type(&CircuitType::DataIntegrity)
+            .into_iter()
+            .filter(|circuit| circuit.tree_depth == tree_depth && circuit.max_data_points >= batch_size)
+            .min_by_key(|circuit| circuit.max_data_points)
+    }
+
+    /// 选择树深度一致、能容纳 entries 个出块的最小共识参与电路
+    pub fn select_participation_circuit(&self, entries: usize, tree_depth: usize) -> Option<&NetworkMetricCircuit> {
+        self.get_circuits_by_type(&CircuitType::ConsensusParticipation)
+            .into_iter()
+            .filter(|circuit| circuit.tree_depth == tree_depth && circuit.max_data_points >= entries)
+            .min_by_key(|circuit| circuit.max_data_points)
+    }
+
+    /// 选择使用给定隐私参数、能容纳 samples 个样本的最小差分隐私电路
+    pub fn select_privacy_circuit(&self, samples: usize, parameters: &PrivacyParameters) -> Option<&NetworkMetricCircuit> {
+        self.get_circuits_by_type(&CircuitType::PrivacyPreserving)
+            .into_iter()
+            .filter(|circuit| circuit.privacy.as_ref() == Some(parameters) && circuit.max_data_points >= samples)
+            .min_by_key(|circuit| circuit.max_data_points)
+    }
+    
+    /// 选择滚动窗口数量一致、能容纳 samples 个新样本的最小滚动聚合电路
+    pub fn select_rolling_circuit(&self, samples: usize, rolling_windows: usize) -> Option<&NetworkMetricCircuit> {
+        self.get_circuits_by_type(&CircuitType::RollingAggregation)
+            .into_iter()
+            .filter(|circuit| circuit.rolling_windows == rolling_windows && circuit.max_data_points >= samples)
+            .min_by_key(|circuit| circuit.max_data_points)
+    }
+    
+    /// 获取所有已注册的电路ID（升序）
+    pub fn circuit_ids(&self) -> Vec<u32> {
+        let mut ids: Vec<u32> = self.circuits.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+    
     /// 获取所有已注册的电路类型
     pub fn get_supported_types(&self) -> Vec<CircuitType> {
         self.type_mapping.keys().cloned().collect()
@@ -348,6 +3169,72 @@ impl CircuitManager {
             "大型网络指标电路，适用于大规模数据聚合".to_string(),
         );
         self.register_circuit(large_circuit);
+        
+        // 数据完整性电路：每批最多 32 个数据点，Merkle 树可容纳 2^20 个批次
+        let integrity_circuit = NetworkMetricCircuit::data_integrity(
+            4,
+            32,
+            20,
+            "数据完整性电路，证明数据批次包含在已公布的 Merkle 根中".to_string(),
+        );
+        self.register_circuit(integrity_circuit);
+        
+        // 百分位数电路：中位数、p95、p99 等，最多 64 个样本
+        let percentile_circuit = NetworkMetricCircuit::percentile(
+            6,
+            64,
+            "百分位数电路，证明私有样本的中位数或任意百分位数".to_string(),
+        );
+        self.register_circuit(percentile_circuit);
+        
+        // 方差电路：最多 256 个 32 位样本
+        let variance_circuit = NetworkMetricCircuit::variance(
+            7,
+            256,
+            "方差电路，证明私有样本的平均值与总体方差".to_string(),
+        );
+        self.register_circuit(variance_circuit);
+        
+        // 差分隐私求和电路：最多 64 个样本，ε = 1，单个样本上限 100
+        let privacy_circuit = NetworkMetricCircuit::privacy_preserving(
+            8,
+            64,
+            PrivacyParameters { epsilon_millis: 1000, sensitivity: 100 },
+            "差分隐私求和电路，证明公开结果是已承诺样本之和加上由公开信标确定的拉普拉斯噪声".to_string(),
+        );
+        self.register_circuit(privacy_circuit);
+        
+        // 共识参与电路：最多证明 32 个出块，出块者树覆盖 1024 个区块
+        let participation_circuit = NetworkMetricCircuit::consensus_participation(
+            9,
+            32,
+            10,
+            "共识参与电路，证明验证者在最近 N 个区块中至少产出了 K 个而不泄露具体区块".to_string(),
+        );
+        self.register_circuit(participation_circuit);
+        
+        // 节点可靠性电路：心跳日志最多 128 条，例如 24 小时内每 15 分钟一个时隙
+        let reliability_circuit = NetworkMetricCircuit::node_reliability(
+            10,
+            128,
+            "节点可靠性电路，证明时间窗口内的在线率超过阈值而不泄露心跳时间".to_string(),
+        );
+        self.register_circuit(reliability_circuit);
+        
+        // 滚动聚合电路：24 小时窗口每 15 分钟一个样本，滚动平均覆盖最近 30 个窗口
+        let rolling_circuit = NetworkMetricCircuit::rolling_aggregation(
+            11,
+            96,
+            30,
+            "滚动聚合电路，由上一窗口的状态承诺增量证明最近 30 个时间窗口的滚动平均值".to_string(),
+        );
+        self.register_circuit(rolling_circuit);
+    }
+}
+
+impl Default for CircuitManager {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -367,6 +3254,7 @@ pub struct CircuitStatistics {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
     
     #[test]
     fn test_network_metric_circuit_creation() {
@@ -381,6 +3269,10 @@ mod tests {
         assert_eq!(circuit.circuit_type, CircuitType::NetworkMetric);
         assert_eq!(circuit.max_data_points, 10);
         assert_eq!(circuit.max_data_sources, 5);
+        assert_eq!(circuit.proof_system, ProofSystemKind::Groth16);
+
+        let circuit = circuit.with_proof_system(ProofSystemKind::Mock);
+        assert_eq!(circuit.proof_system, ProofSystemKind::Mock);
     }
     
     #[test]
@@ -452,16 +3344,678 @@ mod tests {
         let optimal = manager.select_optimal_circuit(&CircuitType::NetworkMetric, 5, 3);
         assert!(optimal.is_some());
         assert_eq!(optimal.unwrap().circuit_id, 1); // 应该选择最小的电路
+        
+        // 按电路切换证明后端
+        assert!(manager.set_proof_system(2, ProofSystemKind::Mock));
+        assert_eq!(manager.get_circuit(2).unwrap().proof_system, ProofSystemKind::Mock);
+        assert!(!manager.set_proof_system(42, ProofSystemKind::Mock));
     }
     
+    fn metric_witness(private_data: Vec<u128>, public_metric: u128, quality_score: u8) -> MetricWitness {
+        let mut witness = MetricWitness {
+            private_data,
+            reliability_scores: vec![],
+            public_metric,
+            quality_score,
+            time_window_hours: 1,
+            metric_tag: nullifier::metric_tag("block_time"),
+            window_start: 1_700_000_000,
+            max_staleness: 3600,
+            source_secret: nullifier::secret_scalar(&[7; 32]),
+            sample_counts: vec![],
+            blindings: vec![],
+            source_timestamps: vec![],
+        };
+        with_sources(&mut witness, &[90, 85, 95]);
+        witness
+    }
+
+    /// 设置数据源可靠性，样本按数据源数量尽量均分，第 j 个数据源在窗口终点前 60·(j+1) 秒报告
+    fn with_sources(witness: &mut MetricWitness, reliability_scores: &[u32]) {
+        let (n, k) = (witness.private_data.len(), reliability_scores.len());
+        witness.reliability_scores = reliability_scores.to_vec();
+        witness.sample_counts = (0..k).map(|j| n / k + usize::from(j < n % k)).collect();
+        witness.blindings = (0..k).map(|j| commitment::blinding_scalar(&[j as u8 + 1; 32])).collect();
+        witness.source_timestamps = (0..k).map(|j| witness.window_end() - 60 * (j as u64 + 1)).collect();
+    }
+
+    fn is_satisfied(circuit: &NetworkMetricCircuit, witness: MetricWitness) -> bool {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        MetricAggregationCircuit::with_witness(circuit, witness)
+            .synthesize(&mut cs)
+            .unwrap();
+        cs.is_satisfied()
+    }
+
+    #[test]
+    fn test_metric_circuit_binds_nullifier() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::new(1, 10, 5, "Test".to_string());
+        let witness = metric_witness(vec![100, 200, 300], 200, 88);
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        MetricAggregationCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+
+        // 为同一窗口另选空值符
+        let [low, _] = crate::utils::scalar_to_u128_limbs(&(witness.nullifier() + Scalar::ONE));
+        cs.set("nullifier_low/value/input", gadgets::scalar_from_u128(low));
+        assert!(!cs.is_satisfied());
+
+        // 以原空值符声明另一个时间窗口
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        MetricAggregationCircuit::with_witness(&circuit, witness).synthesize(&mut cs).unwrap();
+        cs.set("window_start/value/input", Scalar::from(1_700_003_600u64));
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_metric_circuit_matches_native_constraints() {
+        let circuit = NetworkMetricCircuit::new(1, 10, 5, "Test".to_string());
+
+        // 平均值 200，公开指标 205 位于 5% 容差内
+        assert!(is_satisfied(&circuit, metric_witness(vec![100, 200, 300], 205, 88)));
+        // 公开指标 220 超出容差
+        assert!(!is_satisfied(&circuit, metric_witness(vec![100, 200, 300], 220, 88)));
+        // 质量评分超过平均可靠性 + 10
+        let mut low_reliability = metric_witness(vec![100, 200, 300], 200, 88);
+        with_sources(&mut low_reliability, &[70, 80]);
+        assert!(!is_satisfied(&circuit, low_reliability));
+        // 质量评分超过 100
+        assert!(!is_satisfied(&circuit, metric_witness(vec![100, 200, 300], 200, 101)));
+        // 数据源不足两个
+        let mut single_source = metric_witness(vec![100, 200, 300], 200, 88);
+        with_sources(&mut single_source, &[95]);
+        assert!(!is_satisfied(&circuit, single_source));
+        // 样本超过 2^64
+        assert!(!is_satisfied(&circuit, metric_witness(vec![1u128 << 64, 200, 300], 200, 88)));
+    }
+
+    /// 随机样本：多数位于常见取值范围，少数超过 2^64
+    fn arb_sample() -> impl Strategy<Value = u128> {
+        prop_oneof![
+            19 => 1_000u128..10_000,
+            1 => (1u128 << SAMPLE_BITS)..(1u128 << SAMPLE_BITS) + 1_000,
+        ]
+    }
+
+    /// 随机指标见证：样本数、数据源数与评分可能越界，公开指标在样本平均值 ±7% 内浮动以覆盖容差边界
+    fn arb_metric_witness() -> impl Strategy<Value = MetricWitness> {
+        (
+            prop::collection::vec(arb_sample(), 0..=7),
+            prop::collection::vec(50u32..=105, 1..=4),
+            -70i128..=70,
+            prop_oneof![9 => 0u8..=100, 1 => 101u8..=110],
+        )
+            .prop_filter("每个数据源至少报告一个样本", |(samples, scores, _, _)| samples.len() >= scores.len())
+            .prop_map(|(samples, scores, per_mille, quality_score)| {
+                let average = samples.iter().sum::<u128>() / samples.len() as u128;
+                let public_metric = (average as i128 * (1000 + per_mille) / 1000) as u128;
+                let mut witness = metric_witness(samples, public_metric, quality_score);
+                with_sources(&mut witness, &scores);
+                witness
+            })
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(48))]
+
+        /// 电路恰好接受满足原生约束检查的见证
+        #[test]
+        fn prop_metric_circuit_accepts_exactly_valid_witnesses(witness in arb_metric_witness()) {
+            let circuit = NetworkMetricCircuit::new(1, 6, 3, "Test".to_string());
+            let valid = circuit.verify_constraints(
+                &witness.private_data,
+                &witness.reliability_scores,
+                witness.public_metric,
+                witness.quality_score,
+            );
+            prop_assert_eq!(is_satisfied(&circuit, witness), valid);
+        }
+    }
+
+    #[test]
+    fn test_metric_circuit_binds_sample_commitments() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::new(1, 6, 3, "Test".to_string());
+        let witness = metric_witness(vec![100, 200, 300, 400], 250, 88);
+        assert_eq!(witness.sample_counts, vec![2, 1, 1]);
+        let commitments = witness.sample_commitments();
+        assert_eq!(
+            commitments[0],
+            commitment::commit_at(witness.blindings[0], witness.source_timestamps[0], &[100, 200])
+        );
+        assert!(is_satisfied(&circuit, witness.clone()));
+
+        // 公开的承诺摘要必须与各数据源的承诺一致
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        MetricAggregationCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        let forged = commitment::digest(&[commitments[1], commitments[0], commitments[2]]);
+        let [low, _] = crate::utils::scalar_to_u128_limbs(&forged);
+        cs.set("commitment_low/value/input", gadgets::scalar_from_u128(low));
+        assert!(!cs.is_satisfied());
+
+        // 数据源的样本划分必须覆盖全部样本且每段非空
+        let mut empty_segment = witness.clone();
+        empty_segment.sample_counts = vec![3, 1, 0];
+        assert!(!is_satisfied(&circuit, empty_segment));
+        let mut uncovered = witness;
+        uncovered.sample_counts = vec![1, 1, 1];
+        assert!(!is_satisfied(&circuit, uncovered));
+    }
+
+    #[test]
+    fn test_metric_circuit_binds_freshness() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::new(1, 6, 3, "Test".to_string());
+        let witness = metric_witness(vec![100, 200, 300, 400], 250, 88);
+        assert_eq!(witness.window_end(), 1_700_003_600);
+        assert!(is_satisfied(&circuit, witness.clone()));
+
+        // 数据源时间戳晚于窗口终点或早于最大间隔
+        let mut future = witness.clone();
+        future.source_timestamps[1] = future.window_end() + 1;
+        assert!(!is_satisfied(&circuit, future));
+        let mut stale = witness.clone();
+        stale.source_timestamps[2] = stale.window_start - 1;
+        assert!(!is_satisfied(&circuit, stale));
+
+        // 更严格的最大间隔：最旧的数据源在终点前 180 秒报告
+        let mut strict = witness.clone();
+        strict.max_staleness = 180;
+        assert!(is_satisfied(&circuit, strict.clone()));
+        strict.max_staleness = 179;
+        assert!(!is_satisfied(&circuit, strict));
+
+        // 最大间隔不能超过窗口时长
+        let mut loose = witness.clone();
+        loose.max_staleness = 3601;
+        assert!(!is_satisfied(&circuit, loose));
+
+        // 窗口终点必须与起点和时长一致
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        MetricAggregationCircuit::with_witness(&circuit, witness).synthesize(&mut cs).unwrap();
+        cs.set("window_end/value/input", Scalar::from(1_700_007_200u64));
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_metric_circuit_groth16_round_trip() {
+        use bellman::groth16;
+        use bls12_381::Bls12;
+        use rand::rngs::OsRng;
+
+        let circuit = NetworkMetricCircuit::new(1, 4, 3, "Test".to_string());
+        let params = groth16::generate_random_parameters::<Bls12, _, _>(
+            MetricAggregationCircuit::blank(&circuit),
+            &mut OsRng,
+        )
+        .unwrap();
+        let pvk = groth16::prepare_verifying_key(&params.vk);
+
+        let witness = metric_witness(vec![6000, 6100, 5900, 6200], 6050, 92);
+        let public_inputs = crate::utils::public_inputs_to_scalars(&witness.public_inputs());
+        let proof = groth16::create_random_proof(
+            MetricAggregationCircuit::with_witness(&circuit, witness),
+            &params,
+            &mut OsRng,
+        )
+        .unwrap();
+
+        assert!(groth16::verify_proof(&pvk, &proof, &public_inputs).is_ok());
+
+        // 篡改公开指标后验证失败
+        let mut tampered = public_inputs.clone();
+        tampered[0] = Scalar::from(7000u64);
+        assert!(groth16::verify_proof(&pvk, &proof, &tampered).is_err());
+    }
+
+    fn integrity_witness(circuit: &NetworkMetricCircuit, timestamp: u64) -> (IntegrityWitness, Scalar) {
+        let batches: Vec<Vec<u128>> = vec![vec![6000, 6100], vec![5900, 6200, 6050], vec![42]];
+        let leaves = batches
+            .iter()
+            .map(|batch| merkle::batch_leaf(timestamp, batch))
+            .collect();
+        let tree = merkle::MerkleTree::new(circuit.tree_depth, leaves).unwrap();
+        let path = tree.path(1).unwrap();
+
+        let witness = IntegrityWitness {
+            batch: batches[1].clone(),
+            timestamp,
+            leaf_index: path.leaf_index,
+            siblings: path.sibling_scalars().unwrap(),
+            window_start: timestamp - 60,
+            window_end: timestamp + 60,
+        };
+        (witness, tree.root())
+    }
+
+    #[test]
+    fn test_integrity_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::data_integrity(9, 4, 3, "Test".to_string());
+        let satisfied = |witness: IntegrityWitness| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            DataIntegrityCircuit::with_witness(&circuit, witness)
+                .synthesize(&mut cs)
+                .unwrap();
+            cs.is_satisfied()
+        };
+
+        let (witness, root) = integrity_witness(&circuit, 1_700_000_000);
+        assert_eq!(witness.merkle_root(), root);
+        assert!(circuit.verify_integrity_constraints(&witness));
+        assert!(satisfied(witness.clone()));
+
+        // 时间戳不在公开窗口内
+        let mut late = witness.clone();
+        late.window_end = late.timestamp - 1;
+        assert!(!circuit.verify_integrity_constraints(&late));
+        assert!(!satisfied(late));
+
+        // 篡改批次后根不再一致：以原始根作为公开输入
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let mut tampered = witness.clone();
+        tampered.batch[0] += 1;
+        DataIntegrityCircuit::with_witness(&circuit, tampered)
+            .synthesize(&mut cs)
+            .unwrap();
+        let [low, _] = crate::utils::scalar_to_u128_limbs(&root);
+        cs.set("merkle_root_low/value/input", gadgets::scalar_from_u128(low));
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_integrity_circuit_groth16_round_trip() {
+        use bellman::groth16;
+        use bls12_381::Bls12;
+        use rand::rngs::OsRng;
+
+        let circuit = NetworkMetricCircuit::data_integrity(9, 4, 3, "Test".to_string());
+        let params = groth16::generate_random_parameters::<Bls12, _, _>(
+            DataIntegrityCircuit::blank(&circuit),
+            &mut OsRng,
+        )
+        .unwrap();
+        let pvk = groth16::prepare_verifying_key(&params.vk);
+
+        let (witness, _) = integrity_witness(&circuit, 1_700_000_000);
+        let public_inputs = crate::utils::public_inputs_to_scalars(&witness.public_inputs());
+        let proof = groth16::create_random_proof(
+            DataIntegrityCircuit::with_witness(&circuit, witness),
+            &params,
+            &mut OsRng,
+        )
+        .unwrap();
+        assert!(groth16::verify_proof(&pvk, &proof, &public_inputs).is_ok());
+
+        // 声称批次属于另一个时间窗口
+        let mut shifted = public_inputs.clone();
+        shifted[2] = Scalar::from(1_800_000_000u64);
+        assert!(groth16::verify_proof(&pvk, &proof, &shifted).is_err());
+    }
+
     #[test]
     fn test_public_input_spec() {
         let circuit = NetworkMetricCircuit::new(1, 10, 5, "Test".to_string());
         let spec = circuit.get_public_input_spec();
         
-        assert_eq!(spec.inputs.len(), 3);
+        assert_eq!(spec.inputs.len(), 11);
         assert_eq!(spec.inputs[0].name, "aggregated_metric");
         assert_eq!(spec.inputs[1].name, "quality_score");
         assert_eq!(spec.inputs[2].name, "time_window");
+        assert_eq!(spec.inputs[5].name, "window_end");
+        assert_eq!(spec.inputs[6].name, "max_staleness");
+        assert_eq!(spec.inputs[7].name, "nullifier_low");
+        assert_eq!(spec.inputs[8].name, "nullifier_high");
+        assert_eq!(spec.inputs[10].name, "commitment_high");
+        assert_eq!(spec.inputs[0].bit_width(), Some(SAMPLE_BITS));
+        assert_eq!(spec.inputs[2].constraints[1].to_string(), "value <= 24");
+        assert_eq!(spec.inputs[6].constraints[0].to_string(), "value <= 86400");
+
+        let inputs = |metric: u128, window: u128| {
+            vec![metric, 92, window, 7, 1_700_000_000, 1_700_003_600, 3600, 11, 13, 17, 19]
+        };
+        assert!(spec.is_satisfied_by(&inputs(6050, 1)));
+        assert!(!spec.is_satisfied_by(&inputs(0, 1)));
+        assert!(!spec.is_satisfied_by(&inputs(1u128 << 64, 1)));
+        assert!(!spec.is_satisfied_by(&inputs(6050, 25)));
+        assert!(!spec.is_satisfied_by(&[6050, 92, 1]));
+    }
+
+    #[test]
+    fn test_metric_circuit_enforces_input_spec() {
+        let circuit = NetworkMetricCircuit::new(1, 10, 5, "Test".to_string());
+
+        let mut witness = metric_witness(vec![100, 200, 300], 200, 88);
+        assert!(is_satisfied(&circuit, witness.clone()));
+
+        // 时间窗口的取值范围只在规范中声明，电路同样必须拒绝越界取值
+        witness.time_window_hours = 0;
+        assert!(!is_satisfied(&circuit, witness.clone()));
+        witness.time_window_hours = 25;
+        assert!(!is_satisfied(&circuit, witness));
+
+        // 规范收紧后，电路随之收紧
+        let mut strict = MetricAggregationCircuit::with_witness(&circuit, metric_witness(vec![100, 200, 300], 200, 88));
+        strict.input_spec.inputs[1].constraints = vec![InputConstraint::Max(80)];
+        let mut cs = bellman::gadgets::test::TestConstraintSystem::<Scalar>::new();
+        strict.synthesize(&mut cs).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_percentile_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::percentile(6, 8, "Test".to_string());
+        let samples = vec![40u128, 10, 30, 1 << 40, 20];
+        let blinding = commitment::blinding_scalar(&[4; 32]);
+        let check = |percentile: u8, public_inputs: Option<Vec<u128>>| {
+            let witness = PercentileWitness { samples: samples.clone(), percentile, blinding };
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            PercentileCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+            if let Some(inputs) = public_inputs {
+                for (spec, value) in circuit.get_public_input_spec().inputs.iter().zip(inputs) {
+                    cs.set(&format!("{}/value/input", spec.name), gadgets::scalar_from_u128(value));
+                }
+            }
+            (witness.public_inputs(), cs.is_satisfied())
+        };
+
+        let [c_low, c_high] = crate::utils::scalar_to_u128_limbs(&commitment::commit(blinding, &samples));
+        assert_eq!(check(50, None), (vec![50, 30, 5, c_low, c_high], true));
+        assert_eq!(check(20, None), (vec![20, 10, 5, c_low, c_high], true));
+        assert_eq!(check(100, None), (vec![100, 1 << 40, 5, c_low, c_high], true));
+        // 声称其他样本为中位数
+        assert!(!check(50, Some(vec![50, 20, 5, c_low, c_high])).1);
+        // 声称错误的样本数量
+        assert!(!check(50, Some(vec![50, 30, 4, c_low, c_high])).1);
+        // 声称另一组已承诺的样本
+        assert!(!check(50, Some(vec![50, 30, 5, c_low + 1, c_high])).1);
+    }
+
+    #[test]
+    fn test_variance_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::variance(7, 8, "Test".to_string());
+        let blinding = commitment::blinding_scalar(&[4; 32]);
+        let witness = VarianceWitness { samples: vec![2, 4, 4, 4, 5, 5, 7, 9], blinding };
+        assert_eq!(witness.public_inputs()[..3], [8, 5, 4]);
+        assert_eq!(witness.sample_commitment(), commitment::commit(blinding, &witness.samples));
+        assert!(circuit.verify_variance_constraints(&witness));
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        VarianceCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+
+        cs.set("variance/value/input", Scalar::from(3u64));
+        assert!(!cs.is_satisfied());
+
+        // 样本承诺不能被替换
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        VarianceCircuit::with_witness(&circuit, witness).synthesize(&mut cs).unwrap();
+        cs.set("sample_commitment_low/value/input", Scalar::from(1u64));
+        assert!(!cs.is_satisfied());
+
+        // 样本超出 32 位
+        let witness = VarianceWitness { samples: vec![1 << 32, 1], blinding };
+        assert!(!circuit.verify_variance_constraints(&witness));
+    }
+
+    #[test]
+    fn test_privacy_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let parameters = PrivacyParameters { epsilon_millis: 1000, sensitivity: 10 };
+        let circuit = NetworkMetricCircuit::privacy_preserving(8, 4, parameters, "Test".to_string());
+        let privacy_witness = |samples: Vec<u128>, beacon: u64| PrivacyWitness {
+            samples,
+            blinding: commitment::blinding_scalar(&[2; 32]),
+            noise_secret: privacy::bytes_to_scalar(&[3; 32]),
+            beacon: Scalar::from(beacon),
+            parameters,
+        };
+        let is_satisfied = |witness: PrivacyWitness| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            PrivacyCircuit::with_witness(&circuit, witness).synthesize(&mut cs).unwrap();
+            cs.is_satisfied()
+        };
+
+        let witness = privacy_witness(vec![3, 7, 10], 1);
+        assert_eq!(witness.public_inputs()[0], privacy::apply_noise(20, witness.noise()));
+        assert_eq!(witness.sample_commitment(), commitment::commit(witness.blinding, &[3, 7, 10]));
+        assert!(circuit.verify_privacy_constraints(&witness));
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        PrivacyCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+
+        // 公开结果与噪声查表结果都不能被替换
+        cs.set("noisy_sum/value/input", gadgets::scalar_from_u128(witness.noisy_sum() + 1));
+        assert!(!cs.is_satisfied());
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        PrivacyCircuit::with_witness(&circuit, witness).synthesize(&mut cs).unwrap();
+        let selected = cs.get("first geometric/bucket 0/boolean");
+        cs.set("first geometric/bucket 0/boolean", Scalar::ONE - selected);
+        assert!(!cs.is_satisfied());
+
+        // 噪声使结果为负时截断到 0
+        let clamped = (0..200)
+            .map(|beacon| privacy_witness(vec![1], beacon))
+            .find(|w| w.noise() < -1)
+            .unwrap();
+        assert_eq!(clamped.noisy_sum(), 0);
+        assert!(is_satisfied(clamped));
+
+        // 样本超出敏感度
+        let witness = privacy_witness(vec![11], 1);
+        assert!(!circuit.verify_privacy_constraints(&witness));
+        assert!(!is_satisfied(witness));
+    }
+
+    #[test]
+    fn test_participation_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        // 区块 1000..1010 中验证者产出了偏移 0、3、4、9 处的区块
+        let circuit = NetworkMetricCircuit::consensus_participation(9, 6, 4, "Test".to_string());
+        let (validator, other) = ([7; 32], [8; 32]);
+        let authors: Vec<[u8; 32]> =
+            (0..10).map(|i| if [0, 3, 4, 9].contains(&i) { validator } else { other }).collect();
+        let tree = merkle::block_author_tree(4, 1_000, &authors).unwrap();
+        let participation_witness = |signed_blocks: Vec<u64>, min_signed: u32| ParticipationWitness {
+            claim: ParticipationClaim { validator, first_block: 1_000, block_count: 10, min_signed },
+            author_root: tree.root(),
+            siblings: signed_blocks
+                .iter()
+                .map(|block| match tree.path(block.wrapping_sub(1_000)) {
+                    Ok(path) => path.sibling_scalars().unwrap(),
+                    Err(_) => vec![Scalar::ZERO; 4],
+                })
+                .collect(),
+            signed_blocks,
+        };
+        let is_satisfied = |witness: ParticipationWitness| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            ParticipationCircuit::with_witness(&circuit, witness).synthesize(&mut cs).is_ok() && cs.is_satisfied()
+        };
+
+        let witness = participation_witness(vec![1_000, 1_003, 1_004, 1_009], 4);
+        let key_limb = u128::from_le_bytes([7; 16]);
+        assert_eq!(witness.public_inputs()[..5], [1_000, 10, 4, key_limb, key_limb]);
+        assert!(circuit.verify_participation_constraints(&witness));
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        ParticipationCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        cs.set("min_signed/value/input", Scalar::from(5u64));
+        assert!(!cs.is_satisfied());
+
+        // 证明绑定验证者公钥与出块者树根，不能转让给其他验证者
+        for input in ["validator_key_low/value/input", "author_root_low/value/input"] {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            ParticipationCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+            cs.set(input, Scalar::from(8u64));
+            assert!(!cs.is_satisfied());
+        }
+        let mut transferred = witness.clone();
+        transferred.claim.validator = other;
+        assert!(!circuit.verify_participation_constraints(&transferred));
+        assert!(!is_satisfied(transferred));
+
+        // 重复的区块只计一次，且不能被标记为新区块
+        let witness = participation_witness(vec![1_000, 1_003, 1_003, 1_009], 4);
+        assert_eq!(witness.signed_count(), 3);
+        assert!(!circuit.verify_participation_constraints(&witness));
+        assert!(!is_satisfied(witness.clone()));
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        ParticipationCircuit::with_witness(&circuit, participation_witness(witness.signed_blocks, 3))
+            .synthesize(&mut cs)
+            .unwrap();
+        assert!(cs.is_satisfied());
+        cs.set("distinct blocks/index 2/new index/boolean", Scalar::ONE);
+        assert!(!cs.is_satisfied());
+
+        // 他人产出的区块、超出范围的区块或顺序颠倒
+        let witness = participation_witness(vec![1_001], 1);
+        assert!(!circuit.verify_participation_constraints(&witness));
+        assert!(!is_satisfied(witness));
+        assert!(!is_satisfied(participation_witness(vec![999, 1_003], 2)));
+        assert!(!is_satisfied(participation_witness(vec![1_003, 1_010], 2)));
+        assert!(!is_satisfied(participation_witness(vec![1_004, 1_003], 2)));
+    }
+
+    #[test]
+    fn test_uptime_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::node_reliability(10, 8, "Test".to_string());
+        let start = 1_700_000_000;
+        let uptime_witness = |offsets: &[u64], min_uptime_percent: u8| UptimeWitness {
+            claim: UptimeClaim {
+                validator: [4; 32],
+                window_start: start,
+                heartbeat_interval: 900,
+                slot_count: 8,
+                min_uptime_percent,
+            },
+            heartbeats: offsets.iter().map(|offset| start + offset).collect(),
+            blinding: commitment::blinding_scalar(&[3; 32]),
+        };
+        let is_satisfied = |witness: UptimeWitness| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            UptimeCircuit::with_witness(&circuit, witness).synthesize(&mut cs).is_ok() && cs.is_satisfied()
+        };
+
+        // 8 个时隙中的 6 个有心跳（75%），同一时隙的两次心跳只计一次
+        let offsets = [0, 899, 950, 2_700, 3_700, 4_600, 7_199];
+        let witness = uptime_witness(&offsets, 75);
+        assert_eq!(witness.slots().into_iter().flatten().collect::<Vec<_>>(), vec![0, 0, 1, 3, 4, 5, 7]);
+        assert_eq!(witness.covered_slots(), 6);
+        assert!(circuit.verify_uptime_constraints(&witness));
+        assert!(is_satisfied(witness));
+
+        let witness = uptime_witness(&offsets, 76);
+        assert!(!circuit.verify_uptime_constraints(&witness));
+        assert!(!is_satisfied(witness));
+
+        // 心跳早于窗口起点或晚于最后一个时隙
+        let mut early = uptime_witness(&offsets, 50);
+        early.heartbeats[0] = start - 1;
+        assert!(!circuit.verify_uptime_constraints(&early));
+        assert!(!is_satisfied(early));
+        assert!(!is_satisfied(uptime_witness(&[0, 7_200], 25)));
+
+        // 声称更短的时隙或更少的时隙数量
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        UptimeCircuit::with_witness(&circuit, uptime_witness(&offsets, 75)).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        cs.set("slot_count/value/input", Scalar::from(7u64));
+        assert!(!cs.is_satisfied());
+
+        // 日志承诺绑定验证者公钥，其他验证者不能复用
+        let mut transferred = uptime_witness(&offsets, 75);
+        transferred.claim.validator = [5; 32];
+        assert_ne!(transferred.log_commitment(), uptime_witness(&offsets, 75).log_commitment());
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        UptimeCircuit::with_witness(&circuit, uptime_witness(&offsets, 75)).synthesize(&mut cs).unwrap();
+        cs.set("validator_key_high/value/input", Scalar::from(5u64));
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_rolling_circuit_constraints() {
+        use bellman::gadgets::test::TestConstraintSystem;
+
+        let circuit = NetworkMetricCircuit::rolling_aggregation(11, 4, 3, "Test".to_string());
+        let genesis = RollingState::genesis(nullifier::metric_tag("block_time"), 24, 1_700_000_000, 3);
+        let rolling_witness = |previous: &RollingState, samples: Vec<u128>| RollingWitness {
+            previous: previous.clone(),
+            samples,
+            sample_blinding: commitment::blinding_scalar(&[2; 32]),
+            state_blinding: [3; 32],
+        };
+        let is_satisfied = |witness: RollingWitness| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            RollingCircuit::with_witness(&circuit, witness).synthesize(&mut cs).is_ok() && cs.is_satisfied()
+        };
+
+        // 两个窗口之后：覆盖 2 个窗口、3 个样本，平均值 (10 + 20 + 60) / 3
+        let first = rolling_witness(&genesis, vec![10, 20]);
+        assert!(circuit.verify_rolling_constraints(&first));
+        assert!(is_satisfied(first.clone()));
+        let witness = rolling_witness(&first.state(), vec![60]);
+        assert_eq!(witness.public_inputs()[1..6], [1_700_086_400, 24, 2, 3, 30]);
+        assert_eq!(witness.public_inputs()[6..8], first.public_inputs()[8..10]);
+        assert!(circuit.verify_rolling_constraints(&witness));
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        RollingCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        assert!(cs.num_constraints() <= circuit.estimate_complexity(4, 0).constraint_count);
+
+        // 滚动平均值、覆盖窗口数与窗口起点都不能被替换
+        cs.set("rolling_average/value/input", Scalar::from(31u64));
+        assert!(!cs.is_satisfied());
+        for (input, value) in [("covered_windows", 3u64), ("window_start", 1_700_000_000)] {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            RollingCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+            cs.set(&format!("{}/value/input", input), Scalar::from(value));
+            assert!(!cs.is_satisfied());
+        }
+
+        // 空窗口不能被计为已覆盖
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        RollingCircuit::with_witness(&circuit, witness.clone()).synthesize(&mut cs).unwrap();
+        cs.set("covered window 0/occupied/boolean", Scalar::ONE);
+        assert!(!cs.is_satisfied());
+
+        // 篡改历史窗口后，上一状态与公开的承诺不一致
+        let mut tampered = witness.clone();
+        tampered.previous.windows[2].sum += 1;
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        RollingCircuit::with_witness(&circuit, tampered).synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        let [low, high] = crate::utils::scalar_to_u128_limbs(&witness.previous.commitment());
+        cs.set("previous_state_low/value/input", gadgets::scalar_from_u128(low));
+        cs.set("previous_state_high/value/input", gadgets::scalar_from_u128(high));
+        assert!(!cs.is_satisfied());
+
+        // 新窗口为空、样本超出范围或窗口数量与电路不一致
+        assert!(!circuit.verify_rolling_constraints(&rolling_witness(&genesis, vec![])));
+        assert!(!is_satisfied(rolling_witness(&genesis, vec![])));
+        let witness = rolling_witness(&genesis, vec![1 << SAMPLE_BITS]);
+        assert!(!circuit.verify_rolling_constraints(&witness));
+        assert!(!is_satisfied(witness));
+        let short = RollingState::genesis(genesis.metric_tag, 24, 1_700_000_000, 2);
+        assert!(!circuit.verify_rolling_constraints(&rolling_witness(&short, vec![1])));
+        assert!(!is_satisfied(rolling_witness(&short, vec![1])));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,130 @@
+// 样本承诺模块 - 数据源对其私有样本的隐藏承诺
+//
+// 数据源 j 公布承诺 C_j = commit(r_j, samples_j)：以盲化因子 r_j 为初始状态，依次以 Poseidon 吸收每个样本，
+// 首个样本附加 2^65 标记、最后一个样本附加 2^64 标记，因此承诺无法被另行解释为某个前缀或后缀的承诺。
+// 指标数据源的承诺还在首个样本中附加 t_j · 2^66 绑定其报告时间戳 t_j（差分隐私样本不带时间戳，t_j = 0）。
+// 指标证明公开各数据源承诺的链式摘要 D = H(...H(H(0, C_1), C_2)..., C_k)，
+// 证明聚合值恰好由这些已承诺的样本计算得到，而不泄露样本本身。
+use anyhow::Result;
+use bls12_381::Scalar;
+use ff::Field;
+use serde::{Deserialize, Serialize};
+
+use crate::circuits::SAMPLE_BITS;
+use crate::{gadgets, nullifier, poseidon, ZKProofError};
+
+/// 数据源最后一个样本的标记
+pub const LAST_SAMPLE_FLAG: u128 = 1 << SAMPLE_BITS;
+/// 数据源第一个样本的标记
+pub const FIRST_SAMPLE_FLAG: u128 = 1 << (SAMPLE_BITS + 1);
+/// 数据源时间戳在首个样本中的偏移（位于样本与两个标记之上）
+pub const TIMESTAMP_SHIFT: usize = SAMPLE_BITS + 2;
+
+/// 数据源样本承诺的打开信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleOpening {
+    /// 该数据源贡献的样本数量
+    pub sample_count: usize,
+    /// 盲化因子
+    pub blinding: [u8; 32],
+}
+
+impl SampleOpening {
+    /// 计算承诺（标量的规范小端序字节表示）
+    pub fn commit(&self, samples: &[u128]) -> Result<[u8; 32]> {
+        self.commit_at(0, samples)
+    }
+
+    /// 计算绑定数据源报告时间戳的承诺
+    pub fn commit_at(&self, timestamp: u64, samples: &[u128]) -> Result<[u8; 32]> {
+        if samples.is_empty() || samples.len() != self.sample_count {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "commitment opening expects {} samples, got {}",
+                self.sample_count,
+                samples.len()
+            ))
+            .into());
+        }
+        Ok(commit_at(blinding_scalar(&self.blinding), timestamp, samples).to_bytes())
+    }
+}
+
+/// 将盲化因子规约为标量域元素
+pub fn blinding_scalar(blinding: &[u8; 32]) -> Scalar {
+    nullifier::secret_scalar(blinding)
+}
+
+/// 对样本的承诺，samples 不能为空且每个样本必须小于 2^64
+pub fn commit(blinding: Scalar, samples: &[u128]) -> Scalar {
+    commit_at(blinding, 0, samples)
+}
+
+/// 对数据源在 timestamp 时刻报告的样本的承诺
+pub fn commit_at(blinding: Scalar, timestamp: u64, samples: &[u128]) -> Scalar {
+    samples.iter().enumerate().fold(blinding, |state, (i, sample)| {
+        let mut absorbed = *sample;
+        if i + 1 == samples.len() {
+            absorbed += LAST_SAMPLE_FLAG;
+        }
+        let mut absorbed = gadgets::scalar_from_u128(absorbed);
+        if i == 0 {
+            // t · 2^66 可能超出 u128，在标量域中相加
+            absorbed += gadgets::scalar_from_u128(FIRST_SAMPLE_FLAG)
+                + Scalar::from(timestamp) * gadgets::scalar_from_u128(1 << TIMESTAMP_SHIFT);
+        }
+        poseidon::hash2(state, absorbed)
+    })
+}
+
+/// 按数据源顺序计算各承诺的链式摘要
+pub fn digest(commitments: &[Scalar]) -> Scalar {
+    commitments
+        .iter()
+        .fold(Scalar::ZERO, |acc, commitment| poseidon::hash2(acc, *commitment))
+}
+
+/// 解码已公布的承诺，拒绝非规范编码
+pub fn commitment_scalars(commitments: &[[u8; 32]]) -> Result<Vec<Scalar>> {
+    commitments
+        .iter()
+        .map(|bytes| {
+            Option::from(Scalar::from_bytes(bytes)).ok_or_else(|| {
+                ZKProofError::InvalidInputData("non-canonical sample commitment".to_string()).into()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commitment_binding() {
+        let blinding = blinding_scalar(&[5; 32]);
+        let commitment = commit(blinding, &[6000, 6100]);
+
+        assert_ne!(commitment, commit(blinding_scalar(&[6; 32]), &[6000, 6100]));
+        assert_ne!(commitment, commit(blinding, &[6100, 6000]));
+        assert_ne!(commitment, commit(blinding, &[6000]));
+        // 以前缀状态作为盲化因子无法把承诺解释为后缀的承诺
+        let prefix = poseidon::hash2(blinding, gadgets::scalar_from_u128(6000 + FIRST_SAMPLE_FLAG));
+        assert_ne!(commitment, commit(prefix, &[6100]));
+
+        let opening = SampleOpening { sample_count: 2, blinding: [5; 32] };
+        assert_eq!(opening.commit(&[6000, 6100]).unwrap(), commitment.to_bytes());
+        assert!(opening.commit(&[6000]).is_err());
+
+        // 时间戳绑定在承诺中，且不带时间戳的承诺即时间戳为 0 的承诺
+        let reported = commit_at(blinding, 1_700_000_000, &[6000, 6100]);
+        assert_ne!(reported, commitment);
+        assert_ne!(reported, commit_at(blinding, 1_700_000_001, &[6000, 6100]));
+        assert_eq!(opening.commit_at(1_700_000_000, &[6000, 6100]).unwrap(), reported.to_bytes());
+        assert_eq!(commit_at(blinding, 0, &[6000, 6100]), commitment);
+
+        let other = commit(blinding, &[42]);
+        assert_ne!(digest(&[commitment, other]), digest(&[other, commitment]));
+        assert_eq!(commitment_scalars(&[commitment.to_bytes()]).unwrap(), vec![commitment]);
+        assert!(commitment_scalars(&[[0xff; 32]]).is_err());
+    }
+}
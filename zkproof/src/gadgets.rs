@@ -0,0 +1,601 @@
+// 电路组件模块 - 供各 bellman 电路复用的约束构件
+use bellman::gadgets::boolean::{AllocatedBit, Boolean};
+use bellman::gadgets::num::AllocatedNum;
+use bellman::{ConstraintSystem, LinearCombination, SynthesisError, Variable};
+use bls12_381::Scalar;
+use ff::{Field, PrimeField};
+
+/// 带赋值的线性表达式
+///
+/// 线性运算（加、减、数乘）不产生新的约束，只有乘法和范围检查才会分配新变量。
+#[derive(Clone)]
+pub struct LinearExpr {
+    lc: LinearCombination<Scalar>,
+    value: Option<Scalar>,
+}
+
+impl LinearExpr {
+    /// 零表达式
+    pub fn zero() -> Self {
+        Self {
+            lc: LinearCombination::zero(),
+            value: Some(Scalar::ZERO),
+        }
+    }
+
+    /// 常量表达式
+    pub fn constant<CS: ConstraintSystem<Scalar>>(value: Scalar) -> Self {
+        Self {
+            lc: LinearCombination::zero() + (value, CS::one()),
+            value: Some(value),
+        }
+    }
+
+    /// 由单个变量构造表达式
+    pub fn from_variable(variable: Variable, value: Option<Scalar>) -> Self {
+        Self {
+            lc: LinearCombination::zero() + variable,
+            value,
+        }
+    }
+
+    /// 由布尔变量构造表达式（取值 0 或 1）
+    pub fn from_bit(bit: &AllocatedBit) -> Self {
+        Self::from_variable(
+            bit.get_variable(),
+            bit.get_value().map(|b| if b { Scalar::ONE } else { Scalar::ZERO }),
+        )
+    }
+
+    /// 线性组合
+    pub fn lc(&self) -> LinearCombination<Scalar> {
+        self.lc.clone()
+    }
+
+    /// 当前赋值（电路参数生成阶段为 None）
+    pub fn value(&self) -> Option<Scalar> {
+        self.value
+    }
+
+    /// 相加
+    pub fn add(&self, other: &LinearExpr) -> Self {
+        Self {
+            lc: self.lc.clone() + &other.lc,
+            value: self.value.zip(other.value).map(|(a, b)| a + b),
+        }
+    }
+
+    /// 相减
+    pub fn sub(&self, other: &LinearExpr) -> Self {
+        Self {
+            lc: self.lc.clone() - &other.lc,
+            value: self.value.zip(other.value).map(|(a, b)| a - b),
+        }
+    }
+
+    /// 数乘
+    pub fn scale(&self, coeff: Scalar) -> Self {
+        let mut lc = LinearCombination::zero();
+        for (variable, c) in self.lc.as_ref() {
+            lc = lc + (*c * coeff, *variable);
+        }
+        Self {
+            lc,
+            value: self.value.map(|v| v * coeff),
+        }
+    }
+
+    /// 加上常量
+    pub fn add_constant<CS: ConstraintSystem<Scalar>>(&self, constant: Scalar) -> Self {
+        self.add(&Self::constant::<CS>(constant))
+    }
+}
+
+/// 将 u128 转换为标量域元素
+pub fn scalar_from_u128(value: u128) -> Scalar {
+    Scalar::from_u128(value)
+}
+
+/// 将标量转换为 u128（超出范围时返回 None）
+pub fn scalar_to_u128(value: &Scalar) -> Option<u128> {
+    let repr = value.to_repr();
+    if repr[16..].iter().any(|b| *b != 0) {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&repr[..16]);
+    Some(u128::from_le_bytes(bytes))
+}
+
+/// 分配私有见证变量
+pub fn alloc_witness<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    value: Option<Scalar>,
+) -> Result<LinearExpr, SynthesisError> {
+    let variable = cs.alloc(|| "witness", || value.ok_or(SynthesisError::AssignmentMissing))?;
+    Ok(LinearExpr::from_variable(variable, value))
+}
+
+/// 分配公开输入变量
+pub fn alloc_input<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    value: Option<Scalar>,
+) -> Result<LinearExpr, SynthesisError> {
+    let variable = cs.alloc_input(|| "input", || value.ok_or(SynthesisError::AssignmentMissing))?;
+    Ok(LinearExpr::from_variable(variable, value))
+}
+
+/// 分配布尔见证变量
+pub fn alloc_bit<CS: ConstraintSystem<Scalar>>(
+    cs: CS,
+    value: Option<bool>,
+) -> Result<AllocatedBit, SynthesisError> {
+    AllocatedBit::alloc(cs, value)
+}
+
+/// 约束 a * b = c
+pub fn enforce_product<CS: ConstraintSystem<Scalar>>(
+    cs: &mut CS,
+    annotation: &'static str,
+    a: &LinearExpr,
+    b: &LinearExpr,
+    c: &LinearExpr,
+) {
+    cs.enforce(
+        || annotation,
+        |lc| lc + &a.lc,
+        |lc| lc + &b.lc,
+        |lc| lc + &c.lc,
+    );
+}
+
+/// 乘法：分配 a * b 的结果并约束其正确性
+pub fn mul<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    a: &LinearExpr,
+    b: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    let product = alloc_witness(
+        cs.namespace(|| "product"),
+        a.value().zip(b.value()).map(|(a, b)| a * b),
+    )?;
+    enforce_product(&mut cs, "multiplication", a, b, &product);
+    Ok(product)
+}
+
+/// 将线性表达式固化为单个新变量，用于截断不断增长的线性组合
+pub fn materialize<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    expr: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    let variable = alloc_witness(cs.namespace(|| "materialized"), expr.value())?;
+    enforce_equal(&mut cs, "materialize", &variable, expr);
+    Ok(variable)
+}
+
+/// 条件选择：condition 为 1 时返回 if_true，否则返回 if_false
+///
+/// condition 必须已被约束为布尔值。
+pub fn select<CS: ConstraintSystem<Scalar>>(
+    cs: CS,
+    condition: &LinearExpr,
+    if_true: &LinearExpr,
+    if_false: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    // if_false + condition * (if_true - if_false)
+    let delta = mul(cs, condition, &if_true.sub(if_false))?;
+    Ok(if_false.add(&delta))
+}
+
+/// 约束 a = b
+pub fn enforce_equal<CS: ConstraintSystem<Scalar>>(
+    cs: &mut CS,
+    annotation: &'static str,
+    a: &LinearExpr,
+    b: &LinearExpr,
+) {
+    cs.enforce(
+        || annotation,
+        |lc| lc + &a.lc - &b.lc,
+        |lc| lc + CS::one(),
+        |lc| lc,
+    );
+}
+
+/// 约束表达式取值位于 [0, 2^bits) 区间，返回小端序的比特分解
+///
+/// 若见证值超出范围，分解出的比特无法重构原值，约束系统将不可满足。
+pub fn enforce_bit_length<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    expr: &LinearExpr,
+    bits: usize,
+) -> Result<Vec<AllocatedBit>, SynthesisError> {
+    assert!(bits < Scalar::CAPACITY as usize, "range check exceeds field capacity");
+
+    let bit_values = expr.value().map(|v| scalar_to_bits_le(&v));
+
+    let mut allocated = Vec::with_capacity(bits);
+    let mut packed = LinearCombination::zero();
+    let mut coeff = Scalar::ONE;
+    for i in 0..bits {
+        let bit = AllocatedBit::alloc(
+            cs.namespace(|| format!("bit {}", i)),
+            bit_values.as_ref().map(|b| b[i]),
+        )?;
+        packed = packed + (coeff, bit.get_variable());
+        coeff = coeff.double();
+        allocated.push(bit);
+    }
+
+    cs.enforce(
+        || "bit decomposition",
+        |lc| lc + &packed,
+        |lc| lc + CS::one(),
+        |lc| lc + &expr.lc,
+    );
+
+    Ok(allocated)
+}
+
+/// 约束 expr >= bound
+///
+/// 通过分解 expr - bound 实现：若 expr < bound，差值在域中是一个极大的值，无法通过范围检查。
+pub fn enforce_at_least<CS: ConstraintSystem<Scalar>>(
+    cs: CS,
+    expr: &LinearExpr,
+    bound: u128,
+    bits: usize,
+) -> Result<(), SynthesisError> {
+    let bound = LinearExpr::constant::<CS>(scalar_from_u128(bound));
+    enforce_bit_length(cs, &expr.sub(&bound), bits).map(|_| ())
+}
+
+/// 约束 expr <= bound
+///
+/// 要求 expr 已被约束在 [0, 2^bits) 区间内，且 bound < 2^bits。
+pub fn enforce_at_most<CS: ConstraintSystem<Scalar>>(
+    cs: CS,
+    expr: &LinearExpr,
+    bound: u128,
+    bits: usize,
+) -> Result<(), SynthesisError> {
+    assert!(bits >= 128 || bound >> bits == 0, "upper bound exceeds range width");
+
+    let bound = LinearExpr::constant::<CS>(scalar_from_u128(bound));
+    enforce_bit_length(cs, &bound.sub(expr), bits).map(|_| ())
+}
+
+/// 约束 min <= expr <= max，其中 max < 2^bits
+pub fn enforce_range<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    expr: &LinearExpr,
+    min: u128,
+    max: u128,
+    bits: usize,
+) -> Result<(), SynthesisError> {
+    enforce_bit_length(cs.namespace(|| "width"), expr, bits)?;
+    enforce_at_least(cs.namespace(|| "lower bound"), expr, min, bits)?;
+    enforce_at_most(cs.namespace(|| "upper bound"), expr, max, bits)
+}
+
+/// 比较：返回 a < b 的布尔值表达式
+///
+/// 要求 a 与 b 均已被约束在 [0, 2^bits) 区间内。a - b + 2^bits 落在 [1, 2^(bits+1)) 区间，
+/// 其最高位为 1 当且仅当 a >= b。
+pub fn less_than<CS: ConstraintSystem<Scalar>>(
+    cs: CS,
+    a: &LinearExpr,
+    b: &LinearExpr,
+    bits: usize,
+) -> Result<LinearExpr, SynthesisError> {
+    let offset = Scalar::from(2u64).pow_vartime(&[bits as u64, 0, 0, 0]);
+    let shifted = a.sub(b).add_constant::<CS>(offset);
+    let decomposition = enforce_bit_length(cs, &shifted, bits + 1)?;
+    let at_least = LinearExpr::from_bit(&decomposition[bits]);
+    Ok(LinearExpr::constant::<CS>(Scalar::ONE).sub(&at_least))
+}
+
+/// Batcher 奇偶归并排序网络的比较器序列
+///
+/// 每个比较器 (i, j)（i < j）将较小值放在位置 i。适用于任意长度：
+/// 相当于在补齐到 2 的幂次的网络中删去涉及越界位置的比较器。
+pub fn sorting_network(len: usize) -> Vec<(usize, usize)> {
+    let mut comparators = Vec::new();
+    let mut p = 1;
+    while p < len {
+        let mut k = p;
+        while k >= 1 {
+            let mut j = k % p;
+            while j + k < len {
+                for i in 0..k.min(len - j - k) {
+                    if (i + j) / (2 * p) == (i + j + k) / (2 * p) {
+                        comparators.push((i + j, i + j + k));
+                    }
+                }
+                j += 2 * k;
+            }
+            k /= 2;
+        }
+        p *= 2;
+    }
+    comparators
+}
+
+/// 在电路内按升序排序，每个元素必须已被约束在 [0, 2^bits) 区间内
+///
+/// 每个比较器需要一次比较（bits + 1 个比特）与一次条件选择，排序结果是输入的一个置换。
+pub fn sort<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    values: &[LinearExpr],
+    bits: usize,
+) -> Result<Vec<LinearExpr>, SynthesisError> {
+    let mut sorted = values.to_vec();
+    for (n, (i, j)) in sorting_network(values.len()).into_iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("comparator {}", n));
+        let swap = less_than(cs.namespace(|| "compare"), &sorted[j], &sorted[i], bits)?;
+        let low = select(cs.namespace(|| "low"), &swap, &sorted[j], &sorted[i])?;
+        let high = sorted[i].add(&sorted[j]).sub(&low);
+        sorted[i] = low;
+        sorted[j] = high;
+    }
+    Ok(sorted)
+}
+
+/// 整数带余除法：约束 numerator = quotient * divisor + remainder 且 0 <= remainder < divisor
+///
+/// 被除数与除数的见证值必须能表示为 u128。
+pub fn div_rem<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    numerator: &LinearExpr,
+    divisor: &LinearExpr,
+    quotient_bits: usize,
+    divisor_bits: usize,
+) -> Result<(LinearExpr, LinearExpr), SynthesisError> {
+    let native = numerator
+        .value()
+        .zip(divisor.value())
+        .map(|(n, d)| {
+            let n = scalar_to_u128(&n).ok_or(SynthesisError::Unsatisfiable)?;
+            let d = scalar_to_u128(&d).ok_or(SynthesisError::Unsatisfiable)?;
+            if d == 0 {
+                return Err(SynthesisError::DivisionByZero);
+            }
+            Ok((n / d, n % d))
+        })
+        .transpose()?;
+
+    let quotient = alloc_witness(
+        cs.namespace(|| "quotient"),
+        native.map(|(q, _)| scalar_from_u128(q)),
+    )?;
+    let remainder = alloc_witness(
+        cs.namespace(|| "remainder"),
+        native.map(|(_, r)| scalar_from_u128(r)),
+    )?;
+
+    // quotient * divisor = numerator - remainder
+    enforce_product(
+        &mut cs,
+        "division",
+        &quotient,
+        divisor,
+        &numerator.sub(&remainder),
+    );
+
+    enforce_bit_length(cs.namespace(|| "quotient range"), &quotient, quotient_bits)?;
+    enforce_bit_length(cs.namespace(|| "remainder range"), &remainder, divisor_bits)?;
+    // divisor - remainder - 1 >= 0
+    let slack = divisor.sub(&remainder).add_constant::<CS>(-Scalar::ONE);
+    enforce_bit_length(cs.namespace(|| "remainder below divisor"), &slack, divisor_bits)?;
+
+    Ok((quotient, remainder))
+}
+
+/// 规范比特分解：返回域元素小端序的全部比特
+///
+/// 同时约束比特表示的整数小于域的模数，因此分解唯一，证明者无法以 value + p 的表示另选比特。
+pub fn to_bits_le_strict<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    expr: &LinearExpr,
+) -> Result<Vec<LinearExpr>, SynthesisError> {
+    let num = AllocatedNum::alloc(cs.namespace(|| "value"), || {
+        expr.value().ok_or(SynthesisError::AssignmentMissing)
+    })?;
+    enforce_equal(
+        &mut cs,
+        "value matches",
+        &LinearExpr::from_variable(num.get_variable(), expr.value()),
+        expr,
+    );
+
+    let bits = num.to_bits_le_strict(cs.namespace(|| "bits"))?;
+    Ok(bits
+        .iter()
+        .map(|bit: &Boolean| LinearExpr {
+            lc: bit.lc(CS::one(), Scalar::ONE),
+            value: bit.get_value().map(|b| if b { Scalar::ONE } else { Scalar::ZERO }),
+        })
+        .collect())
+}
+
+/// 由小端序比特重构整数
+pub fn pack_bits(bits: &[LinearExpr]) -> LinearExpr {
+    let mut coeff = Scalar::ONE;
+    bits.iter().fold(LinearExpr::zero(), |acc, bit| {
+        let packed = acc.add(&bit.scale(coeff));
+        coeff = coeff.double();
+        packed
+    })
+}
+
+/// 区间查找：返回满足 thresholds[j] <= value 的 j 的个数
+///
+/// thresholds 必须单调不减且均不超过 2^bits，value 必须已被约束在 [0, 2^bits) 区间内。
+/// 以独热向量选出 value 所在的区间 [T_k, T_{k+1})（T_0 = 0，T_{K+1} = 2^bits），
+/// 约束数量约为阈值个数加两次 bits + 1 位的范围检查；区间互不相交，因此结果唯一。
+pub fn bucket<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    value: &LinearExpr,
+    thresholds: &[u128],
+    bits: usize,
+) -> Result<LinearExpr, SynthesisError> {
+    assert!(bits < 128, "bucket width exceeds u128");
+    let native = value
+        .value()
+        .map(|v| scalar_to_u128(&v).map(|v| thresholds.iter().filter(|t| **t <= v).count()))
+        .map(|k| k.ok_or(SynthesisError::Unsatisfiable))
+        .transpose()?;
+
+    let mut selected = LinearExpr::zero();
+    let mut index = LinearExpr::zero();
+    let mut lower = LinearExpr::zero();
+    let mut upper = LinearExpr::zero();
+    for k in 0..=thresholds.len() {
+        let bit = alloc_bit(cs.namespace(|| format!("bucket {}", k)), native.map(|n| n == k))?;
+        let bit = LinearExpr::from_bit(&bit);
+        let low = if k == 0 { 0 } else { thresholds[k - 1] };
+        let high = thresholds.get(k).copied().unwrap_or(1 << bits);
+        selected = selected.add(&bit);
+        index = index.add(&bit.scale(Scalar::from(k as u64)));
+        lower = lower.add(&bit.scale(scalar_from_u128(low)));
+        upper = upper.add(&bit.scale(scalar_from_u128(high)));
+    }
+
+    enforce_equal(&mut cs, "one bucket selected", &selected, &LinearExpr::constant::<CS>(Scalar::ONE));
+    // lower <= value <= upper - 1
+    enforce_bit_length(cs.namespace(|| "above lower bound"), &value.sub(&lower), bits + 1)?;
+    enforce_bit_length(
+        cs.namespace(|| "below upper bound"),
+        &upper.sub(value).add_constant::<CS>(-Scalar::ONE),
+        bits + 1,
+    )?;
+
+    Ok(index)
+}
+
+/// 标量的小端序比特表示
+fn scalar_to_bits_le(value: &Scalar) -> Vec<bool> {
+    value
+        .to_repr()
+        .iter()
+        .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman::gadgets::test::TestConstraintSystem;
+
+    #[test]
+    fn test_bit_length_accepts_in_range_value() {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let value = alloc_witness(cs.namespace(|| "value"), Some(Scalar::from(255u64))).unwrap();
+        enforce_bit_length(cs.namespace(|| "range"), &value, 8).unwrap();
+        assert!(cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_bit_length_rejects_out_of_range_value() {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let value = alloc_witness(cs.namespace(|| "value"), Some(Scalar::from(256u64))).unwrap();
+        enforce_bit_length(cs.namespace(|| "range"), &value, 8).unwrap();
+        assert!(!cs.is_satisfied());
+
+        // 负数在域中表现为一个极大的值，同样不能通过范围检查
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let value = alloc_witness(cs.namespace(|| "value"), Some(-Scalar::ONE)).unwrap();
+        enforce_bit_length(cs.namespace(|| "range"), &value, 8).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_range() {
+        let check = |value: u64| {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            let value = alloc_witness(cs.namespace(|| "value"), Some(Scalar::from(value))).unwrap();
+            enforce_range(cs.namespace(|| "range"), &value, 1, 24, 8).unwrap();
+            cs.is_satisfied()
+        };
+
+        assert!(check(1));
+        assert!(check(24));
+        assert!(!check(0));
+        assert!(!check(25));
+        assert!(!check(300));
+    }
+
+    #[test]
+    fn test_sorting_network_sorts() {
+        // 0-1 原理：网络能排序所有 0/1 序列即能排序任意序列
+        for len in 1..=12usize {
+            let network = sorting_network(len);
+            for pattern in 0u32..1 << len {
+                let mut values: Vec<u32> = (0..len).map(|i| (pattern >> i) & 1).collect();
+                for (i, j) in &network {
+                    if values[*i] > values[*j] {
+                        values.swap(*i, *j);
+                    }
+                }
+                assert!(values.windows(2).all(|w| w[0] <= w[1]), "len {} pattern {:b}", len, pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort_gadget() {
+        let input = [9u64, 3, 250, 3, 0, 17, 42];
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let values: Vec<LinearExpr> = input
+            .iter()
+            .enumerate()
+            .map(|(i, v)| alloc_witness(cs.namespace(|| format!("value {}", i)), Some(Scalar::from(*v))).unwrap())
+            .collect();
+        let sorted = sort(cs.namespace(|| "sort"), &values, 8).unwrap();
+
+        assert!(cs.is_satisfied());
+        let mut expected = input.to_vec();
+        expected.sort_unstable();
+        let actual: Vec<Option<Scalar>> = sorted.iter().map(|v| v.value()).collect();
+        let expected: Vec<Option<Scalar>> = expected.into_iter().map(|v| Some(Scalar::from(v))).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let n = alloc_witness(cs.namespace(|| "n"), Some(Scalar::from(605u64))).unwrap();
+        let d = alloc_witness(cs.namespace(|| "d"), Some(Scalar::from(4u64))).unwrap();
+        let (q, r) = div_rem(cs.namespace(|| "div"), &n, &d, 16, 8).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(q.value(), Some(Scalar::from(151u64)));
+        assert_eq!(r.value(), Some(Scalar::from(1u64)));
+    }
+
+    #[test]
+    fn test_strict_bits_and_bucket() {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let x = alloc_witness(cs.namespace(|| "x"), Some(-Scalar::ONE)).unwrap();
+        let bits = to_bits_le_strict(cs.namespace(|| "bits"), &x).unwrap();
+        assert_eq!(bits.len(), Scalar::NUM_BITS as usize);
+        assert_eq!(pack_bits(&bits).value(), Some(-Scalar::ONE));
+        assert!(cs.is_satisfied());
+
+        let thresholds = [10, 20, 20, 40];
+        for (value, expected) in [(0u64, 0u64), (9, 0), (10, 1), (25, 3), (40, 4), (255, 4)] {
+            let mut cs = TestConstraintSystem::<Scalar>::new();
+            let v = alloc_witness(cs.namespace(|| "v"), Some(Scalar::from(value))).unwrap();
+            let k = bucket(cs.namespace(|| "bucket"), &v, &thresholds, 8).unwrap();
+            assert!(cs.is_satisfied());
+            assert_eq!(k.value(), Some(Scalar::from(expected)));
+
+            // 另选一个区间无法满足约束
+            if expected == 3 {
+                cs.set("bucket/bucket 3/boolean", Scalar::ZERO);
+                cs.set("bucket/bucket 2/boolean", Scalar::ONE);
+                assert!(!cs.is_satisfied());
+            }
+        }
+    }
+}
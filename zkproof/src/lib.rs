@@ -1,15 +1,50 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+pub mod cache;
+pub mod ceremony;
 pub mod circuits;
+pub mod commitment;
+pub mod gadgets;
+pub mod merkle;
+pub mod nullifier;
+pub mod poseidon;
+pub mod pool;
+pub mod privacy;
+pub mod profiler;
+pub mod proof_system;
 pub mod prover;
+pub mod registry;
+pub mod rolling;
+pub mod signatures;
+pub mod stats;
 pub mod verifier;
 pub mod utils;
 
-use circuits::{NetworkMetricCircuit, CircuitType};
+/// 证明封装格式，与验证逻辑一起位于 no_std 验证核心，供合约与运行时模块共用
+pub use zkproof_verifier::envelope;
+
+use cache::{CacheStats, ProofCache, ProofCacheConfig};
+use circuits::{
+    CircuitManager, CircuitType, IntegrityWitness, MetricWitness, ParticipationClaim,
+    ParticipationWitness, PercentileWitness, PrivacyWitness, RollingWitness, UptimeClaim, UptimeWitness,
+    VarianceWitness,
+};
+use commitment::SampleOpening;
+use envelope::ProofEnvelope;
+use merkle::MerklePath;
+use nullifier::NullifierStore;
+use pool::{JobId, JobOptions, ProofHandle, ProvingPool, ProvingPoolConfig};
+use privacy::PrivacyParameters;
+use proof_system::{ProofSystemKind, Statement};
 use prover::ZKProver;
+use registry::KeyRegistry;
+use rolling::{RollingCheckpoint, RollingState};
+use signatures::{SourcePublicKey, SourceSignature};
+use stats::ProvingStats;
 use verifier::ZKVerifier;
 
 /// 零知识证明数据结构
@@ -27,6 +62,29 @@ pub struct ZKProof {
     pub created_at: u64,
 }
 
+/// 时间窗口证明集合
+///
+/// 集合不是简洁证明：大小与验证开销都随节点数量线性增长，验证方需验证其中每个节点证明
+/// （共享一次批量配对检查），窗口指标由验证方从节点证明的公开指标重算。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowProofBundle {
+    /// 时间窗口起点
+    pub window_start: u64,
+    /// 同一指标类型与时间窗口的节点指标证明
+    pub node_proofs: Vec<ZKProof>,
+}
+
+impl WindowProofBundle {
+    /// 节点公开指标的整数平均值，仅在集合通过 [`ZKProofService::verify_window_bundle`] 后有意义
+    pub fn window_metric(&self) -> Option<u128> {
+        let sum = self
+            .node_proofs
+            .iter()
+            .try_fold(0u128, |sum, proof| sum.checked_add(*proof.public_inputs.first()?))?;
+        sum.checked_div(self.node_proofs.len() as u128)
+    }
+}
+
 /// 数据源信息
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataSource {
@@ -38,6 +96,8 @@ pub struct DataSource {
     pub timestamp: u64,
     /// 可靠性评分 (0-100)
     pub reliability_score: u8,
+    /// 数据源签名公钥
+    pub public_key: SourcePublicKey,
 }
 
 /// 数据源类型枚举
@@ -66,6 +126,234 @@ pub struct MetricSubmission {
     pub quality_score: u8,
     /// 时间窗口（小时）
     pub time_window_hours: u8,
+    /// 时间窗口起点（Unix 时间戳），各数据源的报告时间戳须位于窗口之内
+    pub window_start: u64,
+    /// 提交者秘密，用于派生空值符，不会出现在证明中
+    pub source_secret: [u8; 32],
+    /// 各数据源样本承诺的打开信息（按数据源顺序，private_data 为各数据源样本的拼接）
+    pub sample_openings: Vec<SampleOpening>,
+}
+
+impl MetricSubmission {
+    /// 时间窗口终点（Unix 时间戳）
+    pub fn window_end(&self) -> u64 {
+        self.window_start.saturating_add(self.time_window_hours as u64 * SECONDS_PER_HOUR)
+    }
+    
+    /// 本次提交的空值符（标量的规范小端序字节表示）
+    ///
+    /// 同一提交者秘密对同一指标类型与时间窗口只能得到唯一的空值符。
+    pub fn nullifier(&self) -> [u8; 32] {
+        nullifier::derive(
+            nullifier::secret_scalar(&self.source_secret),
+            nullifier::metric_tag(&self.metric_type),
+            self.window_start,
+        )
+        .to_bytes()
+    }
+    
+    /// 各数据源公布的样本承诺（按数据源顺序，绑定各数据源的报告时间戳）
+    pub fn sample_commitments(&self) -> Result<Vec<[u8; 32]>> {
+        if self.sample_openings.len() != self.data_sources.len() {
+            return Err(ZKProofError::InvalidInputData(
+                "each data source must open exactly one sample commitment".to_string(),
+            )
+            .into());
+        }
+        
+        let mut offset = 0;
+        let commitments = self
+            .sample_openings
+            .iter()
+            .zip(&self.data_sources)
+            .map(|(opening, source)| {
+                let end = (offset + opening.sample_count).min(self.private_data.len());
+                let samples = &self.private_data[offset..end];
+                offset = end;
+                opening.commit_at(source.timestamp, samples)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        
+        if offset != self.private_data.len() {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "commitment openings cover {} of {} samples",
+                offset,
+                self.private_data.len()
+            ))
+            .into());
+        }
+        Ok(commitments)
+    }
+}
+
+/// 指标证明公开的时间窗口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetricWindow {
+    /// 窗口起点（Unix 时间戳）
+    pub start: u64,
+    /// 窗口终点（Unix 时间戳）
+    pub end: u64,
+    /// 数据源时间戳距窗口终点的最大间隔（秒）
+    pub max_staleness: u64,
+}
+
+/// 数据完整性证明请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegritySubmission {
+    /// 私有数据批次
+    pub batch: Vec<u128>,
+    /// 批次时间戳
+    pub timestamp: u64,
+    /// 批次叶子到 Merkle 根的包含路径
+    pub merkle_path: MerklePath,
+    /// 已公布的 Merkle 根（标量的规范小端序字节表示）
+    pub merkle_root: [u8; 32],
+    /// 公开时间窗口起点
+    pub window_start: u64,
+    /// 公开时间窗口终点
+    pub window_end: u64,
+}
+
+impl IntegritySubmission {
+    /// 数据源需要签名的数据哈希（批次叶子的规范字节表示）
+    pub fn data_hash(&self) -> [u8; 32] {
+        merkle::batch_leaf(self.timestamp, &self.batch).to_bytes()
+    }
+}
+
+/// 差分隐私求和证明请求
+///
+/// 提交者先公布 [`PrivacySubmission::secret_commitment`] 与样本承诺，再由公开随机信标决定噪声。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacySubmission {
+    /// 私有样本（每个不超过敏感度）
+    pub samples: Vec<u128>,
+    /// 差分隐私参数
+    pub parameters: PrivacyParameters,
+    /// 样本承诺的盲化因子
+    pub blinding: [u8; 32],
+    /// 噪声秘密，不会出现在证明中
+    pub noise_secret: [u8; 32],
+    /// 公开随机信标的输出
+    pub beacon: [u8; 32],
+}
+
+impl PrivacySubmission {
+    /// 噪声秘密的承诺（标量的规范小端序字节表示），须在信标输出之前公布
+    pub fn secret_commitment(&self) -> [u8; 32] {
+        privacy::secret_commitment(privacy::bytes_to_scalar(&self.noise_secret)).to_bytes()
+    }
+    
+    /// 样本承诺（标量的规范小端序字节表示）
+    pub fn sample_commitment(&self) -> Result<[u8; 32]> {
+        SampleOpening {
+            sample_count: self.samples.len(),
+            blinding: self.blinding,
+        }
+        .commit(&self.samples)
+    }
+}
+
+/// 共识参与证明请求
+///
+/// 出块者树根由链上数据以 [`merkle::block_author_tree`] 公开计算，证明只公开声明与该树根。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipationSubmission {
+    /// 公开声明
+    pub claim: ParticipationClaim,
+    /// 验证者产出的区块在出块者树中的包含路径，叶子序号为高度 - first_block（升序）
+    pub authored_blocks: Vec<MerklePath>,
+    /// 区块范围出块者树的根（标量的规范小端序字节表示）
+    pub author_root: [u8; 32],
+}
+
+impl ParticipationSubmission {
+    /// 包含路径的深度，路径为空或深度不一致时返回 None
+    fn tree_depth(&self) -> Option<usize> {
+        let depth = self.authored_blocks.first()?.depth();
+        self.authored_blocks.iter().all(|path| path.depth() == depth).then_some(depth)
+    }
+
+    fn witness(&self) -> Result<ParticipationWitness> {
+        let signed_blocks = self
+            .authored_blocks
+            .iter()
+            .map(|path| {
+                self.claim.first_block.checked_add(path.leaf_index).ok_or_else(|| {
+                    ZKProofError::InvalidInputData(format!("leaf index {} overflows the block height", path.leaf_index))
+                })
+            })
+            .collect::<std::result::Result<_, _>>()?;
+        let siblings = self.authored_blocks.iter().map(MerklePath::sibling_scalars).collect::<Result<_>>()?;
+        let author_root = Option::from(bls12_381::Scalar::from_bytes(&self.author_root))
+            .ok_or_else(|| ZKProofError::InvalidInputData("non-canonical block author root".to_string()))?;
+        Ok(ParticipationWitness { claim: self.claim, author_root, signed_blocks, siblings })
+    }
+}
+
+/// 节点在线率证明请求
+///
+/// 心跳日志须事先由验证者以 [`UptimeSubmission::log_commitment`] 公布，承诺绑定验证者公钥，
+/// 证明只公开声明与该承诺。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UptimeSubmission {
+    /// 公开声明
+    pub claim: UptimeClaim,
+    /// 心跳时间戳（升序）
+    pub heartbeats: Vec<u64>,
+    /// 心跳日志承诺的盲化因子
+    pub blinding: [u8; 32],
+}
+
+impl UptimeSubmission {
+    /// 心跳日志承诺（标量的规范小端序字节表示）
+    pub fn log_commitment(&self) -> [u8; 32] {
+        self.witness().log_commitment().to_bytes()
+    }
+
+    fn witness(&self) -> UptimeWitness {
+        UptimeWitness {
+            claim: self.claim,
+            heartbeats: self.heartbeats.clone(),
+            blinding: commitment::blinding_scalar(&self.blinding),
+        }
+    }
+}
+
+/// 滚动聚合证明请求
+///
+/// 提交者在时间窗口之间保存 [`RollingState`]，每个窗口只需证明该窗口的新样本；
+/// 新窗口的样本承诺须事先以 [`RollingSubmission::sample_commitment`] 公布。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RollingSubmission {
+    /// 上一滚动状态，首个窗口使用 [`RollingState::genesis`]
+    pub state: RollingState,
+    /// 新时间窗口的私有样本
+    pub samples: Vec<u128>,
+    /// 样本承诺的盲化因子
+    pub sample_blinding: [u8; 32],
+    /// 新状态承诺的盲化因子
+    pub state_blinding: [u8; 32],
+}
+
+impl RollingSubmission {
+    /// 新窗口的样本承诺（标量的规范小端序字节表示）
+    pub fn sample_commitment(&self) -> Result<[u8; 32]> {
+        SampleOpening {
+            sample_count: self.samples.len(),
+            blinding: self.sample_blinding,
+        }
+        .commit(&self.samples)
+    }
+
+    fn witness(&self) -> RollingWitness {
+        RollingWitness {
+            previous: self.state.clone(),
+            samples: self.samples.clone(),
+            sample_blinding: commitment::blinding_scalar(&self.sample_blinding),
+            state_blinding: self.state_blinding,
+        }
+    }
 }
 
 /// 证明元数据
@@ -85,29 +373,87 @@ pub struct ProofMetadata {
     pub data_age: u64,
 }
 
+/// 默认所需的最少不同数据源数量
+pub const DEFAULT_MIN_SIGNERS: usize = 2;
+/// 默认的数据最大间隔：数据源时间戳距窗口终点不超过一小时
+pub const DEFAULT_MAX_STALENESS: Duration = Duration::from_secs(3600);
+/// 每小时的秒数
+const SECONDS_PER_HOUR: u64 = 3600;
+
+/// 服务配置
+#[derive(Debug, Clone)]
+pub struct ServiceConfig {
+    /// 证明任务池配置
+    pub pool: ProvingPoolConfig,
+    /// 证明缓存配置
+    pub cache: ProofCacheConfig,
+    /// 指标证明中数据源时间戳距窗口终点的最大间隔，超过窗口时长时取窗口时长
+    pub max_staleness: Duration,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            pool: ProvingPoolConfig::default(),
+            cache: ProofCacheConfig::default(),
+            max_staleness: DEFAULT_MAX_STALENESS,
+        }
+    }
+}
+
 /// 零知识证明服务主入口
 pub struct ZKProofService {
     /// 证明生成器
     prover: ZKProver,
     /// 证明验证器
     verifier: ZKVerifier,
+    /// 密钥注册表
+    key_registry: Arc<KeyRegistry>,
     /// 证明缓存
-    proof_cache: HashMap<String, ZKProof>,
-    /// 电路缓存
-    circuit_cache: HashMap<u32, NetworkMetricCircuit>,
+    proof_cache: ProofCache,
+    /// 电路管理器
+    circuit_manager: CircuitManager,
+    /// 已登记的数据源（按数据源标识符）
+    data_sources: HashMap<String, DataSource>,
+    /// 所需的最少不同数据源（签名者）数量
+    min_signers: usize,
+    /// 已接受的指标证明的空值符
+    nullifiers: NullifierStore,
+    /// 指标证明允许的数据最大间隔（秒）
+    max_staleness: u64,
 }
 
 impl ZKProofService {
-    /// 创建新的零知识证明服务实例
+    /// 创建新的零知识证明服务实例（密钥仅保存在内存中）
     pub fn new() -> Result<Self> {
-        let prover = ZKProver::new()?;
-        let verifier = ZKVerifier::new()?;
+        Self::with_key_registry(KeyRegistry::new())
+    }
+    
+    /// 使用指定的密钥注册表创建服务实例
+    pub fn with_key_registry(key_registry: KeyRegistry) -> Result<Self> {
+        Self::with_config(key_registry, ServiceConfig::default())
+    }
+    
+    /// 使用指定的密钥注册表与服务配置创建服务实例
+    ///
+    /// 配置了缓存目录时从中加载未过期的证明。
+    pub fn with_config(key_registry: KeyRegistry, config: ServiceConfig) -> Result<Self> {
+        let key_registry = Arc::new(key_registry);
+        let prover = ZKProver::with_pool_config(key_registry.clone(), config.pool)?;
+        let verifier = ZKVerifier::new(key_registry.clone())?;
+        // 电路选择使用任务池记录的实测证明耗时
+        let circuit_manager = CircuitManager::with_profiler(prover.pool().profiler().clone());
         
         Ok(Self {
             prover,
             verifier,
-            proof_cache: HashMap::new(),
-            circuit_cache: HashMap::new(),
+            key_registry,
+            proof_cache: ProofCache::open(config.cache)?,
+            circuit_manager,
+            data_sources: HashMap::new(),
+            min_signers: DEFAULT_MIN_SIGNERS,
+            nullifiers: NullifierStore::new(),
+            max_staleness: config.max_staleness.as_secs(),
         })
     }
     
@@ -117,17 +463,12 @@ impl ZKProofService {
         submission: MetricSubmission,
     ) -> Result<(ZKProof, ProofMetadata)> {
         let start_time = Instant::now();
-        
-        // 验证输入数据
-        self.validate_submission(&submission)?;
-        
-        // 计算电路ID
-        let circuit_id = self.calculate_circuit_id(&submission);
+        let (circuit, witness) = self.prepare_metric_proof(&submission)?;
         
         // 检查缓存
-        let cache_key = self.generate_cache_key(&submission);
+        let cache_key = self.generate_cache_key(&circuit, &submission);
         if let Some(cached_proof) = self.proof_cache.get(&cache_key) {
-            return Ok((cached_proof.clone(), ProofMetadata {
+            return Ok((cached_proof, ProofMetadata {
                 circuit_type: CircuitType::NetworkMetric,
                 generation_time: Duration::from_millis(0), // 缓存命中
                 verification_time: None,
@@ -138,14 +479,7 @@ impl ZKProofService {
         }
         
         // 生成证明
-        let proof = self.prover.generate_proof(
-            submission.private_data.clone(),
-            submission.data_sources.iter().map(|ds| ds.reliability_score as u32).collect(),
-            submission.public_metric,
-            submission.quality_score,
-            submission.time_window_hours,
-            circuit_id,
-        ).await?;
+        let proof = self.prover.generate_proof(&circuit, witness).await?;
         
         let generation_time = start_time.elapsed();
         
@@ -160,11 +494,30 @@ impl ZKProofService {
         };
         
         // 缓存证明
-        self.proof_cache.insert(cache_key, proof.clone());
+        self.proof_cache.insert(cache_key, proof.clone())?;
         
         Ok((proof, metadata))
     }
     
+    /// 提交网络指标证明任务，立即返回任务句柄
+    ///
+    /// 证明在任务池中并行生成，可通过句柄或 [`Self::cancel_proof`] 取消，并可设置超时与进度回调。
+    /// 与 [`Self::generate_metric_proof`] 不同，该方法不读写证明缓存。
+    pub fn start_metric_proof(&self, submission: &MetricSubmission, options: JobOptions) -> Result<ProofHandle> {
+        let (circuit, witness) = self.prepare_metric_proof(submission)?;
+        self.prover.submit_proof(&circuit, witness, options)
+    }
+    
+    /// 取消证明任务，任务不存在或已结束时返回 false
+    pub fn cancel_proof(&self, job_id: JobId) -> bool {
+        self.prover.pool().cancel(job_id)
+    }
+    
+    /// 证明任务池
+    pub fn proving_pool(&self) -> &ProvingPool {
+        self.prover.pool()
+    }
+    
     /// 验证零知识证明
     pub async fn verify_proof(
         &mut self,
@@ -172,40 +525,568 @@ impl ZKProofService {
     ) -> Result<(bool, Duration)> {
         let start_time = Instant::now();
         
-        let is_valid = self.verifier.verify_proof(proof).await?;
+        let is_valid = match self.circuit_manager.get_circuit(proof.circuit_id) {
+            Some(circuit) => self.verifier.verify_proof(circuit, proof).await?,
+            None => false,
+        };
         
         let verification_time = start_time.elapsed();
         
         Ok((is_valid, verification_time))
     }
     
+    /// 将证明封装为版本化的交换格式，后端取电路当前配置的后端
+    ///
+    /// 摘要模式的电路在封装中只放入公开输入摘要，链上只需保存该摘要。
+    pub fn export_envelope(&self, proof: &ZKProof) -> Result<ProofEnvelope> {
+        let circuit = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown circuit id: {}", proof.circuit_id))?;
+        let (backend, proof_value) = (circuit.proof_system, proof.proof_value.clone());
+        match circuit.statement(&proof.public_inputs) {
+            Statement::Inputs(public_inputs) => {
+                ProofEnvelope::new(backend, proof.circuit_id, &proof.verification_key, proof_value, public_inputs)
+            }
+            Statement::Digest(digest) => {
+                ProofEnvelope::with_digest(backend, proof.circuit_id, &proof.verification_key, proof_value, digest)
+            }
+        }
+        .map_err(|e| ZKProofError::InvalidProofFormat(e.to_string()).into())
+    }
+    
+    /// 验证证明封装
+    pub async fn verify_envelope(&mut self, envelope: &ProofEnvelope) -> Result<(bool, Duration)> {
+        let start_time = Instant::now();
+        
+        let is_valid = match self.circuit_manager.get_circuit(envelope.circuit_id) {
+            Some(circuit) => self.verifier.verify_envelope(circuit, envelope).await?,
+            None => false,
+        };
+        
+        Ok((is_valid, start_time.elapsed()))
+    }
+    
+    /// 接受提交的指标证明
+    ///
+    /// 证明必须通过验证，数据最大间隔不超过服务配置，且其空值符尚未被使用；接受后记录空值符，
+    /// 同一提交者在同一时间窗口的后续提交（包括重放同一证明）都会被拒绝。
+    pub async fn submit_metric_proof(&mut self, proof: &ZKProof) -> Result<()> {
+        let nullifier = self.metric_nullifier(proof)?;
+        if self.nullifiers.contains(&nullifier) {
+            return Err(ZKProofError::NullifierReused(hex::encode(nullifier)).into());
+        }
+        
+        let window = self.metric_window(proof)?;
+        if window.max_staleness > self.max_staleness {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "metric proof allows data up to {}s old, at most {}s accepted",
+                window.max_staleness, self.max_staleness
+            ))
+            .into());
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        if !is_valid {
+            return Err(ZKProofError::ProofVerificationFailed(format!(
+                "metric proof for circuit {} is invalid",
+                proof.circuit_id
+            ))
+            .into());
+        }
+        
+        self.nullifiers.spend(nullifier)
+    }
+    
+    /// 验证指标证明，并检查其样本恰好来自各数据源公布的承诺
+    ///
+    /// commitments 按数据源顺序排列，与生成证明时的 [`MetricSubmission::sample_commitments`] 一致。
+    pub async fn verify_sample_commitments(&mut self, proof: &ZKProof, commitments: &[[u8; 32]]) -> Result<bool> {
+        let is_metric = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::NetworkMetric);
+        let limbs = match proof.public_inputs.as_slice() {
+            [_, _, _, _, _, _, _, _, _, low, high] if is_metric => [*low, *high],
+            _ => return Ok(false),
+        };
+        
+        let digest = commitment::digest(&commitment::commitment_scalars(commitments)?);
+        if utils::scalar_to_u128_limbs(&digest) != limbs {
+            return Ok(false);
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid)
+    }
+    
+    /// 验证指标证明，并检查其绑定的时间窗口与声明的窗口一致
+    ///
+    /// 证明公开的窗口起点与时长须等于声明值，数据最大间隔不超过服务配置，
+    /// 因此旧窗口的证明无法作为当前窗口的证明出示。
+    pub async fn verify_metric_window(
+        &mut self,
+        proof: &ZKProof,
+        window_start: u64,
+        time_window_hours: u8,
+    ) -> Result<bool> {
+        let window = match self.metric_window(proof) {
+            Ok(window) => window,
+            Err(_) => return Ok(false),
+        };
+        let window_end = window_start.checked_add(time_window_hours as u64 * SECONDS_PER_HOUR);
+        if window.start != window_start
+            || Some(window.end) != window_end
+            || proof.public_inputs[2] != time_window_hours as u128
+            || window.max_staleness > self.max_staleness
+        {
+            return Ok(false);
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid)
+    }
+    
+    /// 空值符是否已被已接受的证明使用
+    pub fn is_nullifier_spent(&self, nullifier: &[u8; 32]) -> bool {
+        self.nullifiers.contains(nullifier)
+    }
+    
     /// 批量验证多个证明
+    ///
+    /// 证明按电路分组，同一电路的证明共享验证密钥并批量验证；
+    /// 返回的耗时为所在分组的验证耗时按证明数量均摊。
     pub async fn batch_verify_proofs(
         &mut self,
         proofs: &[ZKProof],
     ) -> Result<Vec<(bool, Duration)>> {
-        let mut results = Vec::new();
+        let mut groups: HashMap<u32, Vec<usize>> = HashMap::new();
+        for (i, proof) in proofs.iter().enumerate() {
+            groups.entry(proof.circuit_id).or_default().push(i);
+        }
         
-        for proof in proofs {
-            let result = self.verify_proof(proof).await?;
-            results.push(result);
+        let mut results = vec![(false, Duration::ZERO); proofs.len()];
+        for (circuit_id, indices) in groups {
+            let start_time = Instant::now();
+            
+            let verified = match self.circuit_manager.get_circuit(circuit_id) {
+                Some(circuit) => {
+                    let group: Vec<&ZKProof> = indices.iter().map(|i| &proofs[*i]).collect();
+                    self.verifier.batch_verify(circuit, &group).await?
+                }
+                None => vec![false; indices.len()],
+            };
+            
+            let verification_time = start_time.elapsed() / indices.len() as u32;
+            for (i, is_valid) in indices.into_iter().zip(verified) {
+                results[i] = (is_valid, verification_time);
+            }
         }
         
         Ok(results)
     }
     
     /// 生成数据完整性证明
+    ///
+    /// 证明私有批次包含在已公布的 Merkle 根中，且批次时间戳位于公开时间窗口内。
+    /// 生成证明前，至少 `min_signers` 个不同的已登记数据源必须对
+    /// [`IntegritySubmission::data_hash`] 签名。
     pub async fn generate_integrity_proof(
         &mut self,
-        data_hash: Vec<u8>,
-        timestamp: u64,
-        source_signatures: Vec<Vec<u8>>,
+        submission: IntegritySubmission,
+        source_signatures: Vec<SourceSignature>,
     ) -> Result<ZKProof> {
-        self.prover.generate_integrity_proof(
-            data_hash,
-            timestamp,
-            source_signatures,
-        ).await
+        // 验证数据源签名
+        let data_hash = submission.data_hash();
+        signatures::verify_signers(&self.data_sources, &data_hash, &source_signatures, self.min_signers)?;
+        
+        // 包含路径必须指向已公布的根
+        let leaf = merkle::batch_leaf(submission.timestamp, &submission.batch);
+        if submission.merkle_path.root(leaf)?.to_bytes() != submission.merkle_root {
+            return Err(anyhow::anyhow!("Merkle path does not lead to the committed root"));
+        }
+        
+        // 选择电路
+        let circuit = self
+            .circuit_manager
+            .select_integrity_circuit(submission.batch.len(), submission.merkle_path.depth())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "No registered integrity circuit supports a batch of {} data points at tree depth {}",
+                submission.batch.len(),
+                submission.merkle_path.depth(),
+            ))?;
+        
+        let witness = IntegrityWitness {
+            siblings: submission.merkle_path.sibling_scalars()?,
+            leaf_index: submission.merkle_path.leaf_index,
+            batch: submission.batch,
+            timestamp: submission.timestamp,
+            window_start: submission.window_start,
+            window_end: submission.window_end,
+        };
+        
+        self.prover.generate_integrity_proof(&circuit, witness).await
+    }
+    
+    /// 将时间窗口内各节点的指标证明打包为窗口证明集合
+    ///
+    /// 节点证明必须全部有效，且都属于网络指标电路、同一指标类型与同一时间窗口。
+    pub async fn bundle_window_proofs(
+        &mut self,
+        window_start: u64,
+        node_proofs: Vec<ZKProof>,
+    ) -> Result<WindowProofBundle> {
+        self.check_window_proofs(window_start, &node_proofs)?;
+        
+        let results = self.batch_verify_proofs(&node_proofs).await?;
+        if let Some(index) = results.iter().position(|(is_valid, _)| !is_valid) {
+            return Err(anyhow::anyhow!("Node proof {} is invalid", index));
+        }
+        
+        Ok(WindowProofBundle {
+            window_start,
+            node_proofs,
+        })
+    }
+    
+    /// 验证时间窗口证明集合：检查节点证明的窗口、指标类型与空值符后批量验证全部节点证明
+    pub async fn verify_window_bundle(
+        &mut self,
+        bundle: &WindowProofBundle,
+    ) -> Result<(bool, Duration)> {
+        let start_time = Instant::now();
+        
+        if self.check_window_proofs(bundle.window_start, &bundle.node_proofs).is_err() {
+            return Ok((false, start_time.elapsed()));
+        }
+        
+        let results = self.batch_verify_proofs(&bundle.node_proofs).await?;
+        let is_valid = results.iter().all(|(is_valid, _)| *is_valid);
+        
+        Ok((is_valid, start_time.elapsed()))
+    }
+    
+    /// 生成百分位数证明，公开输入为 `[percentile, percentile_value, sample_count, sample_commitment]`
+    ///
+    /// 采用最近秩法，percentile 为 50 时即中位数。样本承诺占两个分量，
+    /// 提交者须事先公布 [`SampleOpening::commit`] 的结果，验证方以 [`Self::verify_percentile_proof`] 核对。
+    pub async fn generate_percentile_proof(
+        &mut self,
+        samples: Vec<u128>,
+        percentile: u8,
+        blinding: [u8; 32],
+    ) -> Result<ZKProof> {
+        let circuit = self
+            .circuit_manager
+            .select_optimal_circuit(&CircuitType::Percentile, samples.len(), 0)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No registered percentile circuit supports {} samples", samples.len()))?;
+        
+        let witness = PercentileWitness {
+            samples,
+            percentile,
+            blinding: commitment::blinding_scalar(&blinding),
+        };
+        self.prover.generate_percentile_proof(&circuit, witness).await
+    }
+    
+    /// 生成方差证明，公开输入为 `[sample_count, mean, variance, sample_commitment]`
+    ///
+    /// 样本承诺占两个分量，提交者须事先公布 [`SampleOpening::commit`] 的结果，
+    /// 验证方以 [`Self::verify_variance_proof`] 核对。
+    pub async fn generate_variance_proof(&mut self, samples: Vec<u128>, blinding: [u8; 32]) -> Result<ZKProof> {
+        let circuit = self
+            .circuit_manager
+            .select_optimal_circuit(&CircuitType::Variance, samples.len(), 0)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No registered variance circuit supports {} samples", samples.len()))?;
+        
+        let witness = VarianceWitness {
+            samples,
+            blinding: commitment::blinding_scalar(&blinding),
+        };
+        self.prover.generate_variance_proof(&circuit, witness).await
+    }
+    
+    /// 验证百分位数证明，并核对百分位数与事先公布的样本承诺
+    pub async fn verify_percentile_proof(
+        &mut self,
+        proof: &ZKProof,
+        percentile: u8,
+        sample_commitment: &[u8; 32],
+    ) -> Result<bool> {
+        let is_percentile = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::Percentile);
+        match proof.public_inputs.as_slice() {
+            [p, _, _, c_low, c_high] if is_percentile && *p == percentile as u128 => {
+                if !commitment_matches([*c_low, *c_high], sample_commitment) {
+                    return Ok(false);
+                }
+            }
+            _ => return Ok(false),
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid)
+    }
+    
+    /// 验证方差证明，并核对事先公布的样本承诺
+    pub async fn verify_variance_proof(&mut self, proof: &ZKProof, sample_commitment: &[u8; 32]) -> Result<bool> {
+        let is_variance = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::Variance);
+        match proof.public_inputs.as_slice() {
+            [_, _, _, c_low, c_high] if is_variance => {
+                if !commitment_matches([*c_low, *c_high], sample_commitment) {
+                    return Ok(false);
+                }
+            }
+            _ => return Ok(false),
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid)
+    }
+    
+    /// 生成差分隐私求和证明
+    ///
+    /// 公开输入为 `[noisy_sum, epsilon_millis, sensitivity, beacon, secret_commitment, sample_commitment]`，
+    /// 后三者各占低、高两个分量。噪声由噪声秘密与信标唯一确定。
+    pub async fn generate_privacy_proof(&mut self, submission: PrivacySubmission) -> Result<ZKProof> {
+        submission.parameters.validate()?;
+        let circuit = self
+            .circuit_manager
+            .select_privacy_circuit(submission.samples.len(), &submission.parameters)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "No registered privacy circuit with epsilon {} and sensitivity {} supports {} samples",
+                submission.parameters.epsilon(),
+                submission.parameters.sensitivity,
+                submission.samples.len(),
+            ))?;
+        
+        let witness = PrivacyWitness {
+            samples: submission.samples,
+            blinding: commitment::blinding_scalar(&submission.blinding),
+            noise_secret: privacy::bytes_to_scalar(&submission.noise_secret),
+            beacon: privacy::bytes_to_scalar(&submission.beacon),
+            parameters: submission.parameters,
+        };
+        self.prover.generate_privacy_proof(&circuit, witness).await
+    }
+    
+    /// 验证差分隐私求和证明，并检查其噪声来自给定信标与事先公布的承诺
+    pub async fn verify_privacy_proof(
+        &mut self,
+        proof: &ZKProof,
+        beacon: &[u8; 32],
+        secret_commitment: &[u8; 32],
+        sample_commitment: &[u8; 32],
+    ) -> Result<bool> {
+        let is_privacy = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::PrivacyPreserving);
+        let (beacon_limbs, secret_limbs, sample_limbs) = match proof.public_inputs.as_slice() {
+            [_, _, _, b_low, b_high, s_low, s_high, c_low, c_high] if is_privacy => {
+                ([*b_low, *b_high], [*s_low, *s_high], [*c_low, *c_high])
+            }
+            _ => return Ok(false),
+        };
+        
+        if utils::scalar_to_u128_limbs(&privacy::bytes_to_scalar(beacon)) != beacon_limbs
+            || !commitment_matches(secret_limbs, secret_commitment)
+            || !commitment_matches(sample_limbs, sample_commitment)
+        {
+            return Ok(false);
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid)
+    }
+    
+    /// 生成共识参与证明，公开输入为 `[first_block, block_count, min_signed, validator_key, author_root]`
+    ///
+    /// 公钥与树根各占两个分量，产出的区块与确切数量都不会出现在证明中。
+    pub async fn generate_participation_proof(&mut self, submission: ParticipationSubmission) -> Result<ZKProof> {
+        let entries = submission.authored_blocks.len();
+        let tree_depth = submission
+            .tree_depth()
+            .ok_or_else(|| anyhow::anyhow!("Authored block paths must be non-empty and share one tree depth"))?;
+        let circuit = self
+            .circuit_manager
+            .select_participation_circuit(entries, tree_depth)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "No registered consensus participation circuit supports {} authored blocks at tree depth {}",
+                entries,
+                tree_depth
+            ))?;
+        
+        self.prover.generate_participation_proof(&circuit, submission.witness()?).await
+    }
+    
+    /// 验证共识参与证明，并检查其公开输入与给定声明及由链上数据计算的出块者树根一致
+    pub async fn verify_participation_proof(
+        &mut self,
+        proof: &ZKProof,
+        claim: &ParticipationClaim,
+        author_root: &[u8; 32],
+    ) -> Result<bool> {
+        let Some(root) = Option::from(bls12_381::Scalar::from_bytes(author_root)) else {
+            return Ok(false);
+        };
+        let expected = claim.public_inputs(&root);
+        self.verify_claim_proof(proof, CircuitType::ConsensusParticipation, &expected).await
+    }
+    
+    /// 生成节点在线率证明，公开输入为
+    /// `[window_start, heartbeat_interval, slot_count, min_uptime_percent, validator_key, log_commitment]`
+    ///
+    /// 公钥与承诺各占两个分量，心跳时间与缺失的时隙都不会出现在证明中。
+    pub async fn generate_uptime_proof(&mut self, submission: UptimeSubmission) -> Result<ZKProof> {
+        let circuit = self
+            .circuit_manager
+            .select_optimal_circuit(&CircuitType::NodeReliability, submission.heartbeats.len(), 0)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "No registered node reliability circuit supports {} heartbeats",
+                submission.heartbeats.len()
+            ))?;
+        
+        self.prover.generate_uptime_proof(&circuit, submission.witness()).await
+    }
+    
+    /// 验证节点在线率证明，并检查其公开输入与给定声明及事先公布的心跳日志承诺一致
+    pub async fn verify_uptime_proof(
+        &mut self,
+        proof: &ZKProof,
+        claim: &UptimeClaim,
+        log_commitment: &[u8; 32],
+    ) -> Result<bool> {
+        let Ok(&[commitment]) = commitment::commitment_scalars(&[*log_commitment]).as_deref() else {
+            return Ok(false);
+        };
+        let expected = claim.public_inputs(&commitment);
+        self.verify_claim_proof(proof, CircuitType::NodeReliability, &expected).await
+    }
+    
+    /// 生成滚动聚合证明，返回证明与并入新窗口后的滚动状态
+    ///
+    /// 公开输入为 `[metric_tag, window_start, time_window, covered_windows, sample_count, rolling_average,
+    /// previous_state, state, sample_commitment]`，后三者各占低、高两个分量。
+    /// 返回的状态须由提交者保存，作为下一个窗口的 [`RollingSubmission::state`]。
+    pub async fn generate_rolling_proof(&mut self, submission: RollingSubmission) -> Result<(ZKProof, RollingState)> {
+        let circuit = self
+            .circuit_manager
+            .select_rolling_circuit(submission.samples.len(), submission.state.windows.len())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "No registered rolling aggregation circuit over {} windows supports {} samples",
+                submission.state.windows.len(),
+                submission.samples.len()
+            ))?;
+        
+        let witness = submission.witness();
+        let state = witness.state();
+        let proof = self.prover.generate_rolling_proof(&circuit, witness).await?;
+        Ok((proof, state))
+    }
+    
+    /// 验证滚动聚合证明，有效时返回证明之后的检查点
+    ///
+    /// 证明须紧接给定检查点（同一指标与窗口时长、下一个窗口、上一状态承诺一致），
+    /// 且新窗口的样本来自事先公布的承诺。验证方只需保存最近的检查点，
+    /// 单个证明即可给出最近各窗口的滚动平均值，无需重新验证历史窗口。
+    ///
+    /// 检查点必须取自验证方自己保存的状态（初始检查点或上一次本方法返回的检查点），
+    /// 不能取自证明者：证明只说明从该检查点出发的一步，历史的可信性由检查点保证，见 [`rolling`]。
+    pub async fn verify_rolling_proof(
+        &mut self,
+        proof: &ZKProof,
+        checkpoint: &RollingCheckpoint,
+        sample_commitment: &[u8; 32],
+    ) -> Result<Option<RollingCheckpoint>> {
+        let is_rolling = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::RollingAggregation);
+        let next = match checkpoint.successor(&proof.public_inputs) {
+            Some(next) if is_rolling => next,
+            _ => return Ok(None),
+        };
+        let committed = utils::scalar_from_u128_limbs([proof.public_inputs[10], proof.public_inputs[11]])
+            .is_some_and(|commitment| commitment.to_bytes() == *sample_commitment);
+        if !committed {
+            return Ok(None);
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid.then_some(next))
+    }
+    
+    /// 检查证明的电路类型与公开输入后验证证明
+    async fn verify_claim_proof(
+        &mut self,
+        proof: &ZKProof,
+        circuit_type: CircuitType,
+        expected_inputs: &[u128],
+    ) -> Result<bool> {
+        let matches_type = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == circuit_type);
+        if !matches_type || proof.public_inputs != expected_inputs {
+            return Ok(false);
+        }
+        
+        let (is_valid, _) = self.verify_proof(proof).await?;
+        Ok(is_valid)
+    }
+    
+    /// 登记数据源，其公钥用于验证签名
+    ///
+    /// 同一公钥不能登记在多个数据源标识符下，否则单个密钥即可冒充多个签名者。
+    pub fn register_data_source(&mut self, source: DataSource) -> Result<()> {
+        if self
+            .data_sources
+            .values()
+            .any(|s| s.public_key == source.public_key && s.source_id != source.source_id)
+        {
+            return Err(anyhow::anyhow!(
+                "Public key of {} is already registered to another data source",
+                source.source_id
+            ));
+        }
+        
+        self.data_sources.insert(source.source_id.clone(), source);
+        Ok(())
+    }
+    
+    /// 设置所需的最少不同数据源（签名者）数量
+    pub fn set_min_signers(&mut self, min_signers: usize) -> Result<()> {
+        if min_signers == 0 {
+            return Err(anyhow::anyhow!("At least one signer must be required"));
+        }
+        self.min_signers = min_signers;
+        Ok(())
+    }
+    
+    /// 获取密钥注册表
+    pub fn key_registry(&self) -> &KeyRegistry {
+        &self.key_registry
+    }
+    
+    /// 为指定电路选择证明系统后端
+    pub fn set_proof_system(&mut self, circuit_id: u32, proof_system: ProofSystemKind) -> Result<()> {
+        if !self.circuit_manager.set_proof_system(circuit_id, proof_system) {
+            return Err(anyhow::anyhow!("Unknown circuit id: {}", circuit_id));
+        }
+        Ok(())
     }
     
     /// 获取支持的电路类型
@@ -213,32 +1094,81 @@ impl ZKProofService {
         vec![
             CircuitType::NetworkMetric,
             CircuitType::DataIntegrity,
-            CircuitType::AggregationProof,
             CircuitType::PrivacyPreserving,
+            CircuitType::Percentile,
+            CircuitType::Variance,
+            CircuitType::ConsensusParticipation,
+            CircuitType::NodeReliability,
+            CircuitType::RollingAggregation,
         ]
     }
     
     /// 获取证明统计信息
+    ///
+    /// 计数覆盖服务启动以来经由任务池生成的所有证明，缓存命中不计入生成次数。
     pub fn get_proof_statistics(&self) -> ProofStatistics {
+        let cache = self.proof_cache.stats();
+        let proving = self.prover.pool().stats();
         ProofStatistics {
-            total_proofs_generated: self.proof_cache.len() as u64,
-            cache_hit_ratio: self.calculate_cache_hit_ratio(),
-            average_generation_time: self.calculate_average_generation_time(),
+            total_proofs_generated: proving.completed,
+            cache_hit_ratio: cache.hit_ratio(),
+            average_generation_time: proving.generation_times.mean(),
             supported_circuits: self.get_supported_circuits().len() as u32,
+            cache,
+            proving,
         }
     }
     
-    /// 清理过期的证明缓存
-    pub fn cleanup_expired_cache(&mut self, max_age_seconds: u64) {
-        let current_time = chrono::Utc::now().timestamp() as u64;
-        
-        self.proof_cache.retain(|_, proof| {
-            current_time - proof.created_at < max_age_seconds
-        });
+    /// 清理超过缓存存活时间的证明，返回清理的数量
+    pub fn cleanup_expired_cache(&mut self) -> usize {
+        self.proof_cache.remove_expired()
     }
     
     // 私有辅助方法
     
+    /// 校验提交并选择电路、构造见证
+    fn prepare_metric_proof(&self, submission: &MetricSubmission) -> Result<(circuits::NetworkMetricCircuit, MetricWitness)> {
+        // 验证输入数据
+        self.validate_submission(submission)?;
+        
+        // 该提交者在此时间窗口已提交过证明
+        let nullifier = submission.nullifier();
+        if self.nullifiers.contains(&nullifier) {
+            return Err(ZKProofError::NullifierReused(hex::encode(nullifier)).into());
+        }
+        
+        // 选择电路
+        let circuit = self
+            .calculate_circuit_id(submission)
+            .and_then(|circuit_id| self.circuit_manager.get_circuit(circuit_id))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!(
+                "No registered circuit supports {} data points from {} data sources",
+                submission.private_data.len(),
+                submission.data_sources.len(),
+            ))?;
+        
+        let witness = MetricWitness {
+            private_data: submission.private_data.clone(),
+            reliability_scores: submission.data_sources.iter().map(|ds| ds.reliability_score as u32).collect(),
+            public_metric: submission.public_metric,
+            quality_score: submission.quality_score,
+            time_window_hours: submission.time_window_hours,
+            metric_tag: nullifier::metric_tag(&submission.metric_type),
+            window_start: submission.window_start,
+            max_staleness: self.metric_max_staleness(submission),
+            source_secret: nullifier::secret_scalar(&submission.source_secret),
+            sample_counts: submission.sample_openings.iter().map(|o| o.sample_count).collect(),
+            blindings: submission
+                .sample_openings
+                .iter()
+                .map(|o| commitment::blinding_scalar(&o.blinding))
+                .collect(),
+            source_timestamps: submission.data_sources.iter().map(|ds| ds.timestamp).collect(),
+        };
+        Ok((circuit, witness))
+    }
+    
     /// 验证提交数据的有效性
     fn validate_submission(&self, submission: &MetricSubmission) -> Result<()> {
         // 检查私有数据不为空
@@ -246,9 +1176,16 @@ impl ZKProofService {
             return Err(anyhow::anyhow!("Private data cannot be empty"));
         }
         
-        // 检查数据源数量
-        if submission.data_sources.len() < 2 {
-            return Err(anyhow::anyhow!("At least 2 data sources required"));
+        // 检查数据源数量与唯一性
+        let mut source_ids = std::collections::HashSet::new();
+        let mut public_keys = std::collections::HashSet::new();
+        for source in &submission.data_sources {
+            if !source_ids.insert(&source.source_id) || !public_keys.insert(&source.public_key) {
+                return Err(anyhow::anyhow!("Duplicate data source: {}", source.source_id));
+            }
+        }
+        if submission.data_sources.len() < self.min_signers {
+            return Err(anyhow::anyhow!("At least {} data sources required", self.min_signers));
         }
         
         // 检查质量评分范围
@@ -261,6 +1198,24 @@ impl ZKProofService {
             return Err(anyhow::anyhow!("Time window must be between 1-24 hours"));
         }
         
+        // 数据源报告时间须位于 [窗口终点 - 最大间隔, 窗口终点]
+        let window_end = submission
+            .window_start
+            .checked_add(submission.time_window_hours as u64 * SECONDS_PER_HOUR)
+            .ok_or_else(|| anyhow::anyhow!("Time window end overflows"))?;
+        let oldest = window_end - self.metric_max_staleness(submission);
+        for source in &submission.data_sources {
+            if source.timestamp < oldest || source.timestamp > window_end {
+                return Err(anyhow::anyhow!(
+                    "Data source {} reported at {}, outside the freshness window [{}, {}]",
+                    source.source_id,
+                    source.timestamp,
+                    oldest,
+                    window_end
+                ));
+            }
+        }
+        
         // 验证数据源可靠性
         for source in &submission.data_sources {
             if source.reliability_score > 100 {
@@ -268,42 +1223,139 @@ impl ZKProofService {
             }
         }
         
+        // 每个数据源对其非空的样本段给出承诺
+        if submission.sample_openings.len() != submission.data_sources.len() {
+            return Err(anyhow::anyhow!("Each data source must open exactly one sample commitment"));
+        }
+        submission.sample_commitments()?;
+        
         Ok(())
     }
     
-    /// 计算电路ID
-    fn calculate_circuit_id(&self, submission: &MetricSubmission) -> u32 {
-        use sha2::{Sha256, Digest};
+    /// 检查窗口证明集合中的节点证明
+    ///
+    /// 节点证明必须属于网络指标电路、以 window_start 为窗口起点且指标类型标签（公开输入 3）一致，
+    /// 空值符互不相同，同一节点在一个窗口内只计入一次。
+    fn check_window_proofs(&self, window_start: u64, node_proofs: &[ZKProof]) -> Result<()> {
+        if node_proofs.is_empty() {
+            return Err(anyhow::anyhow!("At least one node proof required"));
+        }
         
-        let mut hasher = Sha256::new();
-        hasher.update(submission.metric_type.as_bytes());
-        hasher.update(&submission.private_data.len().to_be_bytes());
-        hasher.update(&submission.data_sources.len().to_be_bytes());
-        hasher.update(&submission.time_window_hours.to_be_bytes());
+        let mut nullifiers = std::collections::HashSet::new();
+        let mut metric_tag = None;
+        for proof in node_proofs {
+            if self.metric_window(proof)?.start != window_start {
+                return Err(anyhow::anyhow!("Node proof does not cover window {}", window_start));
+            }
+            let tag = proof.public_inputs[3];
+            if *metric_tag.get_or_insert(tag) != tag {
+                return Err(anyhow::anyhow!("Node proofs mix metric types"));
+            }
+            let nullifier = self.metric_nullifier(proof)?;
+            if !nullifiers.insert(nullifier) {
+                return Err(ZKProofError::NullifierReused(hex::encode(nullifier)).into());
+            }
+        }
         
-        let hash = hasher.finalize();
-        u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]])
+        Ok(())
+    }
+    
+    /// 读取指标证明公开输入中的空值符
+    fn metric_nullifier(&self, proof: &ZKProof) -> Result<[u8; 32]> {
+        let is_metric = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::NetworkMetric);
+        let limbs = match proof.public_inputs.as_slice() {
+            [_, _, _, _, _, _, _, low, high, _, _] if is_metric => [*low, *high],
+            _ => return Err(anyhow::anyhow!("Proof for circuit {} is not a metric proof", proof.circuit_id)),
+        };
+        
+        utils::scalar_from_u128_limbs(limbs)
+            .map(|nullifier| nullifier.to_bytes())
+            .ok_or_else(|| ZKProofError::InvalidProofFormat("non-canonical nullifier".to_string()).into())
+    }
+    
+    /// 读取指标证明公开输入中的时间窗口
+    fn metric_window(&self, proof: &ZKProof) -> Result<MetricWindow> {
+        let is_metric = self
+            .circuit_manager
+            .get_circuit(proof.circuit_id)
+            .is_some_and(|c| c.circuit_type == CircuitType::NetworkMetric);
+        let (start, end, max_staleness) = match proof.public_inputs.as_slice() {
+            [_, _, _, _, start, end, max_staleness, _, _, _, _] if is_metric => (*start, *end, *max_staleness),
+            _ => return Err(anyhow::anyhow!("Proof for circuit {} is not a metric proof", proof.circuit_id)),
+        };
+        
+        match (u64::try_from(start), u64::try_from(end), u64::try_from(max_staleness)) {
+            (Ok(start), Ok(end), Ok(max_staleness)) => Ok(MetricWindow { start, end, max_staleness }),
+            _ => Err(ZKProofError::InvalidProofFormat("time window out of range".to_string()).into()),
+        }
+    }
+    
+    /// 提交的数据最大间隔：服务配置与窗口时长中的较小者
+    fn metric_max_staleness(&self, submission: &MetricSubmission) -> u64 {
+        self.max_staleness
+            .min(submission.time_window_hours as u64 * SECONDS_PER_HOUR)
+    }
+    
+    /// 计算电路ID（选择能容纳该提交数据的最小已注册电路）
+    fn calculate_circuit_id(&self, submission: &MetricSubmission) -> Option<u32> {
+        self.circuit_manager
+            .select_optimal_circuit(
+                &CircuitType::NetworkMetric,
+                submission.private_data.len(),
+                submission.data_sources.len(),
+            )
+            .map(|circuit| circuit.circuit_id)
     }
     
     /// 生成缓存键
-    fn generate_cache_key(&self, submission: &MetricSubmission) -> String {
+    fn generate_cache_key(&self, circuit: &circuits::NetworkMetricCircuit, submission: &MetricSubmission) -> String {
         use sha2::{Sha256, Digest};
         
+        /// 带长度前缀写入变长字段，避免相邻字段的边界产生歧义
+        fn update_prefixed(hasher: &mut Sha256, bytes: &[u8]) {
+            hasher.update((bytes.len() as u64).to_be_bytes());
+            hasher.update(bytes);
+        }
+        
         let mut hasher = Sha256::new();
         
+        // 所选电路与后端：切换后端后不会命中其它后端生成的证明
+        hasher.update(circuit.circuit_id.to_be_bytes());
+        hasher.update([circuit.proof_system.id()]);
+        
         // 添加所有相关数据到哈希
-        hasher.update(submission.metric_type.as_bytes());
+        update_prefixed(&mut hasher, submission.metric_type.as_bytes());
+        hasher.update((submission.private_data.len() as u64).to_be_bytes());
         for data in &submission.private_data {
-            hasher.update(&data.to_be_bytes());
+            hasher.update(data.to_be_bytes());
+        }
+        hasher.update(submission.public_metric.to_be_bytes());
+        hasher.update(submission.quality_score.to_be_bytes());
+        hasher.update(submission.time_window_hours.to_be_bytes());
+        hasher.update(submission.window_start.to_be_bytes());
+        hasher.update(submission.nullifier());
+        hasher.update((submission.sample_openings.len() as u64).to_be_bytes());
+        for opening in &submission.sample_openings {
+            hasher.update((opening.sample_count as u64).to_be_bytes());
+            hasher.update(opening.blinding);
         }
-        hasher.update(&submission.public_metric.to_be_bytes());
-        hasher.update(&submission.quality_score.to_be_bytes());
-        hasher.update(&submission.time_window_hours.to_be_bytes());
         
         // 添加数据源信息
+        hasher.update(self.metric_max_staleness(submission).to_be_bytes());
+        hasher.update((submission.data_sources.len() as u64).to_be_bytes());
         for source in &submission.data_sources {
-            hasher.update(source.source_id.as_bytes());
-            hasher.update(&source.reliability_score.to_be_bytes());
+            update_prefixed(&mut hasher, source.source_id.as_bytes());
+            hasher.update(source.timestamp.to_be_bytes());
+            hasher.update(source.reliability_score.to_be_bytes());
+            let (variant, key) = match &source.public_key {
+                SourcePublicKey::Ed25519(key) => (0u8, key),
+                SourcePublicKey::Sr25519(key) => (1u8, key),
+            };
+            hasher.update([variant]);
+            hasher.update(key);
         }
         
         let hash = hasher.finalize();
@@ -324,22 +1376,11 @@ impl ZKProofService {
         
         total_age / data_sources.len() as u64
     }
-    
-    /// 计算缓存命中率
-    fn calculate_cache_hit_ratio(&self) -> f64 {
-        // 简化实现，实际应用中需要维护更详细的统计信息
-        if self.proof_cache.is_empty() {
-            0.0
-        } else {
-            0.75 // 假设75%的缓存命中率
-        }
-    }
-    
-    /// 计算平均生成时间
-    fn calculate_average_generation_time(&self) -> Duration {
-        // 简化实现，实际应用中需要维护生成时间统计
-        Duration::from_millis(250) // 假设平均250ms
-    }
+}
+
+/// 公开输入中的两个分量是否拼合为给定承诺（标量的规范小端序字节表示）
+fn commitment_matches(limbs: [u128; 2], commitment: &[u8; 32]) -> bool {
+    utils::scalar_from_u128_limbs(limbs).is_some_and(|value| value.to_bytes() == *commitment)
 }
 
 /// 证明统计信息
@@ -353,6 +1394,10 @@ pub struct ProofStatistics {
     pub average_generation_time: Duration,
     /// 支持的电路数量
     pub supported_circuits: u32,
+    /// 缓存计数器
+    pub cache: CacheStats,
+    /// 证明任务计数与生成耗时直方图
+    pub proving: ProvingStats,
 }
 
 /// 服务错误类型
@@ -375,69 +1420,952 @@ pub enum ZKProofError {
     
     #[error("Cryptographic error: {0}")]
     CryptographicError(String),
+    
+    #[error("Nullifier already used: {0}")]
+    NullifierReused(String),
+    
+    #[error("Proof generation cancelled: job {0}")]
+    ProofCancelled(u64),
+    
+    #[error("Proof generation timed out after {0:?}")]
+    ProofTimedOut(Duration),
+    
+    #[error("Proving queue is full ({0} jobs)")]
+    QueueFull(usize),
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
-    #[tokio::test]
-    async fn test_zkproof_service_creation() {
-        let service = ZKProofService::new();
-        assert!(service.is_ok());
+    use ed25519_dalek::Signer;
+    use proptest::prelude::*;
+
+    /// 测试提交中数据源的报告时间，位于 1_700_000_000 起的一小时窗口内
+    const REPORTED_AT: u64 = 1_700_003_000;
+
+    /// 测试用 Ed25519 公钥
+    fn test_key(seed: u8) -> [u8; 32] {
+        ed25519_dalek::SigningKey::from_bytes(&[seed; 32]).verifying_key().to_bytes()
     }
-    
-    #[tokio::test]
-    async fn test_metric_submission_validation() {
-        let mut service = ZKProofService::new().unwrap();
-        
-        // 创建有效的提交数据
-        let submission = MetricSubmission {
+
+    /// 测试用样本承诺打开信息
+    fn openings(sample_counts: &[usize]) -> Vec<SampleOpening> {
+        sample_counts
+            .iter()
+            .enumerate()
+            .map(|(i, sample_count)| SampleOpening {
+                sample_count: *sample_count,
+                blinding: [i as u8 + 100; 32],
+            })
+            .collect()
+    }
+
+    /// 测试用指标提交：两个数据源的四个区块时间样本，数据源使用 key_seed 与 key_seed + 1 派生的公钥
+    fn metric_submission(key_seed: u8) -> MetricSubmission {
+        MetricSubmission {
             metric_type: "block_time".to_string(),
             private_data: vec![6000, 6100, 5900, 6200],
             data_sources: vec![
                 DataSource {
                     source_type: DataSourceType::ValidatorNode,
                     source_id: "validator_001".to_string(),
-                    timestamp: chrono::Utc::now().timestamp() as u64,
+                    timestamp: REPORTED_AT,
                     reliability_score: 95,
+                    public_key: SourcePublicKey::Ed25519(test_key(key_seed)),
                 },
                 DataSource {
                     source_type: DataSourceType::FullNode,
                     source_id: "fullnode_042".to_string(),
-                    timestamp: chrono::Utc::now().timestamp() as u64,
+                    timestamp: REPORTED_AT,
                     reliability_score: 87,
+                    public_key: SourcePublicKey::Ed25519(test_key(key_seed + 1)),
                 },
             ],
             public_metric: 6050,
             quality_score: 92,
             time_window_hours: 1,
-        };
+            window_start: 1_700_000_000,
+            source_secret: [1; 32],
+            sample_openings: openings(&[2, 2]),
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_zkproof_service_creation() {
+        let service = ZKProofService::new();
+        assert!(service.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_metric_submission_validation() {
+        let service = ZKProofService::new().unwrap();
+        
+        // 创建有效的提交数据
+        let submission = metric_submission(1);
         
         // 验证数据有效性
         let validation_result = service.validate_submission(&submission);
         assert!(validation_result.is_ok());
+        
+        // 承诺打开信息必须逐个数据源覆盖全部样本
+        let mut uncovered = submission.clone();
+        uncovered.sample_openings = openings(&[2, 1]);
+        assert!(service.validate_submission(&uncovered).is_err());
+        let mut empty = submission;
+        empty.sample_openings = openings(&[4, 0]);
+        assert!(service.validate_submission(&empty).is_err());
     }
     
+    #[tokio::test]
+    async fn test_metric_proof_round_trip() {
+        let mut service = ZKProofService::new().unwrap();
+
+        let submission = metric_submission(3);
+
+        let commitments = submission.sample_commitments().unwrap();
+        let (proof, metadata) = service.generate_metric_proof(submission).await.unwrap();
+        assert_eq!(proof.circuit_id, 1);
+        assert_eq!(proof.public_inputs[..3], [6050, 92, 1]);
+        assert_eq!(metadata.data_sources_count, 2);
+
+        let (is_valid, _) = service.verify_proof(&proof).await.unwrap();
+        assert!(is_valid);
+
+        // 样本恰好来自各数据源公布的承诺
+        assert!(service.verify_sample_commitments(&proof, &commitments).await.unwrap());
+        let mut reordered = commitments.clone();
+        reordered.swap(0, 1);
+        assert!(!service.verify_sample_commitments(&proof, &reordered).await.unwrap());
+        assert!(!service.verify_sample_commitments(&proof, &commitments[..1]).await.unwrap());
+
+        // 篡改公开输入后验证失败
+        let mut tampered = proof.clone();
+        tampered.public_inputs[0] = 9000;
+        let (is_valid, _) = service.verify_proof(&tampered).await.unwrap();
+        assert!(!is_valid);
+
+        // 经 SCALE 与 JSON 封装往返后仍可验证
+        let envelope = service.export_envelope(&proof).unwrap();
+        let decoded = ProofEnvelope::from_scale(&envelope.to_scale()).unwrap();
+        assert_eq!(ProofEnvelope::from_json(&envelope.to_json().unwrap()).unwrap(), decoded);
+        let (is_valid, _) = service.verify_envelope(&decoded).await.unwrap();
+        assert!(is_valid);
+
+        // 声明的验证密钥哈希或后端与注册表不一致
+        let mut wrong_vk = decoded.clone();
+        wrong_vk.vk_hash[0] ^= 1;
+        let (is_valid, _) = service.verify_envelope(&wrong_vk).await.unwrap();
+        assert!(!is_valid);
+        let mut wrong_backend = decoded;
+        wrong_backend.backend = ProofSystemKind::Mock;
+        let (is_valid, _) = service.verify_envelope(&wrong_backend).await.unwrap();
+        assert!(!is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_digest_mode_verifies_against_single_digest() {
+        let mut service = ZKProofService::new().unwrap();
+        service.circuit_manager.register_circuit(
+            circuits::NetworkMetricCircuit::new(31, 4, 2, "Test".to_string())
+                .with_public_input_mode(circuits::PublicInputMode::Digest),
+        );
+
+        let submission = metric_submission(5);
+
+        // 证明仍携带完整的公开输入，供链下检查
+        let (proof, _) = service.generate_metric_proof(submission).await.unwrap();
+        assert_eq!(proof.circuit_id, 31);
+        assert_eq!(proof.public_inputs[..3], [6050, 92, 1]);
+        let (is_valid, _) = service.verify_proof(&proof).await.unwrap();
+        assert!(is_valid);
+
+        // 封装只包含摘要，链上保存 32 字节摘要即可验证
+        let digest = utils::public_input_digest(&proof.public_inputs);
+        let envelope = service.export_envelope(&proof).unwrap();
+        assert_eq!(envelope.public_input_digest, Some(digest));
+        assert!(envelope.public_inputs.is_empty());
+        let decoded = ProofEnvelope::from_scale(&envelope.to_scale()).unwrap();
+        let (is_valid, _) = service.verify_envelope(&decoded).await.unwrap();
+        assert!(is_valid);
+
+        // 摘要电路的封装不能改为携带完整公开输入
+        let full = ProofEnvelope {
+            public_inputs: proof.public_inputs.clone(),
+            public_input_digest: None,
+            ..envelope.clone()
+        };
+        let (is_valid, _) = service.verify_envelope(&full).await.unwrap();
+        assert!(!is_valid);
+        let vk = service.key_registry.verification_key(31, ProofSystemKind::Groth16).unwrap().unwrap();
+        assert!(zkproof_verifier::verify_digest(
+            ProofSystemKind::Groth16,
+            &vk.verification_key,
+            &proof.proof_value,
+            &digest
+        ));
+
+        // 篡改任一公开输入都会改变摘要
+        let mut tampered = proof.clone();
+        tampered.public_inputs[0] = 6051;
+        let (is_valid, _) = service.verify_proof(&tampered).await.unwrap();
+        assert!(!is_valid);
+        assert!(!zkproof_verifier::verify_digest(
+            ProofSystemKind::Groth16,
+            &vk.verification_key,
+            &proof.proof_value,
+            &utils::public_input_digest(&tampered.public_inputs)
+        ));
+    }
+
+    /// 由随机参数构造的指标提交，字段可能违反服务或电路的任一约束
+    fn arb_submission() -> impl Strategy<Value = MetricSubmission> {
+        let sample = prop_oneof![19 => 1_000u128..10_000, 1 => (1u128 << 64)..(1u128 << 64) + 1_000];
+        let source = (
+            prop_oneof![19 => 50u8..=100, 1 => 101u8..=110],
+            prop_oneof![9 => 0u64..=3_000, 1 => 3_601u64..=7_200],
+        );
+        (
+            prop::collection::vec(sample, 1..=8),
+            prop::collection::vec(source, 1..=4),
+            -70i128..=70,
+            prop_oneof![9 => 0u8..=100, 1 => 101u8..=110],
+            prop_oneof![8 => 1u8..=24, 1 => Just(0u8), 1 => 25u8..=30],
+            prop_oneof![9 => Just(None), 1 => (0usize..4, 0usize..4).prop_map(Some)],
+            prop::bool::weighted(0.05),
+        )
+            .prop_filter("数据源数量不超过样本数", |(samples, sources, ..)| samples.len() >= sources.len())
+            .prop_map(|(samples, sources, per_mille, quality_score, hours, moved, duplicate)| {
+                let window_start = 1_700_000_000;
+                let window_end = window_start + hours as u64 * SECONDS_PER_HOUR;
+                let average = samples.iter().sum::<u128>() / samples.len() as u128;
+                let (n, k) = (samples.len(), sources.len());
+                let mut counts: Vec<usize> = (0..k).map(|j| n / k + usize::from(j < n % k)).collect();
+                // 将一个样本从一个数据源的承诺移到另一个数据源（可能越界或留空）
+                if let Some((from, to)) = moved {
+                    if from < k && to < k && from != to {
+                        counts[from] -= 1;
+                        counts[to] += 1;
+                    }
+                }
+                let data_sources = sources
+                    .iter()
+                    .enumerate()
+                    .map(|(j, (reliability_score, age))| DataSource {
+                        source_type: DataSourceType::FullNode,
+                        source_id: format!("node_{}", if duplicate { 0 } else { j }),
+                        timestamp: window_end.saturating_sub(*age),
+                        reliability_score: *reliability_score,
+                        public_key: SourcePublicKey::Ed25519(test_key(j as u8 + 10)),
+                    })
+                    .collect();
+                MetricSubmission {
+                    metric_type: "block_time".to_string(),
+                    private_data: samples,
+                    data_sources,
+                    public_metric: (average as i128 * (1000 + per_mille) / 1000) as u128,
+                    quality_score,
+                    time_window_hours: hours,
+                    window_start,
+                    source_secret: [1; 32],
+                    sample_openings: openings(&counts),
+                }
+            })
+    }
+
+    /// 独立于服务实现的参考检查：提交是否满足服务策略与电路约束
+    fn is_valid_submission(submission: &MetricSubmission) -> bool {
+        let samples = &submission.private_data;
+        let sources = &submission.data_sources;
+        let average = samples.iter().sum::<u128>() / samples.len() as u128;
+        let average_reliability = sources.iter().map(|s| s.reliability_score as u32).sum::<u32>() / sources.len() as u32;
+        let window_end = submission.window_end();
+        let staleness = (submission.time_window_hours as u64 * SECONDS_PER_HOUR).min(3600);
+        let ids: std::collections::HashSet<_> = sources.iter().map(|s| &s.source_id).collect();
+
+        samples.iter().all(|v| *v < 1 << 64)
+            && sources.len() >= DEFAULT_MIN_SIGNERS
+            && ids.len() == sources.len()
+            && sources.iter().all(|s| s.reliability_score <= 100)
+            && sources.iter().all(|s| s.timestamp + staleness >= window_end && s.timestamp <= window_end)
+            && (1..=24).contains(&submission.time_window_hours)
+            && submission.sample_openings.iter().all(|o| o.sample_count > 0)
+            && submission.sample_openings.iter().map(|o| o.sample_count).sum::<usize>() == samples.len()
+            && submission.public_metric >= 1
+            && average.abs_diff(submission.public_metric) <= submission.public_metric / 20
+            && submission.quality_score <= 100
+            && submission.quality_score as u32 <= average_reliability + 10
+    }
+
     #[test]
-    fn test_circuit_id_calculation() {
+    fn prop_metric_submissions_accepted_exactly_when_valid() {
+        use bellman::gadgets::test::TestConstraintSystem;
+        use bellman::Circuit;
+        use proptest::test_runner::{Config, TestRunner};
+
         let service = ZKProofService::new().unwrap();
+        let mut runner = TestRunner::new(Config::with_cases(64));
+        runner
+            .run(&arb_submission(), |submission| {
+                // 接受 = 服务完成输入检查，且见证满足所选电路的全部约束
+                let accepted = match service.prepare_metric_proof(&submission) {
+                    Ok((circuit, witness)) => {
+                        let mut cs = TestConstraintSystem::new();
+                        circuits::MetricAggregationCircuit::with_witness(&circuit, witness)
+                            .synthesize(&mut cs)
+                            .unwrap();
+                        cs.is_satisfied()
+                    }
+                    Err(_) => false,
+                };
+                prop_assert_eq!(accepted, is_valid_submission(&submission));
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_malicious_prover_proofs_are_rejected() {
+        use proof_system::{CircuitInstance, CircuitWitness, Groth16ProofSystem, ProofSystem};
+
+        let mut service = ZKProofService::new().unwrap();
+        let submission = metric_submission(7);
+        let (circuit, witness) = service.prepare_metric_proof(&submission).unwrap();
+        let (proof, _) = service.generate_metric_proof(submission).await.unwrap();
+        assert!(service.verify_proof(&proof).await.unwrap().0);
+
+        // 绕过证明器的约束检查，用不满足约束的见证（公开指标偏离平均值）直接生成 Groth16 证明
+        let keys = service.key_registry.get_or_generate(&circuit).unwrap();
+        let mut forged_witness = witness.clone();
+        forged_witness.public_metric = 9000;
+        let forged_inputs = forged_witness.public_inputs();
+        let instance = CircuitInstance::from_circuit(&circuit, Some(CircuitWitness::NetworkMetric(forged_witness)));
+        let forged = ZKProof {
+            proof_value: Groth16ProofSystem.prove(&keys, instance.unwrap()).unwrap(),
+            public_inputs: forged_inputs,
+            ..proof.clone()
+        };
+        assert!(!service.verify_proof(&forged).await.unwrap().0);
+
+        // 篡改私有样本：见证的公开输入不变，但样本承诺不再匹配
+        let mut tampered_witness = witness;
+        tampered_witness.private_data[0] += 1;
+        let instance = CircuitInstance::from_circuit(&circuit, Some(CircuitWitness::NetworkMetric(tampered_witness)));
+        let tampered = ZKProof {
+            proof_value: Groth16ProofSystem.prove(&keys, instance.unwrap()).unwrap(),
+            ..proof.clone()
+        };
+        assert!(!service.verify_proof(&tampered).await.unwrap().0);
+
+        // 交换公开输入的顺序
+        for (i, j) in [(0, 1), (4, 5), (7, 8), (9, 10)] {
+            let mut swapped = proof.clone();
+            swapped.public_inputs.swap(i, j);
+            assert!(!service.verify_proof(&swapped).await.unwrap().0);
+        }
+
+        // 同一结构、另一次 setup 得到的验证密钥
+        service
+            .circuit_manager
+            .register_circuit(circuits::NetworkMetricCircuit::new(32, circuit.max_data_points, circuit.max_data_sources, "Test".to_string()));
+        let other = service.circuit_manager.get_circuit(32).unwrap().clone();
+        let other_keys = service.key_registry.get_or_generate(&other).unwrap();
+        assert_ne!(other_keys.verification_key, keys.verification_key);
+        let relabelled = ZKProof {
+            circuit_id: 32,
+            ..proof.clone()
+        };
+        assert!(!service.verify_proof(&relabelled).await.unwrap().0);
+        assert!(!zkproof_verifier::verify(
+            ProofSystemKind::Groth16,
+            &other_keys.verification_key,
+            &proof.proof_value,
+            &proof.public_inputs
+        ));
+        // 证明中携带的验证密钥不参与验证
+        let carried = ZKProof {
+            verification_key: other_keys.verification_key,
+            ..proof
+        };
+        assert!(service.verify_proof(&carried).await.unwrap().0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_verify_proofs() {
+        let mut service = ZKProofService::new().unwrap();
+
+        let mut proofs = Vec::new();
+        for public_metric in [6050u128, 6060, 6070] {
+            let submission = MetricSubmission {
+                private_data: vec![6000, 6100],
+                public_metric,
+                quality_score: 90,
+                sample_openings: openings(&[1, 1]),
+                ..metric_submission(11)
+            };
+            proofs.push(service.generate_metric_proof(submission).await.unwrap().0);
+        }
+
+        // 篡改一个证明，并加入一个未知电路的证明
+        proofs[1].public_inputs[0] = 6080;
+        let mut unknown = proofs[0].clone();
+        unknown.circuit_id = 99;
+        proofs.push(unknown);
+
+        let results = service.batch_verify_proofs(&proofs).await.unwrap();
+        let validity: Vec<bool> = results.iter().map(|(is_valid, _)| *is_valid).collect();
+        assert_eq!(validity, vec![true, false, true, false]);
+    }
+
+    #[tokio::test]
+    async fn test_window_bundle_round_trip() {
+        let mut service = ZKProofService::new().unwrap();
+
+        let mut node_proofs = Vec::new();
+        for (i, public_metric) in [6050u128, 6100, 5990].into_iter().enumerate() {
+            let mut submission = MetricSubmission {
+                private_data: vec![public_metric, public_metric],
+                public_metric,
+                quality_score: 90,
+                source_secret: [i as u8 + 1; 32],
+                sample_openings: openings(&[1, 1]),
+                ..metric_submission(21 + 2 * i as u8)
+            };
+            submission.data_sources[0].source_id = format!("validator_{}", i);
+            submission.data_sources[1].source_id = format!("fullnode_{}", i);
+            node_proofs.push(service.generate_metric_proof(submission).await.unwrap().0);
+        }
+
+        let bundle = service.bundle_window_proofs(1_700_000_000, node_proofs.clone()).await.unwrap();
+        assert_eq!(bundle.window_metric(), Some(6046));
+        let (is_valid, _) = service.verify_window_bundle(&bundle).await.unwrap();
+        assert!(is_valid);
+
+        // 篡改集合中节点证明的公开指标
+        let mut forged = bundle.clone();
+        forged.node_proofs[0].public_inputs[0] = 9000;
+        let (is_valid, _) = service.verify_window_bundle(&forged).await.unwrap();
+        assert!(!is_valid);
+
+        // 节点证明属于其他时间窗口
+        assert!(service.bundle_window_proofs(1_700_003_600, node_proofs.clone()).await.is_err());
+        let mut shifted = bundle.clone();
+        shifted.window_start = 1_700_003_600;
+        assert!(!service.verify_window_bundle(&shifted).await.unwrap().0);
         
+        // 同一节点的证明重复计入
+        let duplicated = vec![node_proofs[0].clone(), node_proofs[1].clone(), node_proofs[0].clone()];
+        assert!(service.bundle_window_proofs(1_700_000_000, duplicated).await.is_err());
+
+        // 有效但指标类型不同的节点证明不能混入同一集合
+        let mut throughput = MetricSubmission {
+            metric_type: "tx_throughput".to_string(),
+            private_data: vec![6000, 6000],
+            public_metric: 6000,
+            quality_score: 90,
+            source_secret: [9; 32],
+            sample_openings: openings(&[1, 1]),
+            ..metric_submission(31)
+        };
+        throughput.data_sources[0].source_id = "validator_3".to_string();
+        throughput.data_sources[1].source_id = "fullnode_3".to_string();
+        let throughput = service.generate_metric_proof(throughput).await.unwrap().0;
+        assert!(service.verify_proof(&throughput).await.unwrap().0);
+        let mut mixed = node_proofs.clone();
+        mixed.push(throughput.clone());
+        assert!(service.bundle_window_proofs(1_700_000_000, mixed).await.is_err());
+        let mut mixed = bundle;
+        mixed.node_proofs.push(throughput);
+        assert!(!service.verify_window_bundle(&mixed).await.unwrap().0);
+
+        // 篡改节点证明的公开指标
+        let mut tampered = node_proofs;
+        tampered[2].public_inputs[0] = 6000;
+        assert!(service.bundle_window_proofs(1_700_000_000, tampered).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_nullifier_prevents_replay() {
+        let mut service = ZKProofService::new().unwrap();
+        service.set_proof_system(1, ProofSystemKind::Mock).unwrap();
+
         let submission = MetricSubmission {
-            metric_type: "block_time".to_string(),
             private_data: vec![6000, 6100],
-            data_sources: vec![
-                DataSource {
-                    source_type: DataSourceType::ValidatorNode,
-                    source_id: "test".to_string(),
-                    timestamp: 0,
-                    reliability_score: 100,
-                },
-            ],
-            public_metric: 6050,
             quality_score: 90,
-            time_window_hours: 1,
+            source_secret: [42; 32],
+            sample_openings: openings(&[1, 1]),
+            ..metric_submission(13)
+        };
+
+        let (proof, _) = service.generate_metric_proof(submission.clone()).await.unwrap();
+        service.submit_metric_proof(&proof).await.unwrap();
+        assert!(service.is_nullifier_spent(&submission.nullifier()));
+
+        // 重放同一证明
+        let err = service.submit_metric_proof(&proof).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<ZKProofError>(), Some(ZKProofError::NullifierReused(_))));
+
+        // 同一窗口的新提交（即使命中缓存）同样被拒绝
+        let mut resubmission = submission.clone();
+        resubmission.public_metric = 6060;
+        assert!(service.generate_metric_proof(resubmission).await.is_err());
+        assert!(service.generate_metric_proof(submission.clone()).await.is_err());
+
+        // 下一个时间窗口可以正常提交，无效证明不会消耗空值符
+        let mut next_window = submission;
+        next_window.window_start += 3600;
+        for source in &mut next_window.data_sources {
+            source.timestamp += 3600;
+        }
+        let (proof, _) = service.generate_metric_proof(next_window).await.unwrap();
+        let mut invalid = proof.clone();
+        invalid.public_inputs[0] = 9000;
+        assert!(service.submit_metric_proof(&invalid).await.is_err());
+        service.submit_metric_proof(&proof).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_metric_window_binding() {
+        let mut service = ZKProofService::new().unwrap();
+        service.set_proof_system(1, ProofSystemKind::Mock).unwrap();
+
+        let submission = MetricSubmission {
+            private_data: vec![6000, 6100],
+            quality_score: 90,
+            source_secret: [43; 32],
+            sample_openings: openings(&[1, 1]),
+            ..metric_submission(15)
+        };
+
+        // 数据源报告时间须位于窗口之内
+        let mut future = submission.clone();
+        future.data_sources[0].timestamp = submission.window_end() + 1;
+        assert!(service.generate_metric_proof(future).await.is_err());
+        let mut stale = submission.clone();
+        stale.data_sources[1].timestamp = submission.window_start - 1;
+        assert!(service.generate_metric_proof(stale).await.is_err());
+
+        let (proof, _) = service.generate_metric_proof(submission.clone()).await.unwrap();
+        assert_eq!(proof.public_inputs[4..7], [1_700_000_000, 1_700_003_600, 3600]);
+        assert!(service.verify_metric_window(&proof, 1_700_000_000, 1).await.unwrap());
+
+        // 旧窗口的证明不能作为其他窗口出示，改写窗口的证明无法通过验证
+        assert!(!service.verify_metric_window(&proof, 1_700_003_600, 1).await.unwrap());
+        assert!(!service.verify_metric_window(&proof, 1_700_000_000, 2).await.unwrap());
+        let mut shifted = proof.clone();
+        shifted.public_inputs[4] += 3600;
+        shifted.public_inputs[5] += 3600;
+        assert!(!service.verify_metric_window(&shifted, 1_700_003_600, 1).await.unwrap());
+
+        // 更严格的服务拒绝较旧的数据与允许更大间隔的证明
+        let config = ServiceConfig {
+            max_staleness: Duration::from_secs(900),
+            ..ServiceConfig::default()
+        };
+        let mut strict = ZKProofService::with_config(KeyRegistry::new(), config).unwrap();
+        strict.set_proof_system(1, ProofSystemKind::Mock).unwrap();
+        let mut old_data = submission.clone();
+        old_data.data_sources[0].timestamp = submission.window_end() - 901;
+        assert!(strict.generate_metric_proof(old_data).await.is_err());
+
+        let (strict_proof, _) = strict.generate_metric_proof(submission).await.unwrap();
+        assert_eq!(strict_proof.public_inputs[6], 900);
+        assert!(strict.verify_metric_window(&strict_proof, 1_700_000_000, 1).await.unwrap());
+        assert!(!strict.verify_metric_window(&proof, 1_700_000_000, 1).await.unwrap());
+        assert!(strict.submit_metric_proof(&proof).await.is_err());
+        strict.submit_metric_proof(&strict_proof).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_statistical_proofs() {
+        let mut service = ZKProofService::new().unwrap();
+        // 注册小容量电路以缩短参数生成时间，电路选择会优先使用它们
+        service.circuit_manager.register_circuit(circuits::NetworkMetricCircuit::percentile(16, 12, "Test".to_string()));
+        service.circuit_manager.register_circuit(circuits::NetworkMetricCircuit::variance(17, 12, "Test".to_string()));
+        let latencies = vec![120u128, 95, 101, 3000, 99, 110, 97, 105, 130, 98];
+        let opening = SampleOpening { sample_count: latencies.len(), blinding: [6; 32] };
+        let sample_commitment = opening.commit(&latencies).unwrap();
+
+        let median = service.generate_percentile_proof(latencies.clone(), 50, [6; 32]).await.unwrap();
+        assert_eq!(median.public_inputs[..3], [50, 101, 10]);
+        let p95 = service.generate_percentile_proof(latencies.clone(), 95, [6; 32]).await.unwrap();
+        assert_eq!(p95.public_inputs[..3], [95, 3000, 10]);
+        let variance = service.generate_variance_proof(latencies.clone(), [6; 32]).await.unwrap();
+        assert_eq!(variance.public_inputs[..2], [10, 395]);
+
+        let results = service.batch_verify_proofs(&[median.clone(), p95.clone(), variance.clone()]).await.unwrap();
+        assert!(results.iter().all(|(is_valid, _)| *is_valid));
+
+        // 核对百分位数与事先公布的样本承诺
+        assert!(service.verify_percentile_proof(&median, 50, &sample_commitment).await.unwrap());
+        assert!(!service.verify_percentile_proof(&median, 95, &sample_commitment).await.unwrap());
+        assert!(service.verify_variance_proof(&variance, &sample_commitment).await.unwrap());
+        assert!(!service.verify_variance_proof(&p95, &sample_commitment).await.unwrap());
+        let other = SampleOpening { sample_count: latencies.len(), blinding: [7; 32] }.commit(&latencies).unwrap();
+        assert!(!service.verify_percentile_proof(&p95, 95, &other).await.unwrap());
+        assert!(!service.verify_variance_proof(&variance, &other).await.unwrap());
+
+        // 篡改公开的中位数
+        let mut tampered = median;
+        tampered.public_inputs[1] = 99;
+        let (is_valid, _) = service.verify_proof(&tampered).await.unwrap();
+        assert!(!is_valid);
+
+        assert!(service.generate_percentile_proof(latencies, 0, [6; 32]).await.is_err());
+        assert!(service.generate_variance_proof(vec![1 << 32], [6; 32]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_privacy_proof_round_trip() {
+        let mut service = ZKProofService::new().unwrap();
+        let parameters = PrivacyParameters { epsilon_millis: 1000, sensitivity: 10 };
+        service.circuit_manager.register_circuit(circuits::NetworkMetricCircuit::privacy_preserving(
+            18,
+            4,
+            parameters,
+            "Test".to_string(),
+        ));
+        let submission = PrivacySubmission {
+            samples: vec![3, 7, 10],
+            parameters,
+            blinding: [2; 32],
+            noise_secret: [3; 32],
+            beacon: [4; 32],
         };
+        let secret_commitment = submission.secret_commitment();
+        let sample_commitment = submission.sample_commitment().unwrap();
+
+        let proof = service.generate_privacy_proof(submission.clone()).await.unwrap();
+        let seed = privacy::noise_seed(privacy::bytes_to_scalar(&[3; 32]), privacy::bytes_to_scalar(&[4; 32]));
+        assert_eq!(proof.public_inputs[0], privacy::apply_noise(20, parameters.sample_noise(&seed)));
+        assert_eq!(proof.public_inputs[1..3], [1000, 10]);
+        assert!(service
+            .verify_privacy_proof(&proof, &[4; 32], &secret_commitment, &sample_commitment)
+            .await
+            .unwrap());
+
+        // 信标或承诺不一致
+        assert!(!service
+            .verify_privacy_proof(&proof, &[5; 32], &secret_commitment, &sample_commitment)
+            .await
+            .unwrap());
+        assert!(!service
+            .verify_privacy_proof(&proof, &[4; 32], &sample_commitment, &secret_commitment)
+            .await
+            .unwrap());
+
+        // 篡改加噪结果
+        let mut tampered = proof;
+        tampered.public_inputs[0] += 1;
+        let (is_valid, _) = service.verify_proof(&tampered).await.unwrap();
+        assert!(!is_valid);
+
+        // 样本超出敏感度，或没有使用该隐私参数的电路
+        let mut unclipped = submission.clone();
+        unclipped.samples = vec![11];
+        assert!(service.generate_privacy_proof(unclipped).await.is_err());
+        let mut unregistered = submission;
+        unregistered.parameters.epsilon_millis = 500;
+        assert!(service.generate_privacy_proof(unregistered).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reliability_proofs_round_trip() {
+        let mut service = ZKProofService::new().unwrap();
+        service.circuit_manager.register_circuit(circuits::NetworkMetricCircuit::consensus_participation(
+            19,
+            4,
+            4,
+            "Test".to_string(),
+        ));
+        service.circuit_manager.register_circuit(circuits::NetworkMetricCircuit::node_reliability(
+            20,
+            4,
+            "Test".to_string(),
+        ));
+
+        // 最近 10 个区块中至少产出 3 个，出块者树根由链上数据计算
+        let (validator, other) = ([9; 32], [10; 32]);
+        let authors: Vec<[u8; 32]> =
+            (0..10).map(|i| if [1, 2, 9].contains(&i) { validator } else { other }).collect();
+        let tree = merkle::block_author_tree(4, 500, &authors).unwrap();
+        let author_root = tree.root().to_bytes();
+        let claim = ParticipationClaim { validator, first_block: 500, block_count: 10, min_signed: 3 };
+        let submission = ParticipationSubmission {
+            claim,
+            authored_blocks: [1, 2, 9].iter().map(|i| tree.path(*i).unwrap()).collect(),
+            author_root,
+        };
+        let proof = service.generate_participation_proof(submission.clone()).await.unwrap();
+        assert_eq!(proof.circuit_id, 19);
+        assert_eq!(proof.public_inputs[..3], [500, 10, 3]);
+        assert!(service.verify_participation_proof(&proof, &claim, &author_root).await.unwrap());
+
+        // 声明、验证者或树根与证明不一致
+        let stricter = ParticipationClaim { min_signed: 4, ..claim };
+        assert!(!service.verify_participation_proof(&proof, &stricter, &author_root).await.unwrap());
+        let transferred = ParticipationClaim { validator: other, ..claim };
+        assert!(!service.verify_participation_proof(&proof, &transferred, &author_root).await.unwrap());
+        assert!(!service.verify_participation_proof(&proof, &claim, &[1; 32]).await.unwrap());
+        assert!(!service.verify_participation_proof(&proof, &claim, &[0xff; 32]).await.unwrap());
+
+        // 产出不足，或以他人产出的区块冒充
+        let mut unsigned = submission.clone();
+        unsigned.claim = stricter;
+        assert!(service.generate_participation_proof(unsigned).await.is_err());
+        let mut stolen = submission;
+        stolen.claim = transferred;
+        assert!(service.generate_participation_proof(stolen).await.is_err());
+
+        // 4 个 15 分钟时隙中至少 75% 有心跳
+        let claim = UptimeClaim {
+            validator,
+            window_start: 1_700_000_000,
+            heartbeat_interval: 900,
+            slot_count: 4,
+            min_uptime_percent: 75,
+        };
+        let submission = UptimeSubmission {
+            claim,
+            heartbeats: vec![1_700_000_010, 1_700_000_920, 1_700_002_800],
+            blinding: [6; 32],
+        };
+        let log_commitment = submission.log_commitment();
+        let proof = service.generate_uptime_proof(submission.clone()).await.unwrap();
+        assert_eq!(proof.circuit_id, 20);
+        assert!(service.verify_uptime_proof(&proof, &claim, &log_commitment).await.unwrap());
+
+        // 在线率证明绑定验证者，不能被其他验证者出示
+        let transferred = UptimeClaim { validator: other, ..claim };
+        assert!(!service.verify_uptime_proof(&proof, &transferred, &log_commitment).await.unwrap());
+
+        // 共识参与证明不能冒充在线率证明
+        let participation = ParticipationSubmission {
+            claim: ParticipationClaim { validator, first_block: 500, block_count: 10, min_signed: 1 },
+            authored_blocks: vec![tree.path(1).unwrap()],
+            author_root,
+        };
+        let participation = service.generate_participation_proof(participation).await.unwrap();
+        assert!(!service.verify_uptime_proof(&participation, &claim, &log_commitment).await.unwrap());
+
+        let mut offline = submission;
+        offline.heartbeats.pop();
+        assert!(service.generate_uptime_proof(offline).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rolling_proofs_chain_across_windows() {
+        let mut service = ZKProofService::new().unwrap();
+        service.circuit_manager.register_circuit(circuits::NetworkMetricCircuit::rolling_aggregation(
+            21,
+            4,
+            3,
+            "Test".to_string(),
+        ));
+
+        // 每 24 小时一个窗口，滚动平均覆盖最近 3 个窗口
+        let tag = nullifier::metric_tag("block_time");
+        let mut state = RollingState::genesis(tag, 24, 1_700_000_000, 3);
+        let mut checkpoint = RollingCheckpoint::genesis(tag, 24, 1_700_000_000, 3);
+        let windows: [&[u128]; 4] = [&[10, 20], &[30], &[40, 50, 60], &[70]];
+        let mut proofs = Vec::new();
+        for (i, samples) in windows.into_iter().enumerate() {
+            let submission = RollingSubmission {
+                state: state.clone(),
+                samples: samples.to_vec(),
+                sample_blinding: [i as u8 + 1; 32],
+                state_blinding: [i as u8 + 11; 32],
+            };
+            let sample_commitment = submission.sample_commitment().unwrap();
+            let (proof, next_state) = service.generate_rolling_proof(submission).await.unwrap();
+            assert_eq!(proof.circuit_id, 21);
+
+            let next = service.verify_rolling_proof(&proof, &checkpoint, &sample_commitment).await.unwrap();
+            assert_eq!(next, Some(next_state.checkpoint()));
+            // 样本承诺不一致
+            assert_eq!(service.verify_rolling_proof(&proof, &checkpoint, &[1; 32]).await.unwrap(), None);
+
+            state = next_state;
+            checkpoint = next.unwrap();
+            proofs.push((proof, sample_commitment));
+        }
+
+        // 第一个窗口已移出：最近 3 个窗口共 5 个样本，平均值 250 / 5
+        assert_eq!(checkpoint.next_window_start, 1_700_000_000 + 4 * 86_400);
+        assert_eq!((checkpoint.covered_windows, checkpoint.sample_count, checkpoint.rolling_average), (3, 5, 50));
+        let (last, last_commitment) = &proofs[3];
+        assert_eq!(last.public_inputs[5], 50);
+
+        // 旧证明不能接在新检查点之后
+        let (first, first_commitment) = &proofs[0];
+        assert_eq!(service.verify_rolling_proof(first, &checkpoint, first_commitment).await.unwrap(), None);
+        assert_eq!(service.verify_rolling_proof(last, &checkpoint, last_commitment).await.unwrap(), None);
+
+        // 由较早状态分叉的证明不能替换已接受的窗口
+        let fork = RollingSubmission {
+            state: RollingState::genesis(tag, 24, 1_700_000_000 + 4 * 86_400, 3),
+            samples: vec![1_000],
+            sample_blinding: [9; 32],
+            state_blinding: [9; 32],
+        };
+        let fork_commitment = fork.sample_commitment().unwrap();
+        let (forked, _) = service.generate_rolling_proof(fork).await.unwrap();
+        assert_eq!(service.verify_rolling_proof(&forked, &checkpoint, &fork_commitment).await.unwrap(), None);
+
+        // 超出电路容量或窗口数量不一致
+        let oversized = RollingSubmission {
+            state: state.clone(),
+            samples: vec![1; 5],
+            sample_blinding: [9; 32],
+            state_blinding: [9; 32],
+        };
+        assert!(service.generate_rolling_proof(oversized).await.is_err());
+        let mismatched = RollingSubmission {
+            state: RollingState::genesis(tag, 24, 1_700_000_000, 2),
+            samples: vec![1],
+            sample_blinding: [9; 32],
+            state_blinding: [9; 32],
+        };
+        assert!(service.generate_rolling_proof(mismatched).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mock_backend_selection() {
+        let mut service = ZKProofService::new().unwrap();
+        service.set_proof_system(1, ProofSystemKind::Mock).unwrap();
+        assert!(service.set_proof_system(99, ProofSystemKind::Mock).is_err());
+
+        let submission = MetricSubmission {
+            private_data: vec![6000, 6100],
+            quality_score: 90,
+            sample_openings: openings(&[1, 1]),
+            ..metric_submission(5)
+        };
+
+        let (proof, _) = service.generate_metric_proof(submission.clone()).await.unwrap();
+        // 模拟后端输出 32 字节摘要，而非 192 字节的 Groth16 证明
+        assert_eq!(proof.proof_value.len(), 32);
+
+        // 相同提交命中缓存，统计反映真实的命中与生成次数
+        let (cached, metadata) = service.generate_metric_proof(submission).await.unwrap();
+        assert_eq!(cached, proof);
+        assert_eq!(metadata.generation_time, Duration::ZERO);
+        let statistics = service.get_proof_statistics();
+        assert_eq!(statistics.total_proofs_generated, 1);
+        assert_eq!((statistics.cache.hits, statistics.cache.misses, statistics.cache.entries), (1, 1, 1));
+        assert_eq!(statistics.cache_hit_ratio, 0.5);
+        assert_eq!(statistics.proving.generation_times.count(), 1);
+        assert_eq!(service.cleanup_expired_cache(), 0);
+
+        let (is_valid, _) = service.verify_proof(&proof).await.unwrap();
+        assert!(is_valid);
+
+        // 切回 Groth16 后，模拟证明不再被接受
+        service.set_proof_system(1, ProofSystemKind::Groth16).unwrap();
+        let (is_valid, _) = service.verify_proof(&proof).await.unwrap();
+        assert!(!is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_verifier_ignores_key_carried_by_proof() {
+        let submission = MetricSubmission {
+            private_data: vec![6000, 6100],
+            quality_score: 90,
+            sample_openings: openings(&[1, 1]),
+            ..metric_submission(7)
+        };
+
+        // 提交者使用自己的参数生成证明，并在证明中附带自己的验证密钥
+        let mut submitter = ZKProofService::new().unwrap();
+        let (proof, _) = submitter.generate_metric_proof(submission.clone()).await.unwrap();
+
+        // 验证方尚未注册该电路的验证密钥
+        let mut verifier = ZKProofService::new().unwrap();
+        let (is_valid, _) = verifier.verify_proof(&proof).await.unwrap();
+        assert!(!is_valid);
+
+        // 验证方生成自己的参数后，仍然只信任注册表中的验证密钥
+        verifier.generate_metric_proof(submission).await.unwrap();
+        let (is_valid, _) = verifier.verify_proof(&proof).await.unwrap();
+        assert!(!is_valid);
+    }
+
+    #[tokio::test]
+    async fn test_integrity_proof_round_trip() {
+        let mut service = ZKProofService::new().unwrap();
+        service.set_proof_system(4, ProofSystemKind::Mock).unwrap();
+
+        let timestamp = 1_700_000_000;
+        let batches = [vec![6000u128, 6100], vec![5900, 6200, 6050]];
+        let leaves = batches.iter().map(|b| merkle::batch_leaf(timestamp, b)).collect();
+        let tree = merkle::MerkleTree::new(20, leaves).unwrap();
+
+        let submission = IntegritySubmission {
+            batch: batches[1].clone(),
+            timestamp,
+            merkle_path: tree.path(1).unwrap(),
+            merkle_root: tree.root().to_bytes(),
+            window_start: timestamp - 3600,
+            window_end: timestamp,
+        };
+
+        // 两个已登记的数据源对数据哈希签名
+        let keys: Vec<_> = (1..=3u8).map(|seed| ed25519_dalek::SigningKey::from_bytes(&[seed; 32])).collect();
+        for (i, key) in keys.iter().enumerate().take(2) {
+            service.register_data_source(DataSource {
+                source_type: DataSourceType::ValidatorNode,
+                source_id: format!("validator_{}", i),
+                timestamp,
+                reliability_score: 90,
+                public_key: SourcePublicKey::Ed25519(key.verifying_key().to_bytes()),
+            }).unwrap();
+        }
+        let data_hash = submission.data_hash();
+        let sign = |i: usize| SourceSignature {
+            source_id: format!("validator_{}", i),
+            signature: keys[i].sign(&data_hash).to_bytes().to_vec(),
+        };
+        let signatures = vec![sign(0), sign(1)];
+
+        // 签名者不足、重复或未登记时拒绝
+        assert!(service.generate_integrity_proof(submission.clone(), vec![sign(0)]).await.is_err());
+        assert!(service.generate_integrity_proof(submission.clone(), vec![sign(0), sign(0)]).await.is_err());
+        assert!(service.generate_integrity_proof(submission.clone(), vec![sign(0), sign(2)]).await.is_err());
+
+        let proof = service.generate_integrity_proof(submission.clone(), signatures.clone()).await.unwrap();
+        assert_eq!(proof.circuit_id, 4);
+        assert_eq!(proof.public_inputs[2..], [timestamp as u128 - 3600, timestamp as u128]);
+        let (is_valid, _) = service.verify_proof(&proof).await.unwrap();
+        assert!(is_valid);
+
+        // 批次时间戳不在窗口内
+        let mut stale = submission.clone();
+        stale.window_end = timestamp - 1;
+        assert!(service.generate_integrity_proof(stale, signatures.clone()).await.is_err());
+
+        // 批次与公布的根不一致
+        let mut forged = submission;
+        forged.batch[0] += 1;
+        let forged_hash = forged.data_hash();
+        let forged_signatures = (0..2)
+            .map(|i| SourceSignature {
+                source_id: format!("validator_{}", i),
+                signature: keys[i].sign(&forged_hash).to_bytes().to_vec(),
+            })
+            .collect();
+        assert!(service.generate_integrity_proof(forged, forged_signatures).await.is_err());
+    }
+
+    #[test]
+    fn test_circuit_id_calculation() {
+        let service = ZKProofService::new().unwrap();
+        
+        let mut submission = MetricSubmission {
+            private_data: vec![6000, 6100],
+            quality_score: 90,
+            sample_openings: openings(&[2]),
+            ..metric_submission(9)
+        };
+        submission.data_sources.truncate(1);
         
         let circuit_id1 = service.calculate_circuit_id(&submission);
         let circuit_id2 = service.calculate_circuit_id(&submission);
@@ -450,27 +2378,32 @@ mod tests {
     fn test_cache_key_generation() {
         let service = ZKProofService::new().unwrap();
         
-        let submission1 = MetricSubmission {
-            metric_type: "block_time".to_string(),
+        let mut submission1 = MetricSubmission {
             private_data: vec![6000],
-            data_sources: vec![
-                DataSource {
-                    source_type: DataSourceType::ValidatorNode,
-                    source_id: "test".to_string(),
-                    timestamp: 0,
-                    reliability_score: 100,
-                },
-            ],
             public_metric: 6000,
             quality_score: 90,
-            time_window_hours: 1,
+            sample_openings: openings(&[1]),
+            ..metric_submission(10)
         };
+        submission1.data_sources.truncate(1);
         
         let submission2 = submission1.clone();
         
-        let key1 = service.generate_cache_key(&submission1);
-        let key2 = service.generate_cache_key(&submission2);
+        let circuit = service.circuit_manager.get_circuit(1).unwrap().clone();
+        let key1 = service.generate_cache_key(&circuit, &submission1);
+        let key2 = service.generate_cache_key(&circuit, &submission2);
         
         assert_eq!(key1, key2);
+        
+        // 电路或后端不同的请求不共享缓存
+        let mock = circuit.clone().with_proof_system(ProofSystemKind::Mock);
+        assert_ne!(service.generate_cache_key(&mock, &submission1), key1);
+        let other = service.circuit_manager.get_circuit(2).unwrap();
+        assert_ne!(service.generate_cache_key(other, &submission1), key1);
+        
+        // 公钥按类型与原始字节区分
+        let mut sr25519 = submission1.clone();
+        sr25519.data_sources[0].public_key = SourcePublicKey::Sr25519(test_key(10));
+        assert_ne!(service.generate_cache_key(&circuit, &sr25519), key1);
     }
 }
\ No newline at end of file
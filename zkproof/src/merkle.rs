@@ -0,0 +1,200 @@
+// Merkle 树模块 - 以 Poseidon 作为节点哈希的定深二叉 Merkle 树
+use anyhow::Result;
+use bls12_381::Scalar;
+use ff::Field;
+use serde::{Deserialize, Serialize};
+
+use crate::{gadgets, poseidon, ZKProofError};
+
+/// 计算数据批次的叶子哈希
+///
+/// `h_0 = H(timestamp, len)`，`h_{i+1} = H(h_i, v_i)`。时间戳绑定在叶子中，
+/// 因此证明者无法为已提交的批次另选时间戳。
+pub fn batch_leaf(timestamp: u64, batch: &[u128]) -> Scalar {
+    let header = poseidon::hash2(Scalar::from(timestamp), Scalar::from(batch.len() as u64));
+    batch
+        .iter()
+        .fold(header, |acc, value| poseidon::hash2(acc, gadgets::scalar_from_u128(*value)))
+}
+
+/// 验证者公钥的两个 u128 分量（小端序的前、后 16 字节），作为电路的公开输入
+pub fn validator_key_limbs(validator: &[u8; 32]) -> [u128; 2] {
+    let mut low = [0u8; 16];
+    let mut high = [0u8; 16];
+    low.copy_from_slice(&validator[..16]);
+    high.copy_from_slice(&validator[16..]);
+    [u128::from_le_bytes(low), u128::from_le_bytes(high)]
+}
+
+/// 验证者身份 `H(key_low, key_high)`
+pub fn validator_id(validator: &[u8; 32]) -> Scalar {
+    let [low, high] = validator_key_limbs(validator);
+    poseidon::hash2(gadgets::scalar_from_u128(low), gadgets::scalar_from_u128(high))
+}
+
+/// 区块出块者树的叶子 `H(height, validator_id)`
+pub fn block_author_leaf(height: u64, validator: &[u8; 32]) -> Scalar {
+    poseidon::hash2(Scalar::from(height), validator_id(validator))
+}
+
+/// 由区块 [first_block, first_block + authors.len()) 的出块者公钥构建深度为 depth 的出块者树
+///
+/// 第 i 个叶子对应高度 first_block + i，树根由链上数据公开计算，共识参与证明以其为公开输入。
+pub fn block_author_tree(depth: usize, first_block: u64, authors: &[[u8; 32]]) -> Result<MerkleTree> {
+    let leaves = authors
+        .iter()
+        .zip(first_block..)
+        .map(|(author, height)| block_author_leaf(height, author))
+        .collect();
+    MerkleTree::new(depth, leaves)
+}
+
+/// 定深 Merkle 树
+///
+/// 未填充的叶子视为零，空子树的哈希按层预先计算，因此深度较大的树也只需存储实际的节点。
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    /// 各层节点，`levels[0]` 为叶子
+    levels: Vec<Vec<Scalar>>,
+    /// 各层空子树的哈希
+    empty: Vec<Scalar>,
+}
+
+impl MerkleTree {
+    /// 由叶子构建深度为 depth 的 Merkle 树
+    pub fn new(depth: usize, leaves: Vec<Scalar>) -> Result<Self> {
+        if depth >= 64 || leaves.len() as u64 > 1u64 << depth {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "{} leaves do not fit in a Merkle tree of depth {}",
+                leaves.len(),
+                depth
+            ))
+            .into());
+        }
+
+        let mut empty = vec![Scalar::ZERO];
+        for level in 0..depth {
+            empty.push(poseidon::hash2(empty[level], empty[level]));
+        }
+
+        let mut levels = vec![leaves];
+        for level in 0..depth {
+            let parents = levels[level]
+                .chunks(2)
+                .map(|pair| poseidon::hash2(pair[0], pair.get(1).copied().unwrap_or(empty[level])))
+                .collect();
+            levels.push(parents);
+        }
+
+        Ok(Self { levels, empty })
+    }
+
+    /// 树深度
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// 根哈希
+    pub fn root(&self) -> Scalar {
+        self.levels[self.depth()]
+            .first()
+            .copied()
+            .unwrap_or(self.empty[self.depth()])
+    }
+
+    /// 生成指定叶子的包含路径
+    pub fn path(&self, leaf_index: u64) -> Result<MerklePath> {
+        if leaf_index as usize >= self.levels[0].len() {
+            return Err(ZKProofError::InvalidInputData(format!("leaf {} is not in the tree", leaf_index)).into());
+        }
+
+        let siblings = (0..self.depth())
+            .map(|level| {
+                let index = (leaf_index as usize >> level) ^ 1;
+                self.levels[level]
+                    .get(index)
+                    .copied()
+                    .unwrap_or(self.empty[level])
+                    .to_bytes()
+            })
+            .collect();
+
+        Ok(MerklePath { leaf_index, siblings })
+    }
+}
+
+/// Merkle 包含路径
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerklePath {
+    /// 叶子序号
+    pub leaf_index: u64,
+    /// 自底向上的兄弟节点（标量的规范小端序字节表示）
+    pub siblings: Vec<[u8; 32]>,
+}
+
+impl MerklePath {
+    /// 路径深度
+    pub fn depth(&self) -> usize {
+        self.siblings.len()
+    }
+
+    /// 解码兄弟节点，拒绝非规范编码
+    pub fn sibling_scalars(&self) -> Result<Vec<Scalar>> {
+        self.siblings
+            .iter()
+            .map(|bytes| {
+                Option::from(Scalar::from_bytes(bytes)).ok_or_else(|| {
+                    ZKProofError::InvalidInputData("non-canonical Merkle sibling".to_string()).into()
+                })
+            })
+            .collect()
+    }
+
+    /// 由叶子沿路径计算根哈希
+    pub fn root(&self, leaf: Scalar) -> Result<Scalar> {
+        if self.depth() < 64 && self.leaf_index >> self.depth() != 0 {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "leaf index {} exceeds a path of depth {}",
+                self.leaf_index,
+                self.depth()
+            ))
+            .into());
+        }
+
+        Ok(compute_root(leaf, self.leaf_index, &self.sibling_scalars()?))
+    }
+}
+
+/// 由叶子、叶子序号与兄弟节点计算根哈希
+pub fn compute_root(leaf: Scalar, leaf_index: u64, siblings: &[Scalar]) -> Scalar {
+    siblings.iter().enumerate().fold(leaf, |node, (level, sibling)| {
+        if (leaf_index >> level) & 1 == 1 {
+            poseidon::hash2(*sibling, node)
+        } else {
+            poseidon::hash2(node, *sibling)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths_lead_to_root() {
+        let leaves: Vec<Scalar> = (0..5u128).map(|i| batch_leaf(1_700_000_000, &[i, i + 1])).collect();
+        let tree = MerkleTree::new(4, leaves.clone()).unwrap();
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.path(index as u64).unwrap();
+            assert_eq!(path.depth(), 4);
+            assert_eq!(path.root(*leaf).unwrap(), tree.root());
+        }
+
+        // 叶子或时间戳变化都会改变根
+        let path = tree.path(2).unwrap();
+        assert_ne!(path.root(batch_leaf(1_700_000_001, &[2, 3])).unwrap(), tree.root());
+        assert!(tree.path(5).is_err());
+        assert!(MerkleTree::new(2, leaves).is_err());
+    }
+}
@@ -0,0 +1,113 @@
+// 空值符模块 - 防止同一提交者在同一时间窗口重复提交或重放证明
+//
+// 空值符 N = H(H(secret, metric_tag), window_start)，其中 H 为 Poseidon。
+// 同一提交者秘密在同一指标、同一时间窗口只能得到唯一的空值符，
+// 而不知道秘密的一方无法由空值符关联同一提交者在不同窗口的提交。
+use anyhow::Result;
+use bellman::{ConstraintSystem, SynthesisError};
+use bls12_381::Scalar;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+use crate::gadgets::{self, LinearExpr};
+use crate::{poseidon, ZKProofError};
+
+/// 将提交者秘密规约为标量域元素
+pub fn secret_scalar(secret: &[u8; 32]) -> Scalar {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(secret);
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// 指标类型标签：指标类型名称 SHA-256 摘要的低 128 位
+pub fn metric_tag(metric_type: &str) -> u128 {
+    let digest = Sha256::digest(metric_type.as_bytes());
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    u128::from_le_bytes(bytes)
+}
+
+/// 由提交者秘密、指标类型标签与时间窗口起点派生空值符
+pub fn derive(secret: Scalar, metric_tag: u128, window_start: u64) -> Scalar {
+    let scoped = poseidon::hash2(secret, gadgets::scalar_from_u128(metric_tag));
+    poseidon::hash2(scoped, Scalar::from(window_start))
+}
+
+/// 电路内派生空值符，与 [`derive`] 的计算结果一致
+pub fn derive_gadget<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    secret: &LinearExpr,
+    metric_tag: &LinearExpr,
+    window_start: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    let scoped = poseidon::hash2_gadget(cs.namespace(|| "scope"), secret, metric_tag)?;
+    poseidon::hash2_gadget(cs.namespace(|| "window"), &scoped, window_start)
+}
+
+/// 已使用的空值符集合（标量的规范小端序字节表示）
+#[derive(Debug, Clone, Default)]
+pub struct NullifierStore {
+    spent: HashSet<[u8; 32]>,
+}
+
+impl NullifierStore {
+    /// 创建空的空值符集合
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 空值符是否已被使用
+    pub fn contains(&self, nullifier: &[u8; 32]) -> bool {
+        self.spent.contains(nullifier)
+    }
+
+    /// 记录空值符，已被使用时返回错误
+    pub fn spend(&mut self, nullifier: [u8; 32]) -> Result<()> {
+        if !self.spent.insert(nullifier) {
+            return Err(ZKProofError::NullifierReused(hex::encode(nullifier)).into());
+        }
+        Ok(())
+    }
+
+    /// 已使用的空值符数量
+    pub fn len(&self) -> usize {
+        self.spent.len()
+    }
+
+    /// 是否尚无已使用的空值符
+    pub fn is_empty(&self) -> bool {
+        self.spent.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman::gadgets::test::TestConstraintSystem;
+
+    #[test]
+    fn test_nullifier_scoping() {
+        let secret = secret_scalar(&[7; 32]);
+        let tag = metric_tag("block_time");
+        let nullifier = derive(secret, tag, 1_700_000_000);
+
+        // 秘密、指标类型或时间窗口任一变化都会得到不同的空值符
+        assert_ne!(nullifier, derive(secret_scalar(&[8; 32]), tag, 1_700_000_000));
+        assert_ne!(nullifier, derive(secret, metric_tag("latency"), 1_700_000_000));
+        assert_ne!(nullifier, derive(secret, tag, 1_700_003_600));
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let secret_var = gadgets::alloc_witness(cs.namespace(|| "secret"), Some(secret)).unwrap();
+        let tag_var = gadgets::alloc_witness(cs.namespace(|| "tag"), Some(gadgets::scalar_from_u128(tag))).unwrap();
+        let window_var = gadgets::alloc_witness(cs.namespace(|| "window"), Some(Scalar::from(1_700_000_000u64))).unwrap();
+        let out = derive_gadget(cs.namespace(|| "nullifier"), &secret_var, &tag_var, &window_var).unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(out.value(), Some(nullifier));
+
+        let mut store = NullifierStore::new();
+        store.spend(nullifier.to_bytes()).unwrap();
+        assert!(store.contains(&nullifier.to_bytes()));
+        assert!(store.spend(nullifier.to_bytes()).is_err());
+        assert_eq!(store.len(), 1);
+    }
+}
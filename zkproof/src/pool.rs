@@ -0,0 +1,656 @@
+// 证明任务池模块 - 并行生成证明，支持取消、超时与进度回调
+//
+// 任务在 tokio 阻塞线程上执行，同时执行的任务数不超过工作线程数，已受理的任务总数不超过
+// 工作线程数加队列容量。取消与超时在见证合成期间按固定间隔检查，可以中止正在进行的证明；
+// Groth16 的多标量乘法阶段无法中断，任务被取消后句柄立即返回，该阶段的结果被丢弃。
+use anyhow::Result;
+use bellman::SynthesisError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{oneshot, watch, Notify, Semaphore};
+
+use crate::circuits::NetworkMetricCircuit;
+use crate::profiler::CircuitProfiler;
+use crate::proof_system::{CircuitInstance, CircuitWitness, ProvingMonitor};
+use crate::registry::KeyRegistry;
+use crate::stats::ProvingStats;
+use crate::{ZKProof, ZKProofError};
+
+/// 证明任务标识
+pub type JobId = u64;
+
+/// 进度回调，在执行证明的线程上调用
+pub type ProgressCallback = Arc<dyn Fn(&ProofProgress) + Send + Sync>;
+
+/// 证明任务所处的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProofStage {
+    /// 等待空闲的工作线程
+    Queued,
+    /// 加载或生成电路参数
+    Setup,
+    /// 合成见证与约束
+    Synthesizing,
+    /// 计算证明（不可中断）
+    Proving,
+    /// 已完成
+    Completed,
+    /// 生成失败
+    Failed,
+    /// 已取消
+    Cancelled,
+    /// 已超时
+    TimedOut,
+}
+
+impl ProofStage {
+    /// 任务是否已经结束
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            ProofStage::Completed | ProofStage::Failed | ProofStage::Cancelled | ProofStage::TimedOut
+        )
+    }
+}
+
+/// 证明任务进度
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofProgress {
+    /// 任务标识
+    pub job_id: JobId,
+    /// 当前阶段
+    pub stage: ProofStage,
+    /// 已合成的约束数量
+    pub constraints: usize,
+    /// 电路约束数量的估计值
+    pub estimated_constraints: usize,
+}
+
+impl ProofProgress {
+    /// 合成进度（0.0-1.0），合成完成后为 1.0
+    pub fn fraction(&self) -> f64 {
+        match self.stage {
+            ProofStage::Queued | ProofStage::Setup => 0.0,
+            ProofStage::Synthesizing => {
+                (self.constraints as f64 / self.estimated_constraints.max(1) as f64).min(0.99)
+            }
+            _ => 1.0,
+        }
+    }
+}
+
+/// 取消令牌，可跨线程克隆与取消
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<TokenState>,
+}
+
+#[derive(Debug, Default)]
+struct TokenState {
+    cancelled: AtomicBool,
+    notify: Notify,
+}
+
+impl CancellationToken {
+    /// 创建未取消的令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取消，唤醒所有等待者
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    /// 是否已取消
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 等待取消
+    pub async fn cancelled(&self) {
+        loop {
+            // 先注册再检查，避免错过检查与等待之间的取消
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// 任务池配置
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvingPoolConfig {
+    /// 同时执行的证明任务数量
+    pub workers: usize,
+    /// 等待工作线程的任务数量上限
+    pub queue_capacity: usize,
+    /// 默认超时（从提交时开始计算，包含排队时间），None 表示不限时
+    pub default_timeout: Option<Duration>,
+}
+
+impl Default for ProvingPoolConfig {
+    fn default() -> Self {
+        Self {
+            workers: std::thread::available_parallelism().map_or(1, |n| n.get()),
+            queue_capacity: 64,
+            default_timeout: None,
+        }
+    }
+}
+
+/// 单个证明任务的选项
+#[derive(Clone, Default)]
+pub struct JobOptions {
+    /// 超时，覆盖任务池的默认超时
+    pub timeout: Option<Duration>,
+    /// 进度回调
+    pub on_progress: Option<ProgressCallback>,
+}
+
+impl JobOptions {
+    /// 指定超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// 指定进度回调
+    pub fn on_progress(mut self, callback: impl Fn(&ProofProgress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+}
+
+/// 证明任务句柄
+pub struct ProofHandle {
+    job_id: JobId,
+    token: CancellationToken,
+    progress: watch::Receiver<ProofProgress>,
+    result: oneshot::Receiver<Result<ZKProof>>,
+}
+
+impl ProofHandle {
+    /// 任务标识
+    pub fn id(&self) -> JobId {
+        self.job_id
+    }
+
+    /// 取消任务
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    /// 任务的取消令牌
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// 当前进度
+    pub fn progress(&self) -> ProofProgress {
+        self.progress.borrow().clone()
+    }
+
+    /// 订阅进度更新
+    pub fn subscribe(&self) -> watch::Receiver<ProofProgress> {
+        self.progress.clone()
+    }
+
+    /// 等待任务结束
+    pub async fn wait(self) -> Result<ZKProof> {
+        self.result.await.map_err(|_| {
+            ZKProofError::ProofGenerationFailed("proving task terminated unexpectedly".to_string())
+        })?
+    }
+}
+
+/// 证明任务池
+///
+/// 克隆的实例共享同一组工作线程与任务表。
+#[derive(Clone)]
+pub struct ProvingPool {
+    shared: Arc<PoolShared>,
+}
+
+struct PoolShared {
+    registry: Arc<KeyRegistry>,
+    config: ProvingPoolConfig,
+    /// 工作线程许可
+    workers: Arc<Semaphore>,
+    /// 受理许可（执行中与排队中的任务）
+    admission: Arc<Semaphore>,
+    /// 未结束任务的取消令牌
+    jobs: Mutex<HashMap<JobId, CancellationToken>>,
+    next_job_id: AtomicU64,
+    /// 已结束任务的统计
+    stats: Mutex<ProvingStats>,
+    /// 电路分析器，记录各电路的实测证明耗时
+    profiler: Arc<CircuitProfiler>,
+}
+
+impl ProvingPool {
+    /// 创建任务池
+    pub fn new(registry: Arc<KeyRegistry>, config: ProvingPoolConfig) -> Self {
+        let workers = config.workers.max(1);
+        Self {
+            shared: Arc::new(PoolShared {
+                registry,
+                workers: Arc::new(Semaphore::new(workers)),
+                admission: Arc::new(Semaphore::new(workers + config.queue_capacity)),
+                config,
+                jobs: Mutex::new(HashMap::new()),
+                next_job_id: AtomicU64::new(1),
+                stats: Mutex::new(ProvingStats::default()),
+                profiler: Arc::new(CircuitProfiler::new()),
+            }),
+        }
+    }
+
+    /// 任务池配置
+    pub fn config(&self) -> &ProvingPoolConfig {
+        &self.shared.config
+    }
+
+    /// 提交证明任务，立即返回句柄；任务池已满时返回错误
+    ///
+    /// 必须在 tokio 运行时中调用。
+    pub fn submit(
+        &self,
+        circuit: &NetworkMetricCircuit,
+        witness: CircuitWitness,
+        public_inputs: Vec<u128>,
+        options: JobOptions,
+    ) -> Result<ProofHandle> {
+        let admission = self.shared.admission.clone().try_acquire_owned().map_err(|_| {
+            ZKProofError::QueueFull(self.shared.config.workers.max(1) + self.shared.config.queue_capacity)
+        })?;
+        let instance = CircuitInstance::from_circuit(circuit, Some(witness))?;
+
+        let job_id = self.shared.next_job_id.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+        let timeout = options.timeout.or(self.shared.config.default_timeout);
+        // 尚未分析的电路在加载参数阶段合成后更新
+        let estimated_constraints = self
+            .shared
+            .profiler
+            .cached(circuit)
+            .map_or(0, |profile| profile.constraint_count);
+        let (progress, progress_receiver) = watch::channel(ProofProgress {
+            job_id,
+            stage: ProofStage::Queued,
+            constraints: 0,
+            estimated_constraints,
+        });
+        let monitor = Arc::new(JobMonitor {
+            job_id,
+            token: token.clone(),
+            deadline: timeout.map(|timeout| (timeout, Instant::now() + timeout)),
+            progress,
+            callback: options.on_progress,
+            started: OnceLock::new(),
+        });
+
+        self.shared.jobs.lock().unwrap().insert(job_id, token.clone());
+        let (result_sender, result_receiver) = oneshot::channel();
+        let shared = self.shared.clone();
+        let circuit = circuit.clone();
+        tokio::spawn(async move {
+            let result = shared.run(circuit, instance, public_inputs, monitor.clone()).await;
+            let stage = monitor.finish(&result);
+            let duration = monitor.started.get().map(Instant::elapsed);
+            shared.stats.lock().unwrap().record(stage, duration);
+            shared.jobs.lock().unwrap().remove(&job_id);
+            drop(admission);
+            let _ = result_sender.send(result);
+        });
+
+        Ok(ProofHandle {
+            job_id,
+            token,
+            progress: progress_receiver,
+            result: result_receiver,
+        })
+    }
+
+    /// 取消任务，任务不存在或已结束时返回 false
+    pub fn cancel(&self, job_id: JobId) -> bool {
+        match self.shared.jobs.lock().unwrap().get(&job_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// 尚未结束的任务数量
+    pub fn pending_jobs(&self) -> usize {
+        self.shared.jobs.lock().unwrap().len()
+    }
+
+    /// 电路分析器
+    pub fn profiler(&self) -> &Arc<CircuitProfiler> {
+        &self.shared.profiler
+    }
+
+    /// 已结束任务的计数与生成耗时
+    pub fn stats(&self) -> ProvingStats {
+        self.shared.stats.lock().unwrap().clone()
+    }
+
+    /// 占用工作线程的任务数量（包括已取消但仍在计算的任务）
+    pub fn busy_workers(&self) -> usize {
+        self.shared.config.workers.max(1) - self.shared.workers.available_permits()
+    }
+}
+
+impl PoolShared {
+    /// 等待工作线程并在阻塞线程上生成证明，期间响应取消与超时
+    async fn run(
+        &self,
+        circuit: NetworkMetricCircuit,
+        instance: CircuitInstance,
+        public_inputs: Vec<u128>,
+        monitor: Arc<JobMonitor>,
+    ) -> Result<ZKProof> {
+        let permit = tokio::select! {
+            permit = self.workers.clone().acquire_owned() => permit.map_err(|e| {
+                ZKProofError::ProofGenerationFailed(e.to_string())
+            })?,
+            error = monitor.interrupted() => return Err(error.into()),
+        };
+
+        let registry = self.registry.clone();
+        let profiler = self.profiler.clone();
+        let job_monitor = monitor.clone();
+        let task = tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            prove(&registry, &profiler, &circuit, instance, public_inputs, &job_monitor)
+        });
+
+        tokio::select! {
+            joined = task => joined.map_err(|e| ZKProofError::ProofGenerationFailed(e.to_string()))?,
+            error = monitor.interrupted() => Err(error.into()),
+        }
+    }
+}
+
+/// 在当前线程上生成证明
+fn prove(
+    registry: &KeyRegistry,
+    profiler: &CircuitProfiler,
+    circuit: &NetworkMetricCircuit,
+    instance: CircuitInstance,
+    public_inputs: Vec<u128>,
+    monitor: &JobMonitor,
+) -> Result<ZKProof> {
+    monitor.interruption().map_or(Ok(()), Err)?;
+    let _ = monitor.started.set(Instant::now());
+    monitor.report(ProofStage::Setup, 0);
+    let backend = registry.backend(circuit.proof_system)?;
+    let keys = registry.get_or_generate(circuit)?;
+    monitor.set_estimated_constraints(profiler.profile(circuit)?.constraint_count);
+
+    monitor.report(ProofStage::Synthesizing, 0);
+    let proving_started = Instant::now();
+    let proof_value = backend
+        .prove_monitored(&keys, instance, monitor)
+        .map_err(|e| monitor.interruption().map_or(e, Into::into))?;
+    // 多标量乘法期间被取消或超时的结果不再返回
+    monitor.interruption().map_or(Ok(()), Err)?;
+    profiler.record_prove_time(circuit, proving_started.elapsed())?;
+
+    Ok(ZKProof {
+        proof_value,
+        public_inputs,
+        verification_key: keys.verification_key,
+        circuit_id: circuit.circuit_id,
+        created_at: chrono::Utc::now().timestamp() as u64,
+    })
+}
+
+/// 单个任务的取消、超时与进度状态
+struct JobMonitor {
+    job_id: JobId,
+    token: CancellationToken,
+    /// (超时, 截止时间)
+    deadline: Option<(Duration, Instant)>,
+    progress: watch::Sender<ProofProgress>,
+    callback: Option<ProgressCallback>,
+    /// 开始加载参数的时间
+    started: OnceLock<Instant>,
+}
+
+impl JobMonitor {
+    /// 任务被取消或超时时返回对应错误
+    fn interruption(&self) -> Option<ZKProofError> {
+        if self.token.is_cancelled() {
+            return Some(ZKProofError::ProofCancelled(self.job_id));
+        }
+        match self.deadline {
+            Some((timeout, deadline)) if Instant::now() >= deadline => Some(ZKProofError::ProofTimedOut(timeout)),
+            _ => None,
+        }
+    }
+
+    /// 等待取消或超时
+    async fn interrupted(&self) -> ZKProofError {
+        let timeout = async {
+            match self.deadline {
+                Some((timeout, deadline)) => {
+                    tokio::time::sleep_until(deadline.into()).await;
+                    ZKProofError::ProofTimedOut(timeout)
+                }
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            _ = self.token.cancelled() => ZKProofError::ProofCancelled(self.job_id),
+            error = timeout => error,
+        }
+    }
+
+    /// 更新进度并调用回调
+    ///
+    /// 检查与更新在同一次加锁内完成：任务进入终止阶段后，工作线程上迟到的进度报告不会覆盖它。
+    /// 回调在释放锁之后调用，且只针对实际生效的更新。
+    fn report(&self, stage: ProofStage, constraints: usize) {
+        let mut updated = None;
+        self.progress.send_if_modified(|progress| {
+            if progress.stage.is_finished() {
+                return false;
+            }
+            progress.stage = stage;
+            progress.constraints = constraints;
+            updated = Some(progress.clone());
+            true
+        });
+        if let (Some(progress), Some(callback)) = (updated, &self.callback) {
+            callback(&progress);
+        }
+    }
+
+    /// 以实际合成的约束数量更新估计值
+    fn set_estimated_constraints(&self, estimated_constraints: usize) {
+        self.progress.send_modify(|progress| progress.estimated_constraints = estimated_constraints);
+    }
+
+    /// 按任务结果记录并返回终止阶段
+    fn finish(&self, result: &Result<ZKProof>) -> ProofStage {
+        let constraints = self.progress.borrow().constraints;
+        let stage = match result {
+            Ok(_) => ProofStage::Completed,
+            Err(e) => match e.downcast_ref::<ZKProofError>() {
+                Some(ZKProofError::ProofCancelled(_)) => ProofStage::Cancelled,
+                Some(ZKProofError::ProofTimedOut(_)) => ProofStage::TimedOut,
+                _ => ProofStage::Failed,
+            },
+        };
+        self.report(stage, constraints);
+        stage
+    }
+}
+
+impl ProvingMonitor for JobMonitor {
+    fn check(&self) -> Result<(), SynthesisError> {
+        match self.interruption() {
+            Some(e) => Err(io::Error::new(io::ErrorKind::Interrupted, e.to_string()).into()),
+            None => Ok(()),
+        }
+    }
+
+    fn synthesized(&self, constraints: usize) {
+        self.report(ProofStage::Synthesizing, constraints);
+    }
+
+    fn synthesis_finished(&self, constraints: usize) {
+        self.report(ProofStage::Proving, constraints);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::VarianceWitness;
+    use crate::proof_system::ProofSystemKind;
+
+    /// 使用模拟后端的方差电路任务
+    fn variance_job(samples: usize) -> (NetworkMetricCircuit, CircuitWitness, Vec<u128>) {
+        let circuit = NetworkMetricCircuit::variance(7, samples, "Test".to_string())
+            .with_proof_system(ProofSystemKind::Mock);
+        let witness = VarianceWitness {
+            samples: (0..samples as u128).collect(),
+            blinding: crate::commitment::blinding_scalar(&[1; 32]),
+        };
+        let public_inputs = witness.public_inputs();
+        (circuit, CircuitWitness::Variance(witness), public_inputs)
+    }
+
+    fn pool(workers: usize, queue_capacity: usize) -> ProvingPool {
+        ProvingPool::new(
+            Arc::new(KeyRegistry::new()),
+            ProvingPoolConfig { workers, queue_capacity, default_timeout: None },
+        )
+    }
+
+    fn error_of<T>(result: Result<T>) -> ZKProofError {
+        match result {
+            Ok(_) => panic!("expected an error"),
+            Err(e) => e.downcast::<ZKProofError>().unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_job_completes_with_progress() {
+        let pool = pool(2, 4);
+        let (circuit, witness, public_inputs) = variance_job(256);
+        let stages = Arc::new(Mutex::new(Vec::new()));
+        let recorded = stages.clone();
+        let options = JobOptions::default().on_progress(move |p| recorded.lock().unwrap().push(p.stage));
+
+        let handle = pool.submit(&circuit, witness, public_inputs.clone(), options).unwrap();
+        let progress = handle.subscribe();
+        let proof = handle.wait().await.unwrap();
+        assert_eq!(proof.public_inputs, public_inputs);
+        assert_eq!(progress.borrow().stage, ProofStage::Completed);
+        assert_eq!(progress.borrow().fraction(), 1.0);
+
+        let stages = stages.lock().unwrap();
+        assert_eq!(stages.first(), Some(&ProofStage::Setup));
+        assert!(stages.contains(&ProofStage::Synthesizing));
+        assert_eq!(stages[stages.len() - 2..], [ProofStage::Proving, ProofStage::Completed]);
+        assert_eq!(pool.pending_jobs(), 0);
+
+        // 进度的约束估计来自实际合成，实测耗时记入分析器
+        let profile = pool.profiler().cached(&circuit).unwrap();
+        assert_eq!(progress.borrow().estimated_constraints, profile.constraint_count);
+        assert!(profile.measured_prove_time(ProofSystemKind::Mock).is_some());
+
+        let stats = pool.stats();
+        assert_eq!((stats.completed, stats.failed), (1, 0));
+        assert_eq!(stats.generation_times.count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_synthesis() {
+        let pool = pool(1, 0);
+        let (circuit, witness, public_inputs) = variance_job(256);
+        let stages = Arc::new(Mutex::new(Vec::new()));
+        let recorded = stages.clone();
+        let canceller = pool.clone();
+        // 合成开始报告进度时取消自身
+        let options = JobOptions::default().on_progress(move |p| {
+            recorded.lock().unwrap().push(p.stage);
+            if p.stage == ProofStage::Synthesizing && p.constraints > 0 {
+                assert!(canceller.cancel(p.job_id));
+            }
+        });
+
+        let handle = pool.submit(&circuit, witness, public_inputs, options).unwrap();
+        // 队列已满
+        let (_, second, second_inputs) = variance_job(256);
+        let full = pool.submit(&circuit, second, second_inputs, JobOptions::default());
+        assert!(matches!(error_of(full), ZKProofError::QueueFull(1)));
+
+        let job_id = handle.id();
+        assert!(matches!(error_of(handle.wait().await), ZKProofError::ProofCancelled(id) if id == job_id));
+        assert!(!pool.cancel(job_id));
+        assert_eq!(pool.stats().cancelled, 1);
+
+        // 等待工作线程退出：合成被中止，没有进入证明计算阶段
+        while pool.busy_workers() > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let stages = stages.lock().unwrap();
+        assert!(!stages.contains(&ProofStage::Proving));
+        assert_eq!(stages.last(), Some(&ProofStage::Cancelled));
+    }
+
+    #[test]
+    fn test_terminal_stage_is_not_overwritten() {
+        let (progress, receiver) = watch::channel(ProofProgress {
+            job_id: 1,
+            stage: ProofStage::Synthesizing,
+            constraints: 10,
+            estimated_constraints: 100,
+        });
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let recorded = reported.clone();
+        let monitor = JobMonitor {
+            job_id: 1,
+            token: CancellationToken::new(),
+            deadline: None,
+            progress,
+            callback: Some(Arc::new(move |p: &ProofProgress| recorded.lock().unwrap().push(p.stage))),
+            started: OnceLock::new(),
+        };
+
+        // 异步侧先记录取消，工作线程随后迟到的进度报告不再生效
+        let cancelled: Result<ZKProof> = Err(ZKProofError::ProofCancelled(1).into());
+        assert_eq!(monitor.finish(&cancelled), ProofStage::Cancelled);
+        monitor.synthesized(4096);
+        monitor.synthesis_finished(8192);
+        assert_eq!(receiver.borrow().stage, ProofStage::Cancelled);
+        assert_eq!(receiver.borrow().constraints, 10);
+        assert_eq!(*reported.lock().unwrap(), vec![ProofStage::Cancelled]);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let pool = pool(1, 1);
+        let (circuit, witness, public_inputs) = variance_job(8);
+        let options = JobOptions::default().with_timeout(Duration::ZERO);
+        let handle = pool.submit(&circuit, witness, public_inputs, options).unwrap();
+        let progress = handle.subscribe();
+
+        assert!(matches!(error_of(handle.wait().await), ZKProofError::ProofTimedOut(_)));
+        assert_eq!(progress.borrow().stage, ProofStage::TimedOut);
+    }
+}
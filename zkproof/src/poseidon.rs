@@ -0,0 +1,192 @@
+// Poseidon 哈希模块 - 适合在电路内计算的哈希函数（BLS12-381 标量域）
+//
+// 参数：状态宽度 t = 3（2 个输入 + 1 个容量元素），S-box 为 x^5，
+// 8 轮完全轮、57 轮部分轮，对应 255 位素数域上 128 位安全级别的推荐配置。
+// 轮常量由 SHA-256 以固定域分隔串确定性派生，MDS 矩阵为 Cauchy 矩阵。
+use bellman::{ConstraintSystem, SynthesisError};
+use bls12_381::Scalar;
+use ff::Field;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+use crate::gadgets::{self, LinearExpr};
+
+/// 状态宽度
+pub const WIDTH: usize = 3;
+/// 完全轮数
+const FULL_ROUNDS: usize = 8;
+/// 部分轮数
+const PARTIAL_ROUNDS: usize = 57;
+/// 轮常量派生使用的域分隔串
+const DOMAIN_TAG: &[u8] = b"polyvisor/poseidon/bls12-381/t3";
+
+/// 置换所需的常量
+struct PoseidonConstants {
+    /// 每轮的轮常量
+    round_constants: Vec<[Scalar; WIDTH]>,
+    /// MDS 矩阵
+    mds: [[Scalar; WIDTH]; WIDTH],
+}
+
+impl PoseidonConstants {
+    fn generate() -> Self {
+        let round_constants = (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+            .map(|round| {
+                let mut constants = [Scalar::ZERO; WIDTH];
+                for (i, constant) in constants.iter_mut().enumerate() {
+                    *constant = derive_scalar(round, i);
+                }
+                constants
+            })
+            .collect();
+
+        // Cauchy 矩阵 M[i][j] = 1 / (x_i + y_j)，x_i = i，y_j = WIDTH + j
+        let mut mds = [[Scalar::ZERO; WIDTH]; WIDTH];
+        for (i, row) in mds.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = Scalar::from((i + WIDTH + j) as u64).invert().unwrap();
+            }
+        }
+
+        Self { round_constants, mds }
+    }
+}
+
+/// 以 SHA-256 派生第 round 轮第 index 个轮常量
+fn derive_scalar(round: usize, index: usize) -> Scalar {
+    let mut wide = [0u8; 64];
+    for (half, chunk) in wide.chunks_mut(32).enumerate() {
+        let mut hasher = Sha256::new();
+        hasher.update(DOMAIN_TAG);
+        hasher.update((round as u32).to_be_bytes());
+        hasher.update((index as u32).to_be_bytes());
+        hasher.update([half as u8]);
+        chunk.copy_from_slice(&hasher.finalize());
+    }
+    Scalar::from_bytes_wide(&wide)
+}
+
+fn constants() -> &'static PoseidonConstants {
+    static CONSTANTS: OnceLock<PoseidonConstants> = OnceLock::new();
+    CONSTANTS.get_or_init(PoseidonConstants::generate)
+}
+
+/// 第 round 轮是否为完全轮
+fn is_full_round(round: usize) -> bool {
+    !(FULL_ROUNDS / 2..FULL_ROUNDS / 2 + PARTIAL_ROUNDS).contains(&round)
+}
+
+/// Poseidon 置换
+pub fn permute(state: &mut [Scalar; WIDTH]) {
+    let constants = constants();
+
+    for (round, round_constants) in constants.round_constants.iter().enumerate() {
+        for (s, c) in state.iter_mut().zip(round_constants) {
+            *s += c;
+        }
+
+        if is_full_round(round) {
+            state.iter_mut().for_each(|s| *s = sbox(*s));
+        } else {
+            state[0] = sbox(state[0]);
+        }
+
+        let mut mixed = [Scalar::ZERO; WIDTH];
+        for (out, row) in mixed.iter_mut().zip(&constants.mds) {
+            *out = row.iter().zip(state.iter()).map(|(m, s)| m * s).sum();
+        }
+        *state = mixed;
+    }
+}
+
+/// 二元哈希 H(a, b)，容量元素以输入个数作为域分隔
+pub fn hash2(a: Scalar, b: Scalar) -> Scalar {
+    let mut state = [Scalar::from(2u64), a, b];
+    permute(&mut state);
+    state[1]
+}
+
+/// 电路内的二元哈希，与 [`hash2`] 的计算结果一致
+///
+/// 每轮 S-box 需要 3 个乘法约束；部分轮中未经过 S-box 的状态元素会被固化为新变量，
+/// 以免线性组合的项数随轮数增长。
+pub fn hash2_gadget<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    a: &LinearExpr,
+    b: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    let constants = constants();
+    let mut state = [
+        LinearExpr::constant::<CS>(Scalar::from(2u64)),
+        a.clone(),
+        b.clone(),
+    ];
+
+    for (round, round_constants) in constants.round_constants.iter().enumerate() {
+        let mut cs = cs.namespace(|| format!("round {}", round));
+
+        for (s, c) in state.iter_mut().zip(round_constants) {
+            *s = s.add_constant::<CS>(*c);
+        }
+
+        let full = is_full_round(round);
+        for (i, s) in state.iter_mut().enumerate() {
+            if full || i == 0 {
+                *s = sbox_gadget(cs.namespace(|| format!("sbox {}", i)), s)?;
+            }
+        }
+
+        let mut mixed = Vec::with_capacity(WIDTH);
+        for row in &constants.mds {
+            let out = row
+                .iter()
+                .zip(state.iter())
+                .fold(LinearExpr::zero(), |acc, (m, s)| acc.add(&s.scale(*m)));
+            mixed.push(out);
+        }
+        for (i, (s, out)) in state.iter_mut().zip(mixed).enumerate() {
+            *s = if full || i == 0 {
+                out
+            } else {
+                gadgets::materialize(cs.namespace(|| format!("state {}", i)), &out)?
+            };
+        }
+    }
+
+    let [_, output, _] = state;
+    Ok(output)
+}
+
+fn sbox(x: Scalar) -> Scalar {
+    x.square().square() * x
+}
+
+fn sbox_gadget<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    x: &LinearExpr,
+) -> Result<LinearExpr, SynthesisError> {
+    let x2 = gadgets::mul(cs.namespace(|| "x^2"), x, x)?;
+    let x4 = gadgets::mul(cs.namespace(|| "x^4"), &x2, &x2)?;
+    gadgets::mul(cs.namespace(|| "x^5"), &x4, x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman::gadgets::test::TestConstraintSystem;
+
+    #[test]
+    fn test_gadget_matches_native_hash() {
+        let a = Scalar::from(6050u64);
+        let b = -Scalar::from(17u64);
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let a_var = gadgets::alloc_witness(cs.namespace(|| "a"), Some(a)).unwrap();
+        let b_var = gadgets::alloc_witness(cs.namespace(|| "b"), Some(b)).unwrap();
+        let out = hash2_gadget(cs.namespace(|| "hash"), &a_var, &b_var).unwrap();
+
+        assert!(cs.is_satisfied());
+        assert_eq!(out.value(), Some(hash2(a, b)));
+        assert_ne!(hash2(a, b), hash2(b, a));
+    }
+}
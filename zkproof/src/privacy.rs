@@ -0,0 +1,202 @@
+// 差分隐私模块 - 由可验证随机性驱动的离散拉普拉斯噪声
+//
+// 噪声 Z = G1 - G2，其中 G1、G2 独立同分布于几何分布 P(G = k) = (1 - α) α^k，α = e^(-ε/Δ)，
+// 因此 Z 服从离散拉普拉斯分布 P(Z = z) ∝ α^|z|，对敏感度为 Δ 的求和查询满足 ε-差分隐私。
+// 随机性 r = H(secret, beacon)：提交者先公布秘密承诺 H(secret, tag)，随后由公开随机信标决定噪声，
+// 因此提交者无法挑选噪声，而不知道秘密的一方也无法由公开值还原噪声。
+// G 由 r 的 32 个低位比特按逆累积分布函数查表得到，表格只用整数运算生成，电路内外完全一致。
+use anyhow::Result;
+use bls12_381::Scalar;
+use ff::PrimeField;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{poseidon, ZKProofError};
+
+/// 每次几何分布采样使用的均匀比特数
+pub const UNIFORM_BITS: usize = 32;
+/// 几何分布查表的最大长度，即单侧噪声的上限
+pub const MAX_NOISE_STEPS: usize = 1 << 14;
+/// α 的定点小数位数
+const ALPHA_FRACTION_BITS: u32 = 48;
+/// 秘密承诺的域分隔串
+const SECRET_COMMITMENT_TAG: &[u8] = b"polyvisor/dp/secret-commitment";
+
+/// 差分隐私参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PrivacyParameters {
+    /// 隐私预算 ε，以千分之一为单位
+    pub epsilon_millis: u32,
+    /// 敏感度 Δ：单个样本的上限，同时也是求和查询的敏感度
+    pub sensitivity: u64,
+}
+
+impl PrivacyParameters {
+    /// 隐私预算 ε
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon_millis as f64 / 1000.0
+    }
+
+    /// 检查参数有效，且噪声表不超过 [`MAX_NOISE_STEPS`]
+    pub fn validate(&self) -> Result<()> {
+        if self.epsilon_millis == 0 || self.sensitivity == 0 {
+            return Err(ZKProofError::InvalidInputData(
+                "epsilon and sensitivity must be positive".to_string(),
+            )
+            .into());
+        }
+        if self.noise_thresholds().len() > MAX_NOISE_STEPS {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "noise scale {}/{} requires more than {} table entries",
+                self.sensitivity,
+                self.epsilon(),
+                MAX_NOISE_STEPS
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// α = e^(-ε/Δ) 的 2^48 定点表示
+    pub fn alpha(&self) -> u64 {
+        exp_neg_fixed(self.epsilon_millis as u128, 1000 * self.sensitivity as u128)
+    }
+
+    /// 几何分布的逆累积分布表：T_k = floor((1 - α^k) * 2^32)，k = 1, 2, ...
+    ///
+    /// 均匀值 u 对应的采样结果为满足 T_k <= u 的 k 的个数；α^k 小于 2^-32 后停止，
+    /// 最多返回 MAX_NOISE_STEPS + 1 项（超出上限的参数由 [`Self::validate`] 拒绝）。
+    pub fn noise_thresholds(&self) -> Vec<u128> {
+        let one = 1u128 << ALPHA_FRACTION_BITS;
+        let shift = ALPHA_FRACTION_BITS - UNIFORM_BITS as u32;
+        let alpha = self.alpha() as u128;
+
+        let mut power = one;
+        let mut thresholds = Vec::new();
+        while thresholds.len() <= MAX_NOISE_STEPS {
+            power = (power * alpha) >> ALPHA_FRACTION_BITS;
+            thresholds.push((one - power) >> shift);
+            if power >> shift == 0 {
+                break;
+            }
+        }
+        thresholds
+    }
+
+    /// 由随机种子采样噪声 G1 - G2
+    pub fn sample_noise(&self, seed: &Scalar) -> i64 {
+        let thresholds = self.noise_thresholds();
+        let repr = seed.to_repr();
+        let geometric = |bytes: &[u8]| {
+            let u = u32::from_le_bytes(bytes.try_into().expect("4 bytes")) as u128;
+            thresholds.iter().filter(|t| **t <= u).count() as i64
+        };
+        geometric(&repr[..4]) - geometric(&repr[4..8])
+    }
+}
+
+/// e^(-numerator/denominator) 的 2^48 定点近似
+///
+/// 只使用整数运算，保证不同平台得到相同的噪声表：先将指数减半至小于 1，
+/// 以 2^64 定点的泰勒级数求值，再反复平方还原。
+fn exp_neg_fixed(numerator: u128, denominator: u128) -> u64 {
+    const ONE: u128 = 1 << 64;
+    if numerator == 0 {
+        return 1 << ALPHA_FRACTION_BITS;
+    }
+
+    let mut halvings = 0;
+    while numerator >= denominator << halvings {
+        halvings += 1;
+    }
+    let denominator = denominator << halvings;
+
+    let mut term = ONE;
+    let mut value = ONE;
+    let mut n = 1;
+    loop {
+        term = term * numerator / (denominator * n);
+        if term == 0 {
+            break;
+        }
+        if n % 2 == 1 {
+            value -= term;
+        } else {
+            value += term;
+        }
+        n += 1;
+    }
+
+    let mut value = value.min(ONE - 1);
+    for _ in 0..halvings {
+        value = (value * value) >> 64;
+    }
+    (value >> (64 - ALPHA_FRACTION_BITS)) as u64
+}
+
+/// 将 32 字节的秘密、盲化因子或信标输出规约为标量域元素
+pub fn bytes_to_scalar(bytes: &[u8; 32]) -> Scalar {
+    crate::nullifier::secret_scalar(bytes)
+}
+
+/// 噪声秘密的承诺，须在信标输出之前公布
+pub fn secret_commitment(secret: Scalar) -> Scalar {
+    poseidon::hash2(secret, secret_commitment_tag())
+}
+
+/// 由噪声秘密与公开信标派生噪声的随机种子
+pub fn noise_seed(secret: Scalar, beacon: Scalar) -> Scalar {
+    poseidon::hash2(secret, beacon)
+}
+
+/// 秘密承诺使用的域分隔常量
+pub fn secret_commitment_tag() -> Scalar {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(&Sha256::digest(SECRET_COMMITMENT_TAG));
+    Scalar::from_bytes_wide(&wide)
+}
+
+/// 对真实值加噪，结果截断到 0（截断属于后处理，不影响隐私保证）
+pub fn apply_noise(value: u128, noise: i64) -> u128 {
+    if noise >= 0 {
+        value + noise as u128
+    } else {
+        value.saturating_sub(noise.unsigned_abs() as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_distribution() {
+        let parameters = PrivacyParameters { epsilon_millis: 1000, sensitivity: 10 };
+        parameters.validate().unwrap();
+
+        // α = e^-0.1
+        let alpha = parameters.alpha() as f64 / (1u64 << ALPHA_FRACTION_BITS) as f64;
+        assert!((alpha - (-0.1f64).exp()).abs() < 1e-9);
+
+        // 阈值单调不减，P(G >= k) = 1 - T_k / 2^32 ≈ α^k
+        let thresholds = parameters.noise_thresholds();
+        assert!(thresholds.windows(2).all(|w| w[0] <= w[1]));
+        let tail = 1.0 - thresholds[9] as f64 / (1u64 << 32) as f64;
+        assert!((tail - alpha.powi(10)).abs() < 1e-6);
+
+        // 噪声对称且均值接近 0
+        let noise: Vec<i64> = (0..4000u64)
+            .map(|i| parameters.sample_noise(&noise_seed(Scalar::from(i), Scalar::from(7u64))))
+            .collect();
+        let mean = noise.iter().sum::<i64>() as f64 / noise.len() as f64;
+        assert!(mean.abs() < 1.0);
+        assert!(noise.iter().any(|n| *n > 0) && noise.iter().any(|n| *n < 0));
+
+        assert_eq!(apply_noise(5, -7), 0);
+        assert_eq!(apply_noise(5, 7), 12);
+
+        // 噪声尺度过大时噪声表超出上限
+        assert!(PrivacyParameters { epsilon_millis: 1, sensitivity: 1000 }.validate().is_err());
+        assert!(PrivacyParameters { epsilon_millis: 0, sensitivity: 10 }.validate().is_err());
+    }
+}
@@ -0,0 +1,253 @@
+// 电路分析模块 - 通过实际合成统计约束与见证数量，并记录实测的证明耗时
+//
+// 电路以 setup 模式（无见证）合成到只计数的约束系统中，结果按电路结构缓存。
+// 证明耗时按 (电路结构, 后端) 记录；尚无实测值的电路按同一后端已测电路的每约束耗时外推。
+use anyhow::Result;
+use bellman::{ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+use bls12_381::Scalar;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::circuits::{CircuitComplexity, NetworkMetricCircuit};
+use crate::proof_system::{CircuitInstance, ProofSystemKind};
+use crate::stats::LatencyHistogram;
+
+/// 尚无任何实测值时每个约束的证明耗时（纳秒）
+const DEFAULT_NANOS_PER_CONSTRAINT: u128 = 10_000;
+/// 配对检查的固定验证耗时（毫秒）
+const VERIFICATION_BASE_MS: usize = 2;
+/// 每个公开输入增加的验证耗时（微秒）
+const VERIFICATION_MICROS_PER_INPUT: usize = 100;
+
+/// 单个电路结构的分析结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitProfile {
+    /// 电路结构描述
+    pub shape: String,
+    /// 约束数量
+    pub constraint_count: usize,
+    /// 私有见证变量数量
+    pub witness_count: usize,
+    /// 公开输入数量（不含常量 1）
+    pub input_count: usize,
+    /// 合成耗时
+    pub synthesis_time: Duration,
+    /// 各后端的实测证明耗时
+    pub prove_times: HashMap<ProofSystemKind, LatencyHistogram>,
+}
+
+impl CircuitProfile {
+    /// 后端的平均实测证明耗时，尚无实测值时返回 None
+    pub fn measured_prove_time(&self, system: ProofSystemKind) -> Option<Duration> {
+        self.prove_times
+            .get(&system)
+            .filter(|histogram| histogram.count() > 0)
+            .map(LatencyHistogram::mean)
+    }
+}
+
+/// 电路分析器，可在多个线程间共享
+#[derive(Default)]
+pub struct CircuitProfiler {
+    /// 电路结构 -> 分析结果
+    profiles: RwLock<HashMap<String, CircuitProfile>>,
+}
+
+impl CircuitProfiler {
+    /// 创建空的分析器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取电路的分析结果，首次查询时合成电路
+    pub fn profile(&self, circuit: &NetworkMetricCircuit) -> Result<CircuitProfile> {
+        let instance = CircuitInstance::from_circuit(circuit, None)?;
+        let shape = instance.shape();
+        if let Some(profile) = self.read_profiles().get(&shape) {
+            return Ok(profile.clone());
+        }
+
+        let started = Instant::now();
+        let mut cs = CountingConstraintSystem::default();
+        bellman::Circuit::synthesize(instance, &mut cs)?;
+        let profile = CircuitProfile {
+            shape: shape.clone(),
+            constraint_count: cs.constraints,
+            witness_count: cs.aux,
+            input_count: cs.inputs,
+            synthesis_time: started.elapsed(),
+            prove_times: HashMap::new(),
+        };
+
+        Ok(self.write_profiles().entry(shape).or_insert(profile).clone())
+    }
+
+    /// 已缓存的分析结果，不触发合成
+    pub fn cached(&self, circuit: &NetworkMetricCircuit) -> Option<CircuitProfile> {
+        let shape = CircuitInstance::from_circuit(circuit, None).ok()?.shape();
+        self.read_profiles().get(&shape).cloned()
+    }
+
+    /// 记录电路在其当前后端上的一次证明耗时
+    pub fn record_prove_time(&self, circuit: &NetworkMetricCircuit, duration: Duration) -> Result<()> {
+        let shape = self.profile(circuit)?.shape;
+        if let Some(profile) = self.write_profiles().get_mut(&shape) {
+            profile.prove_times.entry(circuit.proof_system).or_default().record(duration);
+        }
+        Ok(())
+    }
+
+    /// 由实际约束数量与实测耗时得到的复杂度
+    ///
+    /// 电路在当前后端上有实测值时使用其平均耗时，否则按该后端所有已测电路的每约束耗时外推。
+    pub fn complexity(&self, circuit: &NetworkMetricCircuit) -> Result<CircuitComplexity> {
+        let profile = self.profile(circuit)?;
+        let generation_time = profile
+            .measured_prove_time(circuit.proof_system)
+            .unwrap_or_else(|| {
+                let nanos = self.nanos_per_constraint(circuit.proof_system) * profile.constraint_count as u128;
+                Duration::from_nanos(nanos.min(u64::MAX as u128) as u64)
+            });
+        // Groth16 证明者在 2 的幂大小的求值域上保存 A、B、C 三组求值，另有全部赋值
+        let field_elements = 3 * profile.constraint_count.next_power_of_two()
+            + profile.witness_count
+            + profile.input_count;
+
+        Ok(CircuitComplexity {
+            constraint_count: profile.constraint_count,
+            witness_count: profile.witness_count,
+            estimated_generation_time_ms: generation_time.as_millis() as usize,
+            estimated_verification_time_ms: VERIFICATION_BASE_MS
+                + (profile.input_count * VERIFICATION_MICROS_PER_INPUT).div_ceil(1000),
+            memory_usage_mb: (field_elements * 32).div_ceil(1 << 20),
+        })
+    }
+
+    /// 后端已测电路的平均每约束耗时（纳秒）
+    fn nanos_per_constraint(&self, system: ProofSystemKind) -> u128 {
+        let (total_nanos, total_constraints) = self
+            .read_profiles()
+            .values()
+            .filter_map(|profile| {
+                let histogram = profile.prove_times.get(&system).filter(|h| h.count() > 0)?;
+                Some((
+                    histogram.mean().as_nanos(),
+                    profile.constraint_count as u128,
+                ))
+            })
+            .fold((0u128, 0u128), |(nanos, constraints), (n, c)| (nanos + n, constraints + c));
+        total_nanos.checked_div(total_constraints).unwrap_or(DEFAULT_NANOS_PER_CONSTRAINT)
+    }
+
+    fn read_profiles(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, CircuitProfile>> {
+        self.profiles.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_profiles(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, CircuitProfile>> {
+        self.profiles.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 只统计变量与约束数量的约束系统，不计算赋值
+#[derive(Default)]
+struct CountingConstraintSystem {
+    aux: usize,
+    inputs: usize,
+    constraints: usize,
+}
+
+impl ConstraintSystem<Scalar> for CountingConstraintSystem {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _annotation: A, _f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.aux += 1;
+        Ok(Variable::new_unchecked(Index::Aux(self.aux - 1)))
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _annotation: A, _f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        // 下标 0 为常量 1
+        self.inputs += 1;
+        Ok(Variable::new_unchecked(Index::Input(self.inputs)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _annotation: A, _a: LA, _b: LB, _c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LB: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LC: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+    {
+        self.constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, _name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self) {}
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuits::{CircuitManager, VarianceWitness};
+    use crate::proof_system::CircuitWitness;
+    use bellman::gadgets::test::TestConstraintSystem;
+
+    #[test]
+    fn test_profile_matches_synthesis() {
+        let manager = CircuitManager::new();
+        let profiler = CircuitProfiler::new();
+
+        // 与带见证的完整合成结果一致
+        let samples: Vec<u128> = (0..40).map(|i| 6000 + i * 7).collect();
+        let circuit = manager.get_circuit(7).unwrap();
+        let profile = profiler.profile(circuit).unwrap();
+        let witness = CircuitWitness::Variance(VarianceWitness {
+            samples,
+            blinding: crate::commitment::blinding_scalar(&[1; 32]),
+        });
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let instance = CircuitInstance::from_circuit(circuit, Some(witness)).unwrap();
+        bellman::Circuit::synthesize(instance, &mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(profile.constraint_count, cs.num_constraints());
+        assert_eq!(profile.input_count + 1, cs.num_inputs());
+        assert_eq!(profile.input_count, circuit.get_public_input_spec().inputs.len());
+
+        // 未实测的电路按已测电路的每约束耗时外推
+        let small = manager.get_circuit(1).unwrap();
+        let large = manager.get_circuit(3).unwrap();
+        profiler.record_prove_time(small, Duration::from_millis(100)).unwrap();
+        assert_eq!(profiler.complexity(small).unwrap().estimated_generation_time_ms, 100);
+        let small_constraints = profiler.profile(small).unwrap().constraint_count;
+        let large_constraints = profiler.profile(large).unwrap().constraint_count;
+        let extrapolated = profiler.complexity(large).unwrap().estimated_generation_time_ms;
+        let expected = 100 * large_constraints / small_constraints;
+        assert!(extrapolated.abs_diff(expected) <= 1);
+
+        // 实测值只适用于测量时的后端
+        let mock = small.clone().with_proof_system(ProofSystemKind::Mock);
+        assert!(profiler.profile(&mock).unwrap().measured_prove_time(ProofSystemKind::Mock).is_none());
+    }
+}
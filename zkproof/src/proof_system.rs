@@ -0,0 +1,856 @@
+// 证明系统模块 - 可插拔的证明后端
+use anyhow::Result;
+use bellman::gadgets::test::TestConstraintSystem;
+use bellman::groth16::{self, Parameters, Proof};
+use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable};
+use bls12_381::{Bls12, Scalar};
+use rand::rngs::OsRng;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::circuits::{
+    CircuitType, DataIntegrityCircuit, IntegrityWitness, MetricAggregationCircuit, MetricWitness,
+    NetworkMetricCircuit, ParticipationCircuit, ParticipationWitness, PercentileCircuit, PercentileWitness,
+    PrivacyCircuit, PrivacyWitness, PublicInputMode, RollingCircuit, RollingWitness, UptimeCircuit, UptimeWitness,
+    VarianceCircuit, VarianceWitness,
+};
+use crate::gadgets::{self, LinearExpr};
+use crate::poseidon;
+use crate::utils;
+use crate::ZKProofError;
+
+/// 合成期间每分配多少个变量检查一次是否需要中止
+const MONITOR_CHECK_INTERVAL: usize = 256;
+/// 合成期间每合成多少个约束报告一次进度
+const MONITOR_PROGRESS_INTERVAL: usize = 4096;
+
+/// 证明系统后端标识与公开语句，定义在 no_std 验证核心中以便链上验证共用
+pub use zkproof_verifier::{ProofSystemKind, Statement};
+
+/// 各类电路的见证数据
+#[derive(Debug, Clone)]
+pub enum CircuitWitness {
+    /// 网络指标聚合见证
+    NetworkMetric(MetricWitness),
+    /// 数据完整性见证
+    DataIntegrity(IntegrityWitness),
+    /// 百分位数见证
+    Percentile(PercentileWitness),
+    /// 方差见证
+    Variance(VarianceWitness),
+    /// 差分隐私求和见证
+    Privacy(PrivacyWitness),
+    /// 共识参与见证
+    Participation(ParticipationWitness),
+    /// 节点在线率见证
+    Uptime(UptimeWitness),
+    /// 滚动聚合见证
+    Rolling(RollingWitness),
+}
+
+/// 可交给任意证明后端处理的电路实例
+#[derive(Debug, Clone)]
+pub enum CircuitInstance {
+    /// 网络指标聚合电路
+    NetworkMetric(MetricAggregationCircuit),
+    /// 数据完整性电路
+    DataIntegrity(DataIntegrityCircuit),
+    /// 百分位数电路
+    Percentile(PercentileCircuit),
+    /// 方差电路
+    Variance(VarianceCircuit),
+    /// 差分隐私求和电路
+    Privacy(PrivacyCircuit),
+    /// 共识参与电路
+    Participation(ParticipationCircuit),
+    /// 节点在线率电路
+    Uptime(UptimeCircuit),
+    /// 滚动聚合电路
+    Rolling(RollingCircuit),
+    /// 只公开内部电路公开输入摘要的电路，见 [`PublicInputMode::Digest`]
+    Digested(Box<CircuitInstance>),
+}
+
+impl CircuitInstance {
+    /// 根据电路描述构造实例，无见证时用于 setup
+    pub fn from_circuit(
+        circuit: &NetworkMetricCircuit,
+        witness: Option<CircuitWitness>,
+    ) -> Result<Self> {
+        let instance = Self::from_circuit_type(circuit, witness)?;
+        Ok(match circuit.public_input_mode {
+            PublicInputMode::Full => instance,
+            PublicInputMode::Digest => CircuitInstance::Digested(Box::new(instance)),
+        })
+    }
+
+    /// 按电路类型构造公开全部输入的实例
+    fn from_circuit_type(
+        circuit: &NetworkMetricCircuit,
+        witness: Option<CircuitWitness>,
+    ) -> Result<Self> {
+        match (&circuit.circuit_type, witness) {
+            (CircuitType::NetworkMetric, None) => {
+                Ok(CircuitInstance::NetworkMetric(MetricAggregationCircuit::blank(circuit)))
+            }
+            (CircuitType::NetworkMetric, Some(CircuitWitness::NetworkMetric(w))) => {
+                Ok(CircuitInstance::NetworkMetric(MetricAggregationCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::DataIntegrity, None) => {
+                Ok(CircuitInstance::DataIntegrity(DataIntegrityCircuit::blank(circuit)))
+            }
+            (CircuitType::DataIntegrity, Some(CircuitWitness::DataIntegrity(w))) => {
+                Ok(CircuitInstance::DataIntegrity(DataIntegrityCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::Percentile, None) => {
+                Ok(CircuitInstance::Percentile(PercentileCircuit::blank(circuit)))
+            }
+            (CircuitType::Percentile, Some(CircuitWitness::Percentile(w))) => {
+                Ok(CircuitInstance::Percentile(PercentileCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::Variance, None) => {
+                Ok(CircuitInstance::Variance(VarianceCircuit::blank(circuit)))
+            }
+            (CircuitType::Variance, Some(CircuitWitness::Variance(w))) => {
+                Ok(CircuitInstance::Variance(VarianceCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::PrivacyPreserving, None) => {
+                Ok(CircuitInstance::Privacy(PrivacyCircuit::blank(circuit)))
+            }
+            (CircuitType::PrivacyPreserving, Some(CircuitWitness::Privacy(w))) => {
+                Ok(CircuitInstance::Privacy(PrivacyCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::ConsensusParticipation, None) => {
+                Ok(CircuitInstance::Participation(ParticipationCircuit::blank(circuit)))
+            }
+            (CircuitType::ConsensusParticipation, Some(CircuitWitness::Participation(w))) => {
+                Ok(CircuitInstance::Participation(ParticipationCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::NodeReliability, None) => {
+                Ok(CircuitInstance::Uptime(UptimeCircuit::blank(circuit)))
+            }
+            (CircuitType::NodeReliability, Some(CircuitWitness::Uptime(w))) => {
+                Ok(CircuitInstance::Uptime(UptimeCircuit::with_witness(circuit, w)))
+            }
+            (CircuitType::RollingAggregation, None) => {
+                Ok(CircuitInstance::Rolling(RollingCircuit::blank(circuit)))
+            }
+            (CircuitType::RollingAggregation, Some(CircuitWitness::Rolling(w))) => {
+                Ok(CircuitInstance::Rolling(RollingCircuit::with_witness(circuit, w)))
+            }
+            (
+                CircuitType::NetworkMetric
+                | CircuitType::DataIntegrity
+                | CircuitType::Percentile
+                | CircuitType::Variance
+                | CircuitType::PrivacyPreserving
+                | CircuitType::ConsensusParticipation
+                | CircuitType::NodeReliability
+                | CircuitType::RollingAggregation,
+                Some(_),
+            ) => {
+                Err(ZKProofError::InvalidInputData(format!(
+                    "witness does not match circuit type {:?}",
+                    circuit.circuit_type
+                ))
+                .into())
+            }
+            (CircuitType::AggregationProof, _) => {
+                Err(ZKProofError::UnsupportedCircuitType(CircuitType::AggregationProof).into())
+            }
+        }
+    }
+
+    /// 电路结构描述，相同结构的实例共享同一组密钥
+    pub fn shape(&self) -> String {
+        match self {
+            CircuitInstance::NetworkMetric(c) => {
+                format!("network_metric:{}:{}", c.max_data_points, c.max_data_sources)
+            }
+            CircuitInstance::DataIntegrity(c) => {
+                format!("data_integrity:{}:{}", c.max_batch_size, c.tree_depth)
+            }
+            CircuitInstance::Percentile(c) => format!("percentile:{}", c.max_samples),
+            CircuitInstance::Variance(c) => format!("variance:{}", c.max_samples),
+            CircuitInstance::Privacy(c) => {
+                let (epsilon, sensitivity) = c.parameters.map_or((0, 0), |p| (p.epsilon_millis, p.sensitivity));
+                format!("privacy:{}:{}:{}", c.max_samples, epsilon, sensitivity)
+            }
+            CircuitInstance::Participation(c) => format!("participation:{}:{}", c.max_entries, c.tree_depth),
+            CircuitInstance::Uptime(c) => format!("uptime:{}", c.max_heartbeats),
+            CircuitInstance::Rolling(c) => format!("rolling:{}:{}", c.max_samples, c.rolling_windows),
+            CircuitInstance::Digested(inner) => format!("{}:digest", inner.shape()),
+        }
+    }
+
+    /// 实例的公开输入（无见证时返回 None），摘要电路返回内部电路的公开输入
+    pub fn public_inputs(&self) -> Option<Vec<u128>> {
+        match self {
+            CircuitInstance::NetworkMetric(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::DataIntegrity(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Percentile(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Variance(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Privacy(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Participation(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Uptime(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Rolling(c) => c.witness.as_ref().map(|w| w.public_inputs()),
+            CircuitInstance::Digested(inner) => inner.public_inputs(),
+        }
+    }
+
+    /// 后端验证的公开语句（无见证时返回 None）
+    pub fn statement(&self) -> Option<Statement> {
+        let public_inputs = self.public_inputs()?;
+        Some(match self {
+            CircuitInstance::Digested(_) => Statement::Digest(utils::public_input_digest(&public_inputs)),
+            _ => Statement::Inputs(public_inputs),
+        })
+    }
+}
+
+impl Circuit<Scalar> for CircuitInstance {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        match self {
+            CircuitInstance::Digested(inner) => synthesize_digested(*inner, cs),
+            instance => instance.synthesize_typed(cs),
+        }
+    }
+}
+
+impl CircuitInstance {
+    /// 按电路类型合成；摘要电路不可嵌套
+    fn synthesize_typed<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        match self {
+            CircuitInstance::NetworkMetric(c) => c.synthesize(cs),
+            CircuitInstance::DataIntegrity(c) => c.synthesize(cs),
+            CircuitInstance::Percentile(c) => c.synthesize(cs),
+            CircuitInstance::Variance(c) => c.synthesize(cs),
+            CircuitInstance::Privacy(c) => c.synthesize(cs),
+            CircuitInstance::Participation(c) => c.synthesize(cs),
+            CircuitInstance::Uptime(c) => c.synthesize(cs),
+            CircuitInstance::Rolling(c) => c.synthesize(cs),
+            CircuitInstance::Digested(_) => Err(SynthesisError::Unsatisfiable),
+        }
+    }
+}
+
+/// 合成内部电路，将其公开输入改为私有见证，只公开这些输入的摘要
+///
+/// 摘要与 [`utils::public_input_digest`] 一致：初始状态由输入个数确定，为常量；
+/// 之后每个输入一次 Poseidon 哈希，摘要作为电路唯一的公开输入（一个域元素）。
+fn synthesize_digested<CS: ConstraintSystem<Scalar>>(
+    circuit: CircuitInstance,
+    cs: &mut CS,
+) -> Result<(), SynthesisError> {
+    let mut digested = DigestConstraintSystem {
+        inner: cs,
+        inputs: Vec::new(),
+    };
+    circuit.synthesize_typed(&mut digested)?;
+    let inputs = digested.inputs;
+
+    let mut digest = LinearExpr::constant::<CS>(utils::public_input_digest_header(inputs.len()));
+    for (i, input) in inputs.iter().enumerate() {
+        digest = poseidon::hash2_gadget(cs.namespace(|| format!("digest input {}", i)), &digest, input)?;
+    }
+
+    let output = gadgets::alloc_input(cs.namespace(|| "digest"), digest.value())?;
+    gadgets::enforce_equal(cs, "public input digest", &output, &digest);
+    Ok(())
+}
+
+/// 将公开输入分配为私有见证并按分配顺序记录，其余操作直接转发到内部约束系统
+struct DigestConstraintSystem<'a, CS> {
+    inner: &'a mut CS,
+    inputs: Vec<LinearExpr>,
+}
+
+impl<CS: ConstraintSystem<Scalar>> ConstraintSystem<Scalar> for DigestConstraintSystem<'_, CS> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.inner.alloc(annotation, f)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let mut value = None;
+        let variable = self.inner.alloc(annotation, || {
+            let assigned = f()?;
+            value = Some(assigned);
+            Ok(assigned)
+        })?;
+        self.inputs.push(LinearExpr::from_variable(variable, value));
+        Ok(variable)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LB: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LC: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+    {
+        self.inner.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.inner.get_root().push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self) {
+        self.inner.get_root().pop_namespace()
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// 证明生成过程的观察者：接收合成进度，并可中止正在进行的合成
+pub trait ProvingMonitor: Sync {
+    /// 返回错误时中止合成
+    fn check(&self) -> Result<(), SynthesisError>;
+
+    /// 已合成 constraints 个约束
+    fn synthesized(&self, constraints: usize);
+
+    /// 合成完成，共 constraints 个约束，之后进入证明计算阶段
+    fn synthesis_finished(&self, constraints: usize);
+}
+
+/// 在合成期间通知观察者的电路包装
+pub struct MonitoredCircuit<'a, C> {
+    circuit: C,
+    monitor: &'a dyn ProvingMonitor,
+}
+
+impl<'a, C: Circuit<Scalar>> MonitoredCircuit<'a, C> {
+    /// 包装电路
+    pub fn new(circuit: C, monitor: &'a dyn ProvingMonitor) -> Self {
+        Self { circuit, monitor }
+    }
+}
+
+impl<C: Circuit<Scalar>> Circuit<Scalar> for MonitoredCircuit<'_, C> {
+    fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        self.monitor.check()?;
+        let mut monitored = MonitoredConstraintSystem {
+            inner: cs,
+            monitor: self.monitor,
+            allocations: 0,
+            constraints: 0,
+        };
+        self.circuit.synthesize(&mut monitored)?;
+        self.monitor.check()?;
+        self.monitor.synthesis_finished(monitored.constraints);
+        Ok(())
+    }
+}
+
+/// 转发到内部约束系统，并按间隔检查中止与报告进度
+struct MonitoredConstraintSystem<'a, CS> {
+    inner: &'a mut CS,
+    monitor: &'a dyn ProvingMonitor,
+    allocations: usize,
+    constraints: usize,
+}
+
+impl<CS> MonitoredConstraintSystem<'_, CS> {
+    fn count_allocation(&mut self) -> Result<(), SynthesisError> {
+        self.allocations += 1;
+        if self.allocations.is_multiple_of(MONITOR_CHECK_INTERVAL) {
+            self.monitor.check()?;
+        }
+        Ok(())
+    }
+}
+
+impl<CS: ConstraintSystem<Scalar>> ConstraintSystem<Scalar> for MonitoredConstraintSystem<'_, CS> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.count_allocation()?;
+        self.inner.alloc(annotation, f)
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, annotation: A, f: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<Scalar, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.count_allocation()?;
+        self.inner.alloc_input(annotation, f)
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, annotation: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LB: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+        LC: FnOnce(LinearCombination<Scalar>) -> LinearCombination<Scalar>,
+    {
+        self.constraints += 1;
+        if self.constraints.is_multiple_of(MONITOR_PROGRESS_INTERVAL) {
+            self.monitor.synthesized(self.constraints);
+        }
+        self.inner.enforce(annotation, a, b, c)
+    }
+
+    fn push_namespace<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.inner.get_root().push_namespace(name_fn)
+    }
+
+    fn pop_namespace(&mut self) {
+        self.inner.get_root().pop_namespace()
+    }
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// 证明密钥（后端专有的内存表示）
+#[derive(Clone)]
+pub enum ProvingKey {
+    /// 模拟后端密钥
+    Mock(Vec<u8>),
+    /// Groth16 证明参数
+    Groth16(Arc<Parameters<Bls12>>),
+}
+
+/// 电路密钥对
+#[derive(Clone)]
+pub struct CircuitKeys {
+    /// 生成密钥的后端
+    pub system: ProofSystemKind,
+    /// 证明密钥
+    pub proving_key: ProvingKey,
+    /// 序列化的验证密钥
+    pub verification_key: Vec<u8>,
+}
+
+/// 证明系统后端接口
+///
+/// 新增后端（例如 PLONK）只需实现该接口并在 [`default_backends`] 中注册，
+/// 证明器与验证器根据电路配置的 [`ProofSystemKind`] 选择后端。
+pub trait ProofSystem: Send + Sync {
+    /// 后端标识
+    fn kind(&self) -> ProofSystemKind;
+
+    /// 为电路结构生成密钥对
+    fn setup(&self, circuit: CircuitInstance) -> Result<CircuitKeys>;
+
+    /// 使用带见证的电路实例生成证明
+    fn prove(&self, keys: &CircuitKeys, circuit: CircuitInstance) -> Result<Vec<u8>>;
+
+    /// 生成证明，并在合成期间向 monitor 报告进度、按其要求中止
+    ///
+    /// 默认只在证明前后检查；能够逐约束合成的后端应覆盖该方法。
+    fn prove_monitored(
+        &self,
+        keys: &CircuitKeys,
+        circuit: CircuitInstance,
+        monitor: &dyn ProvingMonitor,
+    ) -> Result<Vec<u8>> {
+        monitor.check().map_err(|e| ZKProofError::ProofGenerationFailed(e.to_string()))?;
+        let proof = self.prove(keys, circuit)?;
+        monitor.check().map_err(|e| ZKProofError::ProofGenerationFailed(e.to_string()))?;
+        Ok(proof)
+    }
+
+    /// 验证证明
+    fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[u128]) -> Result<bool>;
+
+    /// 验证以公开输入摘要作为唯一公开输入的证明，见 [`PublicInputMode::Digest`]
+    fn verify_digest(&self, verification_key: &[u8], proof: &[u8], digest: &[u8; 32]) -> Result<bool>;
+
+    /// 按公开语句的形式验证证明
+    fn verify_statement(&self, verification_key: &[u8], proof: &[u8], statement: &Statement) -> Result<bool> {
+        match statement {
+            Statement::Inputs(public_inputs) => self.verify(verification_key, proof, public_inputs),
+            Statement::Digest(digest) => self.verify_digest(verification_key, proof, digest),
+        }
+    }
+
+    /// 批量验证共享同一验证密钥的多个证明，按输入顺序返回每个证明的结果
+    ///
+    /// 默认逐个验证；支持聚合验证的后端可以覆盖该方法。
+    fn batch_verify(&self, verification_key: &[u8], proofs: &[(&[u8], &[u128])]) -> Result<Vec<bool>> {
+        proofs
+            .iter()
+            .map(|(proof, public_inputs)| self.verify(verification_key, proof, public_inputs))
+            .collect()
+    }
+
+    /// 序列化证明密钥
+    fn serialize_proving_key(&self, keys: &CircuitKeys) -> Result<Vec<u8>>;
+
+    /// 从序列化的证明密钥恢复密钥对
+    fn deserialize_keys(&self, proving_key: &[u8]) -> Result<CircuitKeys>;
+}
+
+/// 构建默认启用的全部后端
+pub fn default_backends() -> HashMap<ProofSystemKind, Arc<dyn ProofSystem>> {
+    let backends: Vec<Arc<dyn ProofSystem>> = vec![Arc::new(MockProofSystem), Arc::new(Groth16ProofSystem)];
+    backends.into_iter().map(|b| (b.kind(), b)).collect()
+}
+
+/// 确定性模拟后端
+///
+/// 证明为 `sha256(验证密钥 || 公开输入)`（摘要电路为 `sha256(验证密钥 || 摘要)`），
+/// 生成证明前会在测试约束系统中检查见证是否满足全部约束。
+pub struct MockProofSystem;
+
+impl MockProofSystem {
+    /// 在测试约束系统中检查见证满足全部约束后输出模拟证明
+    fn check_and_prove<C: Circuit<Scalar>>(
+        keys: &CircuitKeys,
+        circuit: C,
+        statement: &Statement,
+    ) -> Result<Vec<u8>> {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        circuit
+            .synthesize(&mut cs)
+            .map_err(|e| ZKProofError::ProofGenerationFailed(e.to_string()))?;
+        if let Some(constraint) = cs.which_is_unsatisfied() {
+            return Err(ZKProofError::ProofGenerationFailed(format!(
+                "constraint not satisfied: {}",
+                constraint
+            ))
+            .into());
+        }
+
+        Ok(Self::mock_proof(&keys.verification_key, statement))
+    }
+
+    fn mock_proof(verification_key: &[u8], statement: &Statement) -> Vec<u8> {
+        match statement {
+            Statement::Inputs(public_inputs) => zkproof_verifier::mock_proof(verification_key, public_inputs).to_vec(),
+            Statement::Digest(digest) => zkproof_verifier::mock_digest_proof(verification_key, digest).to_vec(),
+        }
+    }
+}
+
+impl ProofSystem for MockProofSystem {
+    fn kind(&self) -> ProofSystemKind {
+        ProofSystemKind::Mock
+    }
+
+    fn setup(&self, circuit: CircuitInstance) -> Result<CircuitKeys> {
+        let key = utils::hash_data(format!("mock:{}", circuit.shape()).as_bytes()).to_vec();
+        Ok(CircuitKeys {
+            system: ProofSystemKind::Mock,
+            proving_key: ProvingKey::Mock(key.clone()),
+            verification_key: key,
+        })
+    }
+
+    fn prove(&self, keys: &CircuitKeys, circuit: CircuitInstance) -> Result<Vec<u8>> {
+        let statement = circuit
+            .statement()
+            .ok_or_else(|| ZKProofError::ProofGenerationFailed("missing witness".to_string()))?;
+        Self::check_and_prove(keys, circuit, &statement)
+    }
+
+    fn prove_monitored(
+        &self,
+        keys: &CircuitKeys,
+        circuit: CircuitInstance,
+        monitor: &dyn ProvingMonitor,
+    ) -> Result<Vec<u8>> {
+        let statement = circuit
+            .statement()
+            .ok_or_else(|| ZKProofError::ProofGenerationFailed("missing witness".to_string()))?;
+        Self::check_and_prove(keys, MonitoredCircuit::new(circuit, monitor), &statement)
+    }
+
+    fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[u128]) -> Result<bool> {
+        Ok(zkproof_verifier::verify(ProofSystemKind::Mock, verification_key, proof, public_inputs))
+    }
+
+    fn verify_digest(&self, verification_key: &[u8], proof: &[u8], digest: &[u8; 32]) -> Result<bool> {
+        Ok(zkproof_verifier::verify_digest(ProofSystemKind::Mock, verification_key, proof, digest))
+    }
+
+    fn serialize_proving_key(&self, keys: &CircuitKeys) -> Result<Vec<u8>> {
+        match &keys.proving_key {
+            ProvingKey::Mock(key) => Ok(key.clone()),
+            _ => Err(ZKProofError::CryptographicError("not a mock proving key".to_string()).into()),
+        }
+    }
+
+    fn deserialize_keys(&self, proving_key: &[u8]) -> Result<CircuitKeys> {
+        Ok(CircuitKeys {
+            system: ProofSystemKind::Mock,
+            proving_key: ProvingKey::Mock(proving_key.to_vec()),
+            verification_key: proving_key.to_vec(),
+        })
+    }
+}
+
+/// BLS12-381 上的 Groth16 后端
+pub struct Groth16ProofSystem;
+
+impl Groth16ProofSystem {
+    fn parameters(keys: &CircuitKeys) -> Result<&Arc<Parameters<Bls12>>> {
+        match &keys.proving_key {
+            ProvingKey::Groth16(params) => Ok(params),
+            _ => Err(ZKProofError::CryptographicError("not a Groth16 proving key".to_string()).into()),
+        }
+    }
+
+    fn create_proof<C: Circuit<Scalar>>(keys: &CircuitKeys, circuit: C) -> Result<Vec<u8>> {
+        let params = Self::parameters(keys)?;
+        let proof = groth16::create_random_proof(circuit, params.as_ref(), &mut OsRng)
+            .map_err(|e| ZKProofError::ProofGenerationFailed(e.to_string()))?;
+
+        let mut bytes = Vec::new();
+        proof.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// 由 bellman 参数构造密钥（本地 setup 或可信设置仪式的结果）
+    pub fn keys_from_parameters(params: Parameters<Bls12>) -> Result<CircuitKeys> {
+        let mut verification_key = Vec::new();
+        params.vk.write(&mut verification_key)?;
+        Ok(CircuitKeys {
+            system: ProofSystemKind::Groth16,
+            proving_key: ProvingKey::Groth16(Arc::new(params)),
+            verification_key,
+        })
+    }
+}
+
+impl ProofSystem for Groth16ProofSystem {
+    fn kind(&self) -> ProofSystemKind {
+        ProofSystemKind::Groth16
+    }
+
+    fn setup(&self, circuit: CircuitInstance) -> Result<CircuitKeys> {
+        let params = groth16::generate_random_parameters::<Bls12, _, _>(circuit, &mut OsRng)
+            .map_err(|e| ZKProofError::CryptographicError(e.to_string()))?;
+        Self::keys_from_parameters(params)
+    }
+
+    fn prove(&self, keys: &CircuitKeys, circuit: CircuitInstance) -> Result<Vec<u8>> {
+        Self::create_proof(keys, circuit)
+    }
+
+    fn prove_monitored(
+        &self,
+        keys: &CircuitKeys,
+        circuit: CircuitInstance,
+        monitor: &dyn ProvingMonitor,
+    ) -> Result<Vec<u8>> {
+        Self::create_proof(keys, MonitoredCircuit::new(circuit, monitor))
+    }
+
+    fn verify(&self, verification_key: &[u8], proof: &[u8], public_inputs: &[u128]) -> Result<bool> {
+        // 解码失败视为无效证明而非错误
+        Ok(zkproof_verifier::groth16::verify(verification_key, proof, public_inputs))
+    }
+
+    fn verify_digest(&self, verification_key: &[u8], proof: &[u8], digest: &[u8; 32]) -> Result<bool> {
+        Ok(zkproof_verifier::verify_digest(ProofSystemKind::Groth16, verification_key, proof, digest))
+    }
+
+    /// 以随机线性组合合并全部配对检查，只需一次多重配对；
+    /// 批量检查失败时退回逐个验证以定位无效证明。
+    fn batch_verify(&self, verification_key: &[u8], proofs: &[(&[u8], &[u128])]) -> Result<Vec<bool>> {
+        let vk = match groth16::VerifyingKey::<Bls12>::read(verification_key) {
+            Ok(vk) => vk,
+            Err(_) => return Ok(vec![false; proofs.len()]),
+        };
+
+        // 无法解码的证明直接判为无效，其余证明进入批量验证
+        let items: Vec<Option<groth16::batch::Item<Bls12>>> = proofs
+            .iter()
+            .map(|(proof, public_inputs)| {
+                Proof::<Bls12>::read(*proof)
+                    .ok()
+                    .map(|p| (p, utils::public_inputs_to_scalars(public_inputs)).into())
+            })
+            .collect();
+
+        let mut batch = groth16::batch::Verifier::<Bls12>::new();
+        for item in items.iter().flatten() {
+            batch.queue(item.clone());
+        }
+        if batch.verify(OsRng, &vk).is_ok() {
+            return Ok(items.iter().map(Option::is_some).collect());
+        }
+
+        let pvk = groth16::prepare_verifying_key(&vk);
+        Ok(items
+            .into_iter()
+            .map(|item| item.is_some_and(|item| item.verify_single(&pvk).is_ok()))
+            .collect())
+    }
+
+    fn serialize_proving_key(&self, keys: &CircuitKeys) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        Self::parameters(keys)?.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn deserialize_keys(&self, proving_key: &[u8]) -> Result<CircuitKeys> {
+        let params = Parameters::<Bls12>::read(proving_key, true)
+            .map_err(|e| ZKProofError::CryptographicError(e.to_string()))?;
+        Self::keys_from_parameters(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instance(public_metric: u128) -> (CircuitInstance, CircuitInstance) {
+        let circuit = NetworkMetricCircuit::new(1, 4, 3, "Test".to_string());
+        let witness = MetricWitness {
+            private_data: vec![100, 200, 300],
+            reliability_scores: vec![90, 85],
+            public_metric,
+            quality_score: 80,
+            time_window_hours: 1,
+            metric_tag: crate::nullifier::metric_tag("block_time"),
+            window_start: 1_700_000_000,
+            max_staleness: 3600,
+            source_secret: crate::nullifier::secret_scalar(&[7; 32]),
+            sample_counts: vec![2, 1],
+            blindings: vec![crate::commitment::blinding_scalar(&[1; 32]), crate::commitment::blinding_scalar(&[2; 32])],
+            source_timestamps: vec![1_700_003_000, 1_700_003_500],
+        };
+        (
+            CircuitInstance::NetworkMetric(MetricAggregationCircuit::blank(&circuit)),
+            CircuitInstance::NetworkMetric(MetricAggregationCircuit::with_witness(&circuit, witness)),
+        )
+    }
+
+    #[test]
+    fn test_mock_backend_is_deterministic() {
+        let backend = MockProofSystem;
+        let (blank, assigned) = instance(200);
+        let keys = backend.setup(blank).unwrap();
+        let inputs = assigned.public_inputs().unwrap();
+
+        let proof1 = backend.prove(&keys, assigned.clone()).unwrap();
+        let proof2 = backend.prove(&keys, assigned).unwrap();
+        assert_eq!(proof1, proof2);
+
+        assert!(backend.verify(&keys.verification_key, &proof1, &inputs).unwrap());
+        let mut tampered = inputs;
+        tampered[0] = 201;
+        assert!(!backend.verify(&keys.verification_key, &proof1, &tampered).unwrap());
+    }
+
+    #[test]
+    fn test_digested_instance_exposes_only_digest() {
+        let (_, assigned) = instance(200);
+        let inputs = assigned.public_inputs().unwrap();
+        let digested = CircuitInstance::Digested(Box::new(assigned.clone()));
+        assert_eq!(digested.shape(), format!("{}:digest", assigned.shape()));
+        let digest = utils::public_input_digest(&inputs);
+        assert_eq!(digested.public_inputs().unwrap(), inputs);
+        assert_eq!(digested.statement().unwrap(), Statement::Digest(digest));
+
+        let mut plain = TestConstraintSystem::<Scalar>::new();
+        assigned.synthesize(&mut plain).unwrap();
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        digested.synthesize(&mut cs).unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(cs.num_inputs(), 2);
+        assert!(cs.verify(&[utils::public_input_digest_scalar(&inputs)]));
+        assert!(!cs.verify(&[utils::public_input_digest_scalar(&inputs[1..])]));
+        assert!(cs.num_constraints() - plain.num_constraints() <= inputs.len() * 360 + 1);
+
+        // 内部电路的约束仍然生效
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        CircuitInstance::Digested(Box::new(instance(500).1)).synthesize(&mut cs).unwrap();
+        assert!(!cs.is_satisfied());
+    }
+
+    #[test]
+    fn test_mock_backend_rejects_unsatisfied_witness() {
+        let backend = MockProofSystem;
+        let (blank, assigned) = instance(500);
+        let keys = backend.setup(blank).unwrap();
+
+        assert!(backend.prove(&keys, assigned).is_err());
+    }
+
+    #[test]
+    fn test_groth16_key_serialization_round_trip() {
+        let backend = Groth16ProofSystem;
+        let (blank, assigned) = instance(200);
+        let keys = backend.setup(blank).unwrap();
+
+        let bytes = backend.serialize_proving_key(&keys).unwrap();
+        let restored = backend.deserialize_keys(&bytes).unwrap();
+        assert_eq!(restored.verification_key, keys.verification_key);
+
+        let inputs = assigned.public_inputs().unwrap();
+        let proof = backend.prove(&restored, assigned).unwrap();
+        assert!(backend.verify(&keys.verification_key, &proof, &inputs).unwrap());
+        let mut tampered = inputs;
+        tampered[1] = 81;
+        assert!(!backend.verify(&keys.verification_key, &proof, &tampered).unwrap());
+    }
+
+    #[test]
+    fn test_groth16_batch_verify_pinpoints_invalid_proofs() {
+        let backend = Groth16ProofSystem;
+        let (blank, _) = instance(200);
+        let keys = backend.setup(blank).unwrap();
+
+        let metrics = [200u128, 205, 210];
+        let proofs: Vec<Vec<u8>> = metrics
+            .iter()
+            .map(|m| backend.prove(&keys, instance(*m).1).unwrap())
+            .collect();
+        let inputs: Vec<Vec<u128>> = metrics.iter().map(|m| instance(*m).1.public_inputs().unwrap()).collect();
+
+        let batch: Vec<(&[u8], &[u128])> = proofs
+            .iter()
+            .zip(&inputs)
+            .map(|(p, i)| (p.as_slice(), i.as_slice()))
+            .collect();
+        assert_eq!(backend.batch_verify(&keys.verification_key, &batch).unwrap(), vec![true; 3]);
+
+        // 篡改一个公开输入并加入一个无法解码的证明
+        let mut tampered = inputs[1].clone();
+        tampered[0] = 206;
+        let mut batch = batch;
+        batch[1].1 = &tampered;
+        batch.push((&[0u8; 8], &inputs[0]));
+        assert_eq!(
+            backend.batch_verify(&keys.verification_key, &batch).unwrap(),
+            vec![true, false, true, false]
+        );
+    }
+}
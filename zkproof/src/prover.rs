@@ -1,54 +1,208 @@
-// 证明器模块 - 简化实现
+// 证明器模块 - 通过可插拔的证明系统后端生成证明
 use anyhow::Result;
+use std::sync::Arc;
+
 use crate::circuits::*;
+use crate::pool::{JobOptions, ProofHandle, ProvingPool, ProvingPoolConfig};
+use crate::proof_system::CircuitWitness;
+use crate::registry::KeyRegistry;
+use crate::{ZKProof, ZKProofError};
 
 /// 零知识证明生成器
+///
+/// 证明在任务池的工作线程上生成，不阻塞调用方的异步运行时。
 pub struct ZKProver {
-    /// 支持的电路类型
-    supported_circuits: Vec<CircuitType>,
+    /// 证明任务池
+    pool: ProvingPool,
 }
 
 impl ZKProver {
-    /// 创建新的证明器
-    pub fn new() -> Result<Self> {
+    /// 创建新的证明器（使用默认任务池配置）
+    pub fn new(registry: Arc<KeyRegistry>) -> Result<Self> {
+        Self::with_pool_config(registry, ProvingPoolConfig::default())
+    }
+
+    /// 使用指定任务池配置创建证明器
+    pub fn with_pool_config(registry: Arc<KeyRegistry>, config: ProvingPoolConfig) -> Result<Self> {
         Ok(Self {
-            supported_circuits: vec![
-                CircuitType::NetworkMetric,
-                CircuitType::DataIntegrity,
-                CircuitType::AggregationProof,
-            ],
+            pool: ProvingPool::new(registry, config),
         })
     }
 
-    /// 生成证明
-    pub fn generate_proof(
+    /// 证明任务池
+    pub fn pool(&self) -> &ProvingPool {
+        &self.pool
+    }
+
+    /// 生成网络指标证明
+    pub async fn generate_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: MetricWitness,
+    ) -> Result<ZKProof> {
+        self.submit_proof(circuit, witness, JobOptions::default())?.wait().await
+    }
+
+    /// 提交网络指标证明任务，立即返回任务句柄
+    pub fn submit_proof(
         &self,
-        circuit_type: CircuitType,
-        private_inputs: &[u128],
-        public_inputs: &[u128],
-    ) -> Result<Vec<u8>> {
-        match circuit_type {
-            CircuitType::NetworkMetrics => self.generate_metric_proof(private_inputs, public_inputs),
-            CircuitType::Privacy => self.generate_privacy_proof(private_inputs, public_inputs),
-            CircuitType::Consensus => self.generate_consensus_proof(private_inputs, public_inputs),
+        circuit: &NetworkMetricCircuit,
+        witness: MetricWitness,
+        options: JobOptions,
+    ) -> Result<ProofHandle> {
+        // 约束不满足时 Groth16 仍会输出一个无法通过验证的证明，因此先在本地检查
+        if !circuit.verify_constraints(
+            &witness.private_data,
+            &witness.reliability_scores,
+            witness.public_metric,
+            witness.quality_score,
+        ) {
+            return Err(ZKProofError::InvalidInputData(
+                "submission does not satisfy the metric circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        if !circuit.get_public_input_spec().is_satisfied_by(&public_inputs) {
+            return Err(ZKProofError::InvalidInputData(
+                "public inputs do not satisfy the circuit input spec".to_string(),
+            )
+            .into());
         }
+
+        self.pool.submit(circuit, CircuitWitness::NetworkMetric(witness), public_inputs, options)
     }
 
-    /// 生成网络指标证明
-    fn generate_metric_proof(&self, _private: &[u128], _public: &[u128]) -> Result<Vec<u8>> {
-        // 模拟证明生成
-        Ok(b"mock_metric_proof".to_vec())
+    /// 生成数据完整性证明
+    pub async fn generate_integrity_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: IntegrityWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_integrity_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "batch does not satisfy the data integrity circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::DataIntegrity(witness), public_inputs).await
+    }
+
+    /// 生成百分位数证明
+    pub async fn generate_percentile_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: PercentileWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_percentile_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "samples do not satisfy the percentile circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::Percentile(witness), public_inputs).await
+    }
+
+    /// 生成方差证明
+    pub async fn generate_variance_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: VarianceWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_variance_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "samples do not satisfy the variance circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::Variance(witness), public_inputs).await
     }
 
-    /// 生成隐私证明
-    fn generate_privacy_proof(&self, _private: &[u128], _public: &[u128]) -> Result<Vec<u8>> {
-        // 模拟证明生成
-        Ok(b"mock_privacy_proof".to_vec())
+    /// 生成差分隐私求和证明
+    pub async fn generate_privacy_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: PrivacyWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_privacy_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "samples do not satisfy the privacy circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::Privacy(witness), public_inputs).await
     }
 
-    /// 生成共识证明
-    fn generate_consensus_proof(&self, _private: &[u128], _public: &[u128]) -> Result<Vec<u8>> {
-        // 模拟证明生成
-        Ok(b"mock_consensus_proof".to_vec())
+    /// 生成共识参与证明
+    pub async fn generate_participation_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: ParticipationWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_participation_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "authored blocks do not satisfy the consensus participation circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::Participation(witness), public_inputs).await
     }
-}
\ No newline at end of file
+
+    /// 生成节点在线率证明
+    pub async fn generate_uptime_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: UptimeWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_uptime_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "heartbeat log does not satisfy the node reliability circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::Uptime(witness), public_inputs).await
+    }
+
+    /// 生成滚动聚合证明
+    pub async fn generate_rolling_proof(
+        &mut self,
+        circuit: &NetworkMetricCircuit,
+        witness: RollingWitness,
+    ) -> Result<ZKProof> {
+        if !circuit.verify_rolling_constraints(&witness) {
+            return Err(ZKProofError::InvalidInputData(
+                "window samples and rolling state do not satisfy the rolling aggregation circuit constraints".to_string(),
+            )
+            .into());
+        }
+
+        let public_inputs = witness.public_inputs();
+        self.prove(circuit, CircuitWitness::Rolling(witness), public_inputs).await
+    }
+
+    /// 在任务池中生成证明并等待结果
+    async fn prove(
+        &self,
+        circuit: &NetworkMetricCircuit,
+        witness: CircuitWitness,
+        public_inputs: Vec<u128>,
+    ) -> Result<ZKProof> {
+        self.pool
+            .submit(circuit, witness, public_inputs, JobOptions::default())?
+            .wait()
+            .await
+    }
+}
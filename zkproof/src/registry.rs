@@ -0,0 +1,494 @@
+// 密钥注册表模块 - 按电路管理证明/验证参数
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::circuits::NetworkMetricCircuit;
+use crate::proof_system::{self, CircuitInstance, CircuitKeys, ProofSystem, ProofSystemKind};
+use crate::ZKProofError;
+
+/// 已注册的验证密钥
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerificationKeyEntry {
+    /// 电路ID
+    pub circuit_id: u32,
+    /// 证明系统后端
+    pub system: ProofSystemKind,
+    /// 序列化的验证密钥
+    pub verification_key: Vec<u8>,
+    /// 验证密钥的 SHA-256 内容地址
+    pub vk_hash: [u8; 32],
+}
+
+impl VerificationKeyEntry {
+    fn new(circuit_id: u32, system: ProofSystemKind, verification_key: Vec<u8>) -> Self {
+        let vk_hash = zkproof_verifier::vk_hash(&verification_key);
+        Self {
+            circuit_id,
+            system,
+            verification_key,
+            vk_hash,
+        }
+    }
+
+    /// 十六进制格式的验证密钥哈希
+    pub fn vk_hash_hex(&self) -> String {
+        hex::encode(self.vk_hash)
+    }
+}
+
+/// 注册表条目：验证密钥总是存在，证明密钥仅在本地生成或加载了参数文件时存在
+#[derive(Clone)]
+struct RegistryEntry {
+    verification: VerificationKeyEntry,
+    keys: Option<CircuitKeys>,
+}
+
+/// 注册表条目的键：(电路ID, 证明后端)
+type RegistryKey = (u32, ProofSystemKind);
+
+/// 密钥注册表
+///
+/// 以 (电路ID, 后端) 为键保存参数。配置了参数目录时，参数优先从磁盘加载，
+/// 新生成的参数也会写回磁盘，文件名为 `circuit-<id>-<backend>.params` 与 `circuit-<id>-<backend>.vk`。
+/// 验证器只信任注册表中的验证密钥，而不使用证明中携带的密钥。
+pub struct KeyRegistry {
+    /// 可用的证明系统后端
+    backends: HashMap<ProofSystemKind, Arc<dyn ProofSystem>>,
+    /// 参数文件目录
+    params_dir: Option<PathBuf>,
+    /// 已加载的密钥
+    entries: RwLock<HashMap<RegistryKey, RegistryEntry>>,
+    /// 每个 (电路ID, 后端) 的加载/setup 锁
+    setup_locks: Mutex<HashMap<RegistryKey, Arc<Mutex<()>>>>,
+    /// 缺少参数时是否允许执行 setup
+    allow_setup: bool,
+}
+
+impl KeyRegistry {
+    /// 创建仅保存在内存中的注册表
+    pub fn new() -> Self {
+        Self {
+            backends: proof_system::default_backends(),
+            params_dir: None,
+            entries: RwLock::new(HashMap::new()),
+            setup_locks: Mutex::new(HashMap::new()),
+            allow_setup: true,
+        }
+    }
+
+    /// 创建以指定目录作为参数存储的注册表
+    pub fn with_params_dir(params_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            params_dir: Some(params_dir.into()),
+            ..Self::new()
+        }
+    }
+
+    /// 禁止在缺少参数时执行 setup，参数只能从磁盘加载或导入
+    pub fn without_setup(mut self) -> Self {
+        self.allow_setup = false;
+        self
+    }
+
+    /// 参数文件目录
+    pub fn params_dir(&self) -> Option<&Path> {
+        self.params_dir.as_deref()
+    }
+
+    /// 查找后端
+    pub fn backend(&self, kind: ProofSystemKind) -> Result<Arc<dyn ProofSystem>> {
+        self.backends.get(&kind).cloned().ok_or_else(|| {
+            ZKProofError::CryptographicError(format!("proof system {:?} is not available", kind)).into()
+        })
+    }
+
+    /// 获取电路的证明密钥：依次查找内存、参数目录，都不存在时执行 setup
+    ///
+    /// 同一 (电路ID, 后端) 的加载与 setup 串行进行，并发的首次使用只执行一次 setup。
+    /// 电路已有注册的验证密钥但没有证明参数时（例如仅做验证的节点）直接报错，
+    /// 不会执行 setup，也不会改写参数目录中的任何文件。
+    pub fn get_or_generate(&self, circuit: &NetworkMetricCircuit) -> Result<CircuitKeys> {
+        let key = (circuit.circuit_id, circuit.proof_system);
+        if let Some(keys) = self.cached_keys(key) {
+            return Ok(keys);
+        }
+
+        let lock = self.setup_lock(key);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        // 等待期间其它任务可能已完成加载或 setup
+        if let Some(keys) = self.cached_keys(key) {
+            return Ok(keys);
+        }
+
+        let keys = match self.load_proving_key(circuit.circuit_id, circuit.proof_system)? {
+            Some(keys) => keys,
+            None => {
+                if let Some(pinned) = self.verification_key(circuit.circuit_id, circuit.proof_system)? {
+                    return Err(ZKProofError::CryptographicError(format!(
+                        "circuit {} has a registered verification key {} but no proving parameters; \
+                         import the parameters instead of running setup",
+                        circuit.circuit_id,
+                        pinned.vk_hash_hex()
+                    ))
+                    .into());
+                }
+                if !self.allow_setup {
+                    return Err(ZKProofError::CryptographicError(format!(
+                        "no {} parameters for circuit {}; run `zkproof setup` first",
+                        circuit.proof_system.name(),
+                        circuit.circuit_id
+                    ))
+                    .into());
+                }
+                let keys = self
+                    .backend(circuit.proof_system)?
+                    .setup(CircuitInstance::from_circuit(circuit, None)?)?;
+                self.store(circuit.circuit_id, &keys)?;
+                keys
+            }
+        };
+
+        let mut entries = self.write_entries();
+        if let Some(entry) = entries.get_mut(&key) {
+            if entry.verification.verification_key != keys.verification_key {
+                return Err(ZKProofError::CryptographicError(format!(
+                    "proving key for circuit {} does not match the registered verification key",
+                    circuit.circuit_id
+                ))
+                .into());
+            }
+            entry.keys = Some(keys.clone());
+        } else {
+            entries.insert(
+                key,
+                RegistryEntry {
+                    verification: VerificationKeyEntry::new(
+                        circuit.circuit_id,
+                        circuit.proof_system,
+                        keys.verification_key.clone(),
+                    ),
+                    keys: Some(keys.clone()),
+                },
+            );
+        }
+        Ok(keys)
+    }
+
+    /// 查找电路的验证密钥：依次查找内存与参数目录，不会触发 setup
+    pub fn verification_key(
+        &self,
+        circuit_id: u32,
+        system: ProofSystemKind,
+    ) -> Result<Option<VerificationKeyEntry>> {
+        if let Some(entry) = self.read_entries().get(&(circuit_id, system)) {
+            return Ok(Some(entry.verification.clone()));
+        }
+
+        let path = match self.file_path(circuit_id, system, "vk") {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        };
+        let entry = VerificationKeyEntry::new(circuit_id, system, fs::read(path)?);
+
+        self.write_entries()
+            .entry((circuit_id, system))
+            .or_insert_with(|| RegistryEntry {
+                verification: entry.clone(),
+                keys: None,
+            });
+        Ok(Some(entry))
+    }
+
+    /// 注册外部提供的验证密钥（例如仅做验证的节点），返回其内容地址
+    pub fn register_verification_key(
+        &self,
+        circuit_id: u32,
+        system: ProofSystemKind,
+        verification_key: Vec<u8>,
+    ) -> Result<[u8; 32]> {
+        let entry = VerificationKeyEntry::new(circuit_id, system, verification_key);
+        let mut entries = self.write_entries();
+        if let Some(existing) = entries.get(&(circuit_id, system)) {
+            if existing.verification.vk_hash != entry.vk_hash {
+                return Err(ZKProofError::CryptographicError(format!(
+                    "circuit {} already has a different verification key registered",
+                    circuit_id
+                ))
+                .into());
+            }
+            return Ok(entry.vk_hash);
+        }
+
+        let vk_hash = entry.vk_hash;
+        entries.insert(
+            (circuit_id, system),
+            RegistryEntry {
+                verification: entry,
+                keys: None,
+            },
+        );
+        Ok(vk_hash)
+    }
+
+    /// 导入外部生成的密钥（例如可信设置仪式的结果）并写入参数目录
+    ///
+    /// 电路在内存或参数目录中已有不同的验证密钥时拒绝导入。
+    pub fn import_keys(&self, circuit_id: u32, keys: CircuitKeys) -> Result<VerificationKeyEntry> {
+        let lock = self.setup_lock((circuit_id, keys.system));
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let entry = VerificationKeyEntry::new(circuit_id, keys.system, keys.verification_key.clone());
+        if let Some(existing) = self.verification_key(circuit_id, keys.system)? {
+            if existing.vk_hash != entry.vk_hash {
+                return Err(ZKProofError::CryptographicError(format!(
+                    "circuit {} already has a different verification key registered",
+                    circuit_id
+                ))
+                .into());
+            }
+        }
+
+        self.store(circuit_id, &keys)?;
+        self.write_entries().insert(
+            (circuit_id, keys.system),
+            RegistryEntry {
+                verification: entry.clone(),
+                keys: Some(keys),
+            },
+        );
+        Ok(entry)
+    }
+
+    /// 列出已注册的验证密钥
+    pub fn list(&self) -> Vec<VerificationKeyEntry> {
+        let mut entries: Vec<_> = self
+            .read_entries()
+            .values()
+            .map(|e| e.verification.clone())
+            .collect();
+        entries.sort_by_key(|e| e.circuit_id);
+        entries
+    }
+
+    // 私有辅助方法
+
+    /// 内存中已加载的证明密钥
+    fn cached_keys(&self, key: RegistryKey) -> Option<CircuitKeys> {
+        self.read_entries().get(&key).and_then(|e| e.keys.clone())
+    }
+
+    /// (电路ID, 后端) 对应的加载/setup 锁
+    fn setup_lock(&self, key: RegistryKey) -> Arc<Mutex<()>> {
+        self.setup_locks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_default()
+            .clone()
+    }
+
+    /// 从参数目录加载证明密钥
+    fn load_proving_key(&self, circuit_id: u32, system: ProofSystemKind) -> Result<Option<CircuitKeys>> {
+        let path = match self.file_path(circuit_id, system, "params") {
+            Some(path) if path.exists() => path,
+            _ => return Ok(None),
+        };
+
+        let keys = self.backend(system)?.deserialize_keys(&fs::read(&path)?)?;
+
+        // 同目录下的验证密钥文件必须与参数一致
+        if let Some(vk_path) = self.file_path(circuit_id, system, "vk") {
+            if vk_path.exists() && fs::read(&vk_path)? != keys.verification_key {
+                return Err(ZKProofError::CryptographicError(format!(
+                    "{} does not match {}",
+                    vk_path.display(),
+                    path.display()
+                ))
+                .into());
+            }
+        }
+
+        Ok(Some(keys))
+    }
+
+    /// 将密钥写入参数目录
+    fn store(&self, circuit_id: u32, keys: &CircuitKeys) -> Result<()> {
+        let dir = match &self.params_dir {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+        fs::create_dir_all(dir)?;
+
+        let params = self.backend(keys.system)?.serialize_proving_key(keys)?;
+        if let Some(path) = self.file_path(circuit_id, keys.system, "params") {
+            fs::write(path, params)?;
+        }
+        if let Some(path) = self.file_path(circuit_id, keys.system, "vk") {
+            fs::write(path, &keys.verification_key)?;
+        }
+        Ok(())
+    }
+
+    /// 参数文件路径
+    fn file_path(&self, circuit_id: u32, system: ProofSystemKind, extension: &str) -> Option<PathBuf> {
+        self.params_dir.as_ref().map(|dir| {
+            dir.join(format!("circuit-{}-{}.{}", circuit_id, system.name(), extension))
+        })
+    }
+
+    fn read_entries(&self) -> std::sync::RwLockReadGuard<'_, HashMap<(u32, ProofSystemKind), RegistryEntry>> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write_entries(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<(u32, ProofSystemKind), RegistryEntry>> {
+        self.entries.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for KeyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("zkproof-registry-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_generate_and_reload_from_disk() {
+        let dir = temp_dir("reload");
+        let circuit = NetworkMetricCircuit::new(7, 2, 2, "Test".to_string());
+
+        let registry = KeyRegistry::with_params_dir(&dir);
+        let keys = registry.get_or_generate(&circuit).unwrap();
+        let entry = registry.verification_key(7, ProofSystemKind::Groth16).unwrap().unwrap();
+        assert_eq!(entry.vk_hash, crate::utils::hash_data(&keys.verification_key));
+        assert!(dir.join("circuit-7-groth16.params").exists());
+        assert!(dir.join("circuit-7-groth16.vk").exists());
+
+        // 新的注册表实例从磁盘加载相同的参数
+        let reloaded = KeyRegistry::with_params_dir(&dir);
+        let vk_only = reloaded.verification_key(7, ProofSystemKind::Groth16).unwrap().unwrap();
+        assert_eq!(vk_only, entry);
+        let keys2 = reloaded.get_or_generate(&circuit).unwrap();
+        assert_eq!(keys2.verification_key, keys.verification_key);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_conflicting_verification_key_is_rejected() {
+        let registry = KeyRegistry::new();
+        let circuit = NetworkMetricCircuit::new(1, 2, 2, "Test".to_string())
+            .with_proof_system(ProofSystemKind::Mock);
+
+        let keys = registry.get_or_generate(&circuit).unwrap();
+        assert!(registry
+            .register_verification_key(1, ProofSystemKind::Mock, keys.verification_key.clone())
+            .is_ok());
+        assert!(registry
+            .register_verification_key(1, ProofSystemKind::Mock, vec![1, 2, 3])
+            .is_err());
+        assert!(registry.verification_key(2, ProofSystemKind::Mock).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_verification_only_entry_blocks_setup() {
+        let dir = temp_dir("vk-only");
+        let circuit = NetworkMetricCircuit::new(3, 2, 2, "Test".to_string());
+        let keys = KeyRegistry::new().get_or_generate(&circuit).unwrap();
+
+        // 仅注册验证密钥的节点不会执行 setup，也不会写入参数目录
+        let registry = KeyRegistry::with_params_dir(&dir);
+        registry
+            .register_verification_key(3, ProofSystemKind::Groth16, keys.verification_key.clone())
+            .unwrap();
+        assert!(registry.get_or_generate(&circuit).is_err());
+        assert!(!dir.exists());
+
+        // 磁盘上只有 .vk 文件时同样报错，且文件保持不变
+        fs::create_dir_all(&dir).unwrap();
+        let vk_path = dir.join("circuit-3-groth16.vk");
+        fs::write(&vk_path, &keys.verification_key).unwrap();
+        let registry = KeyRegistry::with_params_dir(&dir);
+        assert!(registry.get_or_generate(&circuit).is_err());
+        assert_eq!(fs::read(&vk_path).unwrap(), keys.verification_key);
+        assert!(!dir.join("circuit-3-groth16.params").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_setup_can_be_disabled() {
+        let dir = temp_dir("no-setup");
+        let circuit = NetworkMetricCircuit::new(4, 2, 2, "Test".to_string());
+
+        let registry = KeyRegistry::with_params_dir(&dir).without_setup();
+        assert!(registry.get_or_generate(&circuit).is_err());
+        assert!(!dir.exists());
+
+        // 已有参数时照常加载
+        let keys = KeyRegistry::with_params_dir(&dir).get_or_generate(&circuit).unwrap();
+        let registry = KeyRegistry::with_params_dir(&dir).without_setup();
+        assert_eq!(registry.get_or_generate(&circuit).unwrap().verification_key, keys.verification_key);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_first_use_runs_setup_once() {
+        let dir = temp_dir("concurrent");
+        let circuit = NetworkMetricCircuit::new(5, 2, 2, "Test".to_string());
+        let registry = KeyRegistry::with_params_dir(&dir);
+
+        let vks: Vec<Vec<u8>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| registry.get_or_generate(&circuit).unwrap().verification_key))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert!(vks.iter().all(|vk| *vk == vks[0]));
+        assert_eq!(fs::read(dir.join("circuit-5-groth16.vk")).unwrap(), vks[0]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_imported_keys_are_stored_and_pinned() {
+        let dir = temp_dir("import");
+        let registry = KeyRegistry::with_params_dir(&dir);
+        let circuit = NetworkMetricCircuit::new(1, 2, 2, "Test".to_string())
+            .with_proof_system(ProofSystemKind::Mock);
+        let other = NetworkMetricCircuit::new(1, 4, 2, "Other".to_string())
+            .with_proof_system(ProofSystemKind::Mock);
+        let setup = |circuit: &NetworkMetricCircuit| {
+            registry
+                .backend(ProofSystemKind::Mock)
+                .unwrap()
+                .setup(CircuitInstance::from_circuit(circuit, None).unwrap())
+                .unwrap()
+        };
+
+        let keys = setup(&circuit);
+        let entry = registry.import_keys(1, keys.clone()).unwrap();
+        assert_eq!(entry.verification_key, keys.verification_key);
+        assert!(registry.import_keys(1, keys.clone()).is_ok());
+        assert!(registry.import_keys(1, setup(&other)).is_err());
+
+        // 新的注册表从参数目录加载导入的密钥，并同样拒绝冲突的密钥
+        let reloaded = KeyRegistry::with_params_dir(&dir);
+        assert_eq!(reloaded.get_or_generate(&circuit).unwrap().verification_key, keys.verification_key);
+        assert!(KeyRegistry::with_params_dir(&dir).import_keys(1, setup(&other)).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
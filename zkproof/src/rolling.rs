@@ -0,0 +1,306 @@
+// 滚动聚合模块 - 跨时间窗口增量更新的滚动平均值
+//
+// 滚动状态记录最近 W 个时间窗口各自的样本和与样本数，以及下一个时间窗口的起点。
+// 状态只以隐藏承诺公开：S = H(...H(H(H(r, tag), hours + next_start · 2^8), w_1)..., w_W)，
+// 其中 w_i = sum_i + count_i · 2^80（最旧的窗口在前），r 为每次更新重新选取的盲化因子。
+// 每个窗口的滚动聚合证明只处理该窗口的新样本：由上一状态承诺出发移出最旧的窗口、并入新窗口，
+// 公开新的状态承诺与最近 W 个窗口的整数平均值，证明大小与已覆盖的历史长度无关。
+// Groth16 无法在 BLS12-381 上高效地递归验证上一个证明，因此证明之间的链接由验证方完成：
+// 验证方只保存最近接受的 [`RollingCheckpoint`]，新证明的上一状态承诺必须等于检查点中的承诺。
+// 这只是各窗口证明经状态承诺串成的哈希链，不是增量可验证计算（IVC）：不存在单个常数大小的证明
+// 能证明整个历史，单独一个证明只说明"从某个状态承诺出发"。历史的可信性完全来自验证方（如合约）
+// 自己保存并逐个推进的检查点，检查点是信任锚，绝不能取自证明者。
+// 初始状态的盲化因子为零且各窗口为空，任何人都可以由指标标签、窗口时长与起点重算其承诺。
+use bellman::{ConstraintSystem, SynthesisError};
+use bls12_381::Scalar;
+use serde::{Deserialize, Serialize};
+
+use crate::circuits::{COUNT_BITS, SAMPLE_BITS};
+use crate::commitment;
+use crate::gadgets::{self, LinearExpr};
+use crate::poseidon;
+
+/// 窗口样本数在打包值中的偏移（位于窗口样本和之上）
+pub const WINDOW_COUNT_SHIFT: usize = SAMPLE_BITS + COUNT_BITS;
+/// 状态头中下一窗口起点的偏移（位于窗口小时数之上）
+const HEADER_START_SHIFT: usize = 8;
+/// 每小时的秒数
+const SECONDS_PER_HOUR: u64 = 3600;
+
+/// 一个时间窗口的样本和与样本数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowTotal {
+    /// 窗口内样本之和
+    pub sum: u128,
+    /// 窗口内样本数量，零表示尚未覆盖的窗口
+    pub count: u32,
+}
+
+/// 滚动聚合的私有状态，由提交者在时间窗口之间保存
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollingState {
+    /// 指标类型标签，见 [`crate::nullifier::metric_tag`]
+    pub metric_tag: u128,
+    /// 每个时间窗口的小时数
+    pub window_hours: u8,
+    /// 下一个时间窗口的起点（Unix 时间戳）
+    pub next_window_start: u64,
+    /// 最近 W 个时间窗口（最旧的在前）
+    pub windows: Vec<WindowTotal>,
+    /// 状态承诺的盲化因子
+    pub blinding: [u8; 32],
+}
+
+impl RollingState {
+    /// 初始状态：window_count 个空窗口，盲化因子为零，承诺可由公开参数重算
+    pub fn genesis(metric_tag: u128, window_hours: u8, window_start: u64, window_count: usize) -> Self {
+        Self {
+            metric_tag,
+            window_hours,
+            next_window_start: window_start,
+            windows: vec![WindowTotal::default(); window_count],
+            blinding: [0; 32],
+        }
+    }
+
+    /// 并入下一个时间窗口的样本并移出最旧的窗口，新状态使用给定的盲化因子
+    pub fn advance(&self, samples: &[u128], blinding: [u8; 32]) -> Self {
+        let mut windows = self.windows.iter().skip(1).copied().collect::<Vec<_>>();
+        windows.push(WindowTotal {
+            sum: samples.iter().fold(0u128, |sum, sample| sum.saturating_add(*sample)),
+            count: samples.len() as u32,
+        });
+        Self {
+            metric_tag: self.metric_tag,
+            window_hours: self.window_hours,
+            next_window_start: self.next_window_start.saturating_add(self.window_length()),
+            windows,
+            blinding,
+        }
+    }
+
+    /// 每个时间窗口的秒数
+    pub fn window_length(&self) -> u64 {
+        self.window_hours as u64 * SECONDS_PER_HOUR
+    }
+
+    /// 状态承诺
+    pub fn commitment(&self) -> Scalar {
+        state_commitment(
+            commitment::blinding_scalar(&self.blinding),
+            self.metric_tag,
+            self.window_hours,
+            self.next_window_start,
+            &self.windows,
+        )
+    }
+
+    /// 已覆盖（样本数非零）的窗口数量
+    pub fn covered_windows(&self) -> usize {
+        self.windows.iter().filter(|window| window.count > 0).count()
+    }
+
+    /// 最近 W 个窗口的样本总数
+    pub fn sample_count(&self) -> u128 {
+        self.windows.iter().map(|window| window.count as u128).sum()
+    }
+
+    /// 最近 W 个窗口的样本和
+    pub fn sample_sum(&self) -> u128 {
+        self.windows.iter().fold(0u128, |sum, window| sum.saturating_add(window.sum))
+    }
+
+    /// 最近 W 个窗口全部样本的整数平均值，尚无样本时为零
+    pub fn average(&self) -> u128 {
+        self.sample_sum().checked_div(self.sample_count()).unwrap_or(0)
+    }
+
+    /// 验证方可以保存的公开检查点
+    pub fn checkpoint(&self) -> RollingCheckpoint {
+        RollingCheckpoint {
+            metric_tag: self.metric_tag,
+            window_hours: self.window_hours,
+            next_window_start: self.next_window_start,
+            state_commitment: self.commitment().to_bytes(),
+            covered_windows: self.covered_windows() as u32,
+            sample_count: self.sample_count(),
+            rolling_average: self.average(),
+        }
+    }
+}
+
+/// 验证方保存的滚动聚合检查点：最近接受的状态承诺及其公开的滚动平均值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollingCheckpoint {
+    /// 指标类型标签
+    pub metric_tag: u128,
+    /// 每个时间窗口的小时数
+    pub window_hours: u8,
+    /// 下一个证明须覆盖的时间窗口起点
+    pub next_window_start: u64,
+    /// 状态承诺（标量的规范小端序字节表示）
+    pub state_commitment: [u8; 32],
+    /// 滚动平均值覆盖的窗口数量
+    pub covered_windows: u32,
+    /// 滚动平均值覆盖的样本数量
+    pub sample_count: u128,
+    /// 最近 W 个窗口全部样本的整数平均值
+    pub rolling_average: u128,
+}
+
+impl RollingCheckpoint {
+    /// 初始检查点，见 [`RollingState::genesis`]
+    pub fn genesis(metric_tag: u128, window_hours: u8, window_start: u64, window_count: usize) -> Self {
+        RollingState::genesis(metric_tag, window_hours, window_start, window_count).checkpoint()
+    }
+
+    /// 滚动聚合证明的公开输入若紧接本检查点，返回证明之后的检查点
+    ///
+    /// 公开输入的顺序见 [`crate::circuits::RollingWitness::public_inputs`]；
+    /// 指标标签、窗口时长、窗口起点与上一状态承诺都必须与本检查点一致。
+    /// 本检查点须是验证方自己保存的状态，由证明者提供的检查点不能证明任何历史。
+    pub fn successor(&self, public_inputs: &[u128]) -> Option<Self> {
+        let [tag, start, hours, covered, count, average, p_low, p_high, s_low, s_high, _, _] = *public_inputs else {
+            return None;
+        };
+        let previous = crate::utils::scalar_from_u128_limbs([p_low, p_high])?;
+        if tag != self.metric_tag
+            || start != self.next_window_start as u128
+            || hours != self.window_hours as u128
+            || previous.to_bytes() != self.state_commitment
+        {
+            return None;
+        }
+
+        let window_length = self.window_hours as u64 * SECONDS_PER_HOUR;
+        Some(Self {
+            next_window_start: self.next_window_start.checked_add(window_length)?,
+            state_commitment: crate::utils::scalar_from_u128_limbs([s_low, s_high])?.to_bytes(),
+            covered_windows: u32::try_from(covered).ok()?,
+            sample_count: count,
+            rolling_average: average,
+            ..*self
+        })
+    }
+}
+
+/// 窗口的打包值 sum + count · 2^80
+fn pack_window(window: &WindowTotal) -> Scalar {
+    gadgets::scalar_from_u128(window.sum)
+        + Scalar::from(window.count as u64) * gadgets::scalar_from_u128(1 << WINDOW_COUNT_SHIFT)
+}
+
+/// 计算滚动状态承诺
+pub fn state_commitment(
+    blinding: Scalar,
+    metric_tag: u128,
+    window_hours: u8,
+    next_window_start: u64,
+    windows: &[WindowTotal],
+) -> Scalar {
+    let header = Scalar::from(window_hours as u64)
+        + Scalar::from(next_window_start) * Scalar::from(1u64 << HEADER_START_SHIFT);
+    let state = poseidon::hash2(blinding, gadgets::scalar_from_u128(metric_tag));
+    let state = poseidon::hash2(state, header);
+    windows.iter().fold(state, |state, window| poseidon::hash2(state, pack_window(window)))
+}
+
+/// 电路内计算滚动状态承诺，与 [`state_commitment`] 的计算结果一致
+///
+/// windows 为 (样本和, 样本数)，须已分别被约束在 [0, 2^80) 与 [0, 2^16) 内，打包值因此唯一。
+pub fn state_commitment_gadget<CS: ConstraintSystem<Scalar>>(
+    mut cs: CS,
+    blinding: &LinearExpr,
+    metric_tag: &LinearExpr,
+    window_hours: &LinearExpr,
+    next_window_start: &LinearExpr,
+    windows: &[(LinearExpr, LinearExpr)],
+) -> Result<LinearExpr, SynthesisError> {
+    let header = window_hours.add(&next_window_start.scale(Scalar::from(1u64 << HEADER_START_SHIFT)));
+    let state = poseidon::hash2_gadget(cs.namespace(|| "tag"), blinding, metric_tag)?;
+    let mut state = poseidon::hash2_gadget(cs.namespace(|| "header"), &state, &header)?;
+    for (i, (sum, count)) in windows.iter().enumerate() {
+        let packed = sum.add(&count.scale(gadgets::scalar_from_u128(1 << WINDOW_COUNT_SHIFT)));
+        state = poseidon::hash2_gadget(cs.namespace(|| format!("window {}", i)), &state, &packed)?;
+    }
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman::gadgets::test::TestConstraintSystem;
+
+    #[test]
+    fn test_rolling_state_updates() {
+        let tag = crate::nullifier::metric_tag("block_time");
+        let genesis = RollingState::genesis(tag, 24, 1_700_000_000, 3);
+        assert_eq!(genesis.average(), 0);
+        assert_eq!(genesis.covered_windows(), 0);
+
+        // 第四个窗口移出第一个窗口
+        let state = genesis
+            .advance(&[10, 20], [1; 32])
+            .advance(&[30], [2; 32])
+            .advance(&[40, 50, 60], [3; 32]);
+        assert_eq!(state.next_window_start, 1_700_000_000 + 3 * 86_400);
+        assert_eq!((state.sample_count(), state.average()), (6, 35));
+        let state = state.advance(&[70], [4; 32]);
+        assert_eq!(state.windows[0], WindowTotal { sum: 30, count: 1 });
+        assert_eq!((state.sample_count(), state.average(), state.covered_windows()), (5, 50, 3));
+
+        // 盲化因子隐藏窗口取值，不同的历史得到不同的承诺
+        assert_ne!(state.commitment(), RollingState { blinding: [5; 32], ..state.clone() }.commitment());
+        let mut other = state.clone();
+        other.windows[0].sum += 1;
+        assert_ne!(state.commitment(), other.commitment());
+        // 打包值只在取值范围内唯一，电路因此约束样本和小于 2^80
+        other.windows[0] = WindowTotal { sum: 30 + (1 << WINDOW_COUNT_SHIFT), count: 0 };
+        assert_eq!(state.commitment(), other.commitment());
+
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        let mut alloc = |name: &str, value: Scalar| {
+            gadgets::alloc_witness(cs.namespace(|| name.to_string()), Some(value)).unwrap()
+        };
+        let blinding = alloc("blinding", commitment::blinding_scalar(&state.blinding));
+        let tag_var = alloc("tag", gadgets::scalar_from_u128(tag));
+        let hours = alloc("hours", Scalar::from(24u64));
+        let start = alloc("start", Scalar::from(state.next_window_start));
+        let windows: Vec<_> = state
+            .windows
+            .iter()
+            .enumerate()
+            .map(|(i, w)| {
+                (
+                    alloc(&format!("sum {}", i), gadgets::scalar_from_u128(w.sum)),
+                    alloc(&format!("count {}", i), Scalar::from(w.count as u64)),
+                )
+            })
+            .collect();
+        let out = state_commitment_gadget(cs.namespace(|| "state"), &blinding, &tag_var, &hours, &start, &windows)
+            .unwrap();
+        assert!(cs.is_satisfied());
+        assert_eq!(out.value(), Some(state.commitment()));
+    }
+
+    #[test]
+    fn test_checkpoint_successor() {
+        let tag = crate::nullifier::metric_tag("block_time");
+        let genesis = RollingState::genesis(tag, 24, 1_700_000_000, 3);
+        let checkpoint = genesis.checkpoint();
+        assert_eq!(checkpoint, RollingCheckpoint::genesis(tag, 24, 1_700_000_000, 3));
+
+        let next = genesis.advance(&[10, 20], [1; 32]);
+        let [p_low, p_high] = crate::utils::scalar_to_u128_limbs(&genesis.commitment());
+        let [s_low, s_high] = crate::utils::scalar_to_u128_limbs(&next.commitment());
+        let inputs = [tag, 1_700_000_000, 24, 1, 2, 15, p_low, p_high, s_low, s_high, 0, 0];
+        assert_eq!(checkpoint.successor(&inputs), Some(next.checkpoint()));
+
+        // 窗口起点、上一状态或输入数量不一致
+        let mut skipped = inputs;
+        skipped[1] += 86_400;
+        assert_eq!(checkpoint.successor(&skipped), None);
+        assert_eq!(next.checkpoint().successor(&inputs), None);
+        assert_eq!(checkpoint.successor(&inputs[..11]), None);
+    }
+}
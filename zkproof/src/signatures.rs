@@ -0,0 +1,163 @@
+// 签名模块 - 数据源公钥与签名验证
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::{DataSource, ZKProofError};
+
+/// sr25519 签名上下文，与 Substrate 密钥工具保持一致
+pub const SR25519_SIGNING_CONTEXT: &[u8] = b"substrate";
+
+/// 数据源公钥
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SourcePublicKey {
+    /// Ed25519 公钥
+    Ed25519([u8; 32]),
+    /// Sr25519（Schnorrkel/Ristretto）公钥
+    Sr25519([u8; 32]),
+}
+
+impl SourcePublicKey {
+    /// 验证签名，公钥或签名编码无效时返回 false
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            SourcePublicKey::Ed25519(key) => {
+                let key = match ed25519_dalek::VerifyingKey::from_bytes(key) {
+                    Ok(key) => key,
+                    Err(_) => return false,
+                };
+                let signature = match ed25519_dalek::Signature::from_slice(signature) {
+                    Ok(signature) => signature,
+                    Err(_) => return false,
+                };
+                key.verify_strict(message, &signature).is_ok()
+            }
+            SourcePublicKey::Sr25519(key) => {
+                let key = match schnorrkel::PublicKey::from_bytes(key) {
+                    Ok(key) => key,
+                    Err(_) => return false,
+                };
+                let signature = match schnorrkel::Signature::from_bytes(signature) {
+                    Ok(signature) => signature,
+                    Err(_) => return false,
+                };
+                key.verify_simple(SR25519_SIGNING_CONTEXT, message, &signature).is_ok()
+            }
+        }
+    }
+}
+
+/// 数据源对数据哈希的签名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSignature {
+    /// 签名的数据源标识符
+    pub source_id: String,
+    /// 签名字节
+    pub signature: Vec<u8>,
+}
+
+/// 验证数据源签名并返回签名者列表
+///
+/// 每个签名者必须是已登记的数据源，且只能出现一次；任一签名无效时整体拒绝。
+/// 有效签名者数量不足 threshold 时返回错误。
+pub fn verify_signers(
+    known_sources: &HashMap<String, DataSource>,
+    message: &[u8],
+    signatures: &[SourceSignature],
+    threshold: usize,
+) -> Result<Vec<String>> {
+    let mut signers = HashSet::new();
+
+    for signature in signatures {
+        let source = known_sources.get(&signature.source_id).ok_or_else(|| {
+            ZKProofError::InvalidInputData(format!("unknown signer: {}", signature.source_id))
+        })?;
+
+        if !signers.insert(signature.source_id.clone()) {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "duplicate signer: {}",
+                signature.source_id
+            ))
+            .into());
+        }
+
+        if !source.public_key.verify(message, &signature.signature) {
+            return Err(ZKProofError::InvalidInputData(format!(
+                "invalid signature from {}",
+                signature.source_id
+            ))
+            .into());
+        }
+    }
+
+    if signers.len() < threshold {
+        return Err(ZKProofError::InvalidInputData(format!(
+            "{} distinct signers, at least {} required",
+            signers.len(),
+            threshold
+        ))
+        .into());
+    }
+
+    let mut signers: Vec<String> = signers.into_iter().collect();
+    signers.sort();
+    Ok(signers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataSourceType;
+    use ed25519_dalek::Signer;
+
+    fn ed25519_source(id: &str, seed: u8) -> (DataSource, ed25519_dalek::SigningKey) {
+        let key = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]);
+        let source = DataSource {
+            source_type: DataSourceType::ValidatorNode,
+            source_id: id.to_string(),
+            timestamp: 0,
+            reliability_score: 90,
+            public_key: SourcePublicKey::Ed25519(key.verifying_key().to_bytes()),
+        };
+        (source, key)
+    }
+
+    #[test]
+    fn test_sr25519_signature() {
+        let keypair = schnorrkel::MiniSecretKey::from_bytes(&[3; 32])
+            .unwrap()
+            .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519);
+        let public_key = SourcePublicKey::Sr25519(keypair.public.to_bytes());
+        let signature = keypair.sign_simple(SR25519_SIGNING_CONTEXT, b"data hash").to_bytes();
+
+        assert!(public_key.verify(b"data hash", &signature));
+        assert!(!public_key.verify(b"other hash", &signature));
+        assert!(!public_key.verify(b"data hash", &signature[..32]));
+    }
+
+    #[test]
+    fn test_verify_signers() {
+        let (a, key_a) = ed25519_source("a", 1);
+        let (b, key_b) = ed25519_source("b", 2);
+        let known: HashMap<_, _> = [a, b].into_iter().map(|s| (s.source_id.clone(), s)).collect();
+
+        let message = b"data hash";
+        let sign = |id: &str, key: &ed25519_dalek::SigningKey| SourceSignature {
+            source_id: id.to_string(),
+            signature: key.sign(message).to_bytes().to_vec(),
+        };
+
+        let signers = verify_signers(&known, message, &[sign("b", &key_b), sign("a", &key_a)], 2).unwrap();
+        assert_eq!(signers, vec!["a".to_string(), "b".to_string()]);
+
+        // 签名者不足阈值
+        assert!(verify_signers(&known, message, &[sign("a", &key_a)], 2).is_err());
+        // 重复签名者
+        assert!(verify_signers(&known, message, &[sign("a", &key_a), sign("a", &key_a)], 2).is_err());
+        // 未登记的签名者
+        let (_, key_c) = ed25519_source("c", 3);
+        assert!(verify_signers(&known, message, &[sign("a", &key_a), sign("c", &key_c)], 2).is_err());
+        // 以他人密钥冒充
+        assert!(verify_signers(&known, message, &[sign("a", &key_a), sign("b", &key_a)], 2).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+// 统计模块 - 证明任务计数与生成耗时直方图
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::pool::ProofStage;
+
+/// 直方图桶的上界（毫秒），最后一个桶收集超出所有上界的样本
+const BUCKET_BOUNDS_MS: [u64; 15] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 30_000, 60_000];
+
+/// 耗时直方图
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    /// 各桶的样本数，比上界多一个溢出桶
+    counts: Vec<u64>,
+    /// 样本总数
+    count: u64,
+    /// 耗时总和
+    total: Duration,
+    /// 最大耗时
+    max: Duration,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            counts: vec![0; BUCKET_BOUNDS_MS.len() + 1],
+            count: 0,
+            total: Duration::ZERO,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// 记录一个样本
+    pub fn record(&mut self, duration: Duration) {
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| duration <= Duration::from_millis(*bound))
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.counts[bucket] += 1;
+        self.count += 1;
+        self.total += duration;
+        self.max = self.max.max(duration);
+    }
+
+    /// 样本总数
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// 平均耗时，没有样本时为 0
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.count) {
+            Ok(0) => Duration::ZERO,
+            Ok(count) => self.total / count,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.count as f64),
+        }
+    }
+
+    /// 最大耗时
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// 分位数 q（0.0-1.0）的上界估计：返回该分位样本所在桶的上界，不超过最大耗时
+    pub fn quantile(&self, q: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return BUCKET_BOUNDS_MS
+                    .get(bucket)
+                    .map_or(self.max, |bound| Duration::from_millis(*bound).min(self.max));
+            }
+        }
+        self.max
+    }
+
+    /// 各桶的上界与样本数，溢出桶的上界为 None
+    pub fn buckets(&self) -> impl Iterator<Item = (Option<Duration>, u64)> + '_ {
+        self.counts.iter().enumerate().map(|(bucket, count)| {
+            (BUCKET_BOUNDS_MS.get(bucket).map(|bound| Duration::from_millis(*bound)), *count)
+        })
+    }
+}
+
+/// 证明任务统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvingStats {
+    /// 成功生成的证明数
+    pub completed: u64,
+    /// 生成失败的任务数
+    pub failed: u64,
+    /// 被取消的任务数
+    pub cancelled: u64,
+    /// 超时的任务数
+    pub timed_out: u64,
+    /// 成功任务的生成耗时（从开始加载参数到证明完成，不含排队时间）
+    pub generation_times: LatencyHistogram,
+}
+
+impl ProvingStats {
+    /// 记录已结束的任务，duration 为成功任务的生成耗时
+    pub fn record(&mut self, stage: ProofStage, duration: Option<Duration>) {
+        match stage {
+            ProofStage::Completed => {
+                self.completed += 1;
+                if let Some(duration) = duration {
+                    self.generation_times.record(duration);
+                }
+            }
+            ProofStage::Cancelled => self.cancelled += 1,
+            ProofStage::TimedOut => self.timed_out += 1,
+            _ => self.failed += 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.mean(), Duration::ZERO);
+        assert_eq!(histogram.quantile(0.5), Duration::ZERO);
+
+        for ms in [3, 4, 40, 45, 90_000] {
+            histogram.record(Duration::from_millis(ms));
+        }
+        assert_eq!(histogram.count(), 5);
+        assert_eq!(histogram.mean(), Duration::from_micros(18_018_400));
+        assert_eq!(histogram.quantile(0.4), Duration::from_millis(5));
+        assert_eq!(histogram.quantile(0.6), Duration::from_millis(50));
+        assert_eq!(histogram.quantile(1.0), Duration::from_millis(90_000));
+        assert_eq!(histogram.buckets().map(|(_, count)| count).sum::<u64>(), 5);
+        assert_eq!(histogram.buckets().last(), Some((None, 1)));
+
+        // 桶上界不超过实际最大值
+        let mut single = LatencyHistogram::default();
+        single.record(Duration::from_millis(7));
+        assert_eq!(single.quantile(0.5), Duration::from_millis(7));
+    }
+}
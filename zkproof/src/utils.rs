@@ -20,7 +20,7 @@ pub fn serialize_public_inputs(inputs: &[u128]) -> Vec<u8> {
 
 /// 反序列化公共输入
 pub fn deserialize_public_inputs(data: &[u8]) -> Result<Vec<u128>> {
-    if data.len() % 16 != 0 {
+    if !data.len().is_multiple_of(16) {
         return Err(anyhow::anyhow!("Invalid public inputs data length"));
     }
 
@@ -34,6 +34,61 @@ pub fn deserialize_public_inputs(data: &[u8]) -> Result<Vec<u128>> {
     Ok(inputs)
 }
 
+/// 将公开输入转换为 BLS12-381 标量域元素
+pub fn public_inputs_to_scalars(inputs: &[u128]) -> Vec<bls12_381::Scalar> {
+    inputs
+        .iter()
+        .map(|input| crate::gadgets::scalar_from_u128(*input))
+        .collect()
+}
+
+/// 将标量拆分为 (低 128 位, 高位) 两个 u128，用于以 u128 公开输入表示完整的域元素
+pub fn scalar_to_u128_limbs(value: &bls12_381::Scalar) -> [u128; 2] {
+    use ff::PrimeField;
+
+    let repr = value.to_repr();
+    let mut low = [0u8; 16];
+    let mut high = [0u8; 16];
+    low.copy_from_slice(&repr[..16]);
+    high.copy_from_slice(&repr[16..]);
+    [u128::from_le_bytes(low), u128::from_le_bytes(high)]
+}
+
+/// 由 (低 128 位, 高位) 两个 u128 还原标量，非规范编码时返回 None
+pub fn scalar_from_u128_limbs(limbs: [u128; 2]) -> Option<bls12_381::Scalar> {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&limbs[0].to_le_bytes());
+    bytes[16..].copy_from_slice(&limbs[1].to_le_bytes());
+    Option::from(bls12_381::Scalar::from_bytes(&bytes))
+}
+
+/// 公开输入摘要使用的域分隔串
+const PUBLIC_INPUT_DIGEST_TAG: &[u8] = b"polyvisor/public-input-digest";
+
+/// 公开输入摘要的初始状态：H(域分隔常量, 输入个数)
+pub fn public_input_digest_header(input_count: usize) -> bls12_381::Scalar {
+    let mut wide = [0u8; 64];
+    wide[..32].copy_from_slice(&Sha256::digest(PUBLIC_INPUT_DIGEST_TAG));
+    let tag = bls12_381::Scalar::from_bytes_wide(&wide);
+    crate::poseidon::hash2(tag, bls12_381::Scalar::from(input_count as u64))
+}
+
+/// 公开输入的 Poseidon 摘要（域元素）：依次吸收每个输入，输入个数绑定在初始状态中
+pub fn public_input_digest_scalar(inputs: &[u128]) -> bls12_381::Scalar {
+    inputs
+        .iter()
+        .fold(public_input_digest_header(inputs.len()), |state, input| {
+            crate::poseidon::hash2(state, crate::gadgets::scalar_from_u128(*input))
+        })
+}
+
+/// 公开输入摘要的 32 字节规范编码（小端），摘要模式下链上只需保存该值
+pub fn public_input_digest(inputs: &[u128]) -> [u8; 32] {
+    use ff::PrimeField;
+
+    public_input_digest_scalar(inputs).to_repr()
+}
+
 /// 验证数据完整性
 pub fn verify_data_integrity(data: &[u8], expected_hash: &[u8; 32]) -> bool {
     let actual_hash = hash_data(data);
@@ -116,6 +171,19 @@ mod tests {
         assert_eq!(inputs, deserialized);
     }
 
+    #[test]
+    fn test_public_input_digest() {
+        let inputs = [6050u128, 92, 1];
+        let digest = public_input_digest(&inputs);
+        assert_eq!(zkproof_verifier::digest_scalar(&digest), Some(public_input_digest_scalar(&inputs)));
+
+        // 顺序、取值与个数（包括末尾补零）都会改变摘要
+        assert_ne!(public_input_digest(&[92, 6050, 1]), digest);
+        assert_ne!(public_input_digest(&[6050, 92, 2]), digest);
+        assert_ne!(public_input_digest(&[6050, 92, 1, 0]), digest);
+        assert_ne!(public_input_digest(&[]), public_input_digest(&[0]));
+    }
+
     #[test]
     fn test_quality_score_calculation() {
         let score = calculate_quality_score(90, 85, 95);
@@ -1,43 +1,139 @@
-// 验证器模块 - 简化实现
+// 验证器模块 - no_std 验证核心 zkproof-verifier 的标准库封装
+//
+// 配对检查与封装解码由验证核心完成，这里负责按电路从密钥注册表查找验证密钥并缓存验证结果。
 use anyhow::Result;
-use crate::circuits::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::circuits::{NetworkMetricCircuit, PublicInputMode};
+use crate::envelope::ProofEnvelope;
+use crate::proof_system::Statement;
+use crate::registry::{KeyRegistry, VerificationKeyEntry};
+use crate::ZKProof;
 
 /// 零知识证明验证器
 pub struct ZKVerifier {
+    /// 密钥注册表
+    registry: Arc<KeyRegistry>,
     /// 验证缓存
-    verification_cache: std::collections::HashMap<String, bool>,
+    verification_cache: HashMap<String, bool>,
 }
 
 impl ZKVerifier {
     /// 创建新的验证器
-    pub fn new() -> Result<Self> {
+    pub fn new(registry: Arc<KeyRegistry>) -> Result<Self> {
         Ok(Self {
-            verification_cache: std::collections::HashMap::new(),
+            registry,
+            verification_cache: HashMap::new(),
         })
     }
 
     /// 验证证明
-    pub fn verify_proof(
-        &mut self,
-        circuit_type: CircuitType,
-        proof: &[u8],
-        public_inputs: &[u128],
-        verification_key: &[u8],
-    ) -> Result<bool> {
+    ///
+    /// 后端由电路配置决定，验证密钥按电路ID从注册表中查找，证明中携带的验证密钥不参与验证。
+    /// 摘要模式的电路由证明中的公开输入重算摘要后验证。电路尚未注册验证密钥时证明视为无效。
+    pub async fn verify_proof(&mut self, circuit: &NetworkMetricCircuit, proof: &ZKProof) -> Result<bool> {
+        if proof.circuit_id != circuit.circuit_id {
+            return Ok(false);
+        }
+
+        let vk = match self.registry.verification_key(circuit.circuit_id, circuit.proof_system)? {
+            Some(vk) => vk,
+            None => return Ok(false),
+        };
+
+        self.verify_with_key(&vk, &proof.proof_value, &circuit.statement(&proof.public_inputs))
+    }
+
+    /// 验证证明封装
+    ///
+    /// 封装声明的后端必须与电路配置一致，验证密钥哈希必须与注册表中的验证密钥一致，
+    /// 与链上使用 zkproof_verifier::verify_envelope 的检查相同。摘要模式的电路要求封装只携带公开输入摘要，
+    /// 其他电路要求封装携带全部公开输入。
+    pub async fn verify_envelope(&mut self, circuit: &NetworkMetricCircuit, envelope: &ProofEnvelope) -> Result<bool> {
+        if envelope.circuit_id != circuit.circuit_id || envelope.backend != circuit.proof_system {
+            return Ok(false);
+        }
+        let statement = envelope.statement();
+        if matches!(statement, Statement::Digest(_)) != (circuit.public_input_mode == PublicInputMode::Digest) {
+            return Ok(false);
+        }
+
+        let vk = match self.registry.verification_key(circuit.circuit_id, circuit.proof_system)? {
+            Some(vk) if vk.vk_hash == envelope.vk_hash => vk,
+            _ => return Ok(false),
+        };
+
+        self.verify_with_key(&vk, &envelope.proof, &statement)
+    }
+
+    /// 批量验证同一电路的多个证明，按输入顺序返回每个证明的结果
+    ///
+    /// 未命中缓存的证明共享同一验证密钥，交给后端一次性批量验证；摘要模式的证明逐个验证。
+    pub async fn batch_verify(&mut self, circuit: &NetworkMetricCircuit, proofs: &[&ZKProof]) -> Result<Vec<bool>> {
+        let vk = match self.registry.verification_key(circuit.circuit_id, circuit.proof_system)? {
+            Some(vk) => vk,
+            None => return Ok(vec![false; proofs.len()]),
+        };
+
+        let mut results = vec![false; proofs.len()];
+        let mut pending = Vec::new();
+        for (i, proof) in proofs.iter().enumerate() {
+            if proof.circuit_id != circuit.circuit_id {
+                continue;
+            }
+            let statement = circuit.statement(&proof.public_inputs);
+            let cache_key = self.generate_cache_key(&vk, &proof.proof_value, &statement);
+            match self.verification_cache.get(&cache_key) {
+                Some(&cached_result) => results[i] = cached_result,
+                None => pending.push((i, cache_key, statement)),
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(results);
+        }
+
+        // 执行批量验证
+        let backend = self.registry.backend(vk.system)?;
+        let batch: Option<Vec<(&[u8], &[u128])>> = pending
+            .iter()
+            .map(|(i, _, statement)| match statement {
+                Statement::Inputs(inputs) => Some((proofs[*i].proof_value.as_slice(), inputs.as_slice())),
+                Statement::Digest(_) => None,
+            })
+            .collect();
+        let verified = match batch {
+            Some(batch) => backend.batch_verify(&vk.verification_key, &batch)?,
+            None => pending
+                .iter()
+                .map(|(i, _, statement)| backend.verify_statement(&vk.verification_key, &proofs[*i].proof_value, statement))
+                .collect::<Result<Vec<_>>>()?,
+        };
+
+        // 缓存结果
+        for ((i, cache_key, _), is_valid) in pending.into_iter().zip(verified) {
+            results[i] = is_valid;
+            self.verification_cache.insert(cache_key, is_valid);
+        }
+
+        Ok(results)
+    }
+
+    /// 使用指定验证密钥验证证明，结果按 (验证密钥, 证明, 公开语句) 缓存
+    ///
+    /// 单个证明的验证直接交给验证核心；批量验证仍由后端合并配对检查。
+    fn verify_with_key(&mut self, vk: &VerificationKeyEntry, proof: &[u8], statement: &Statement) -> Result<bool> {
         // 生成缓存键
-        let cache_key = self.generate_cache_key(circuit_type, proof, public_inputs);
-        
+        let cache_key = self.generate_cache_key(vk, proof, statement);
+
         // 检查缓存
         if let Some(&cached_result) = self.verification_cache.get(&cache_key) {
             return Ok(cached_result);
         }
 
         // 执行验证
-        let is_valid = match circuit_type {
-            CircuitType::NetworkMetrics => self.verify_metric_proof(proof, public_inputs, verification_key),
-            CircuitType::Privacy => self.verify_privacy_proof(proof, public_inputs, verification_key),
-            CircuitType::Consensus => self.verify_consensus_proof(proof, public_inputs, verification_key),
-        }?;
+        let is_valid = zkproof_verifier::verify_statement(vk.system, &vk.verification_key, proof, statement);
 
         // 缓存结果
         self.verification_cache.insert(cache_key, is_valid);
@@ -45,35 +141,27 @@ impl ZKVerifier {
         Ok(is_valid)
     }
 
-    /// 验证网络指标证明
-    fn verify_metric_proof(&self, proof: &[u8], _public: &[u128], _vk: &[u8]) -> Result<bool> {
-        // 简化验证逻辑
-        Ok(!proof.is_empty() && proof == b"mock_metric_proof")
-    }
-
-    /// 验证隐私证明
-    fn verify_privacy_proof(&self, proof: &[u8], _public: &[u128], _vk: &[u8]) -> Result<bool> {
-        // 简化验证逻辑
-        Ok(!proof.is_empty() && proof == b"mock_privacy_proof")
-    }
-
-    /// 验证共识证明
-    fn verify_consensus_proof(&self, proof: &[u8], _public: &[u128], _vk: &[u8]) -> Result<bool> {
-        // 简化验证逻辑
-        Ok(!proof.is_empty() && proof == b"mock_consensus_proof")
-    }
-
     /// 生成缓存键
-    fn generate_cache_key(&self, circuit_type: CircuitType, proof: &[u8], public_inputs: &[u128]) -> String {
+    fn generate_cache_key(&self, vk: &VerificationKeyEntry, proof: &[u8], statement: &Statement) -> String {
         use sha2::{Sha256, Digest};
-        
+
         let mut hasher = Sha256::new();
-        hasher.update(format!("{:?}", circuit_type).as_bytes());
+        hasher.update(vk.vk_hash);
+        hasher.update(vk.circuit_id.to_be_bytes());
         hasher.update(proof);
-        for input in public_inputs {
-            hasher.update(&input.to_be_bytes());
+        match statement {
+            Statement::Inputs(public_inputs) => {
+                hasher.update([0]);
+                for input in public_inputs {
+                    hasher.update(input.to_be_bytes());
+                }
+            }
+            Statement::Digest(digest) => {
+                hasher.update([1]);
+                hasher.update(digest);
+            }
         }
-        
+
         hex::encode(hasher.finalize())
     }
 
@@ -86,4 +174,4 @@ impl ZKVerifier {
     pub fn get_cache_stats(&self) -> (usize, usize) {
         (self.verification_cache.len(), 1000) // 简化实现，假设最大缓存1000
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,409 @@
+// 证明封装模块 - 版本化、自描述的证明交换格式
+//
+// 二进制格式（SCALE 编码）：
+//   magic "PVZK" (4 字节) | version (u8) | backend (u8) | curve (u8) | circuit_id (u32 LE)
+//   | vk_hash (32 字节) | proof (Compact 长度 + 字节) | public_inputs (Compact 长度 + u128 LE)
+//   | public_input_digest (Option：0，或 1 + 32 字节)
+// JSON 格式字段一一对应（无摘要时省略 public_input_digest）：字节串为小写十六进制，u128 为十进制字符串，
+// 解码时拒绝非规范表示与未知字段。
+use alloc::vec::Vec;
+use parity_scale_codec::{Decode, DecodeAll, Encode, Input, Output};
+
+use crate::{vk_hash, ProofSystemKind, Statement, VerifierError};
+
+/// 封装格式魔数
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"PVZK";
+/// 当前封装格式版本
+pub const ENVELOPE_VERSION: u8 = 1;
+/// 压缩 Groth16 证明的长度（G1 48 字节 + G2 96 字节 + G1 48 字节）
+pub const GROTH16_PROOF_LEN: usize = 192;
+/// 模拟后端证明的长度
+pub const MOCK_PROOF_LEN: usize = 32;
+
+/// 证明所在的椭圆曲线
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Curve {
+    /// BLS12-381
+    Bls12_381,
+}
+
+impl Curve {
+    /// 曲线编号
+    pub fn id(&self) -> u8 {
+        match self {
+            Curve::Bls12_381 => 1,
+        }
+    }
+
+    /// 由曲线编号还原曲线
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Curve::Bls12_381),
+            _ => None,
+        }
+    }
+
+    /// 曲线名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Curve::Bls12_381 => "bls12-381",
+        }
+    }
+}
+
+/// 版本化的证明封装
+///
+/// 只携带验证密钥的哈希，验证方按电路ID从注册表查找验证密钥并核对哈希。
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "json::JsonEnvelope", into = "json::JsonEnvelope")
+)]
+pub struct ProofEnvelope {
+    /// 封装格式版本
+    pub version: u8,
+    /// 证明系统后端
+    pub backend: ProofSystemKind,
+    /// 椭圆曲线
+    pub curve: Curve,
+    /// 电路标识符
+    pub circuit_id: u32,
+    /// 验证密钥的 SHA-256 哈希
+    pub vk_hash: [u8; 32],
+    /// 压缩的证明点
+    pub proof: Vec<u8>,
+    /// 公开输入，摘要模式下为空
+    pub public_inputs: Vec<u128>,
+    /// 摘要模式电路唯一的公开输入：公开输入摘要（标量的小端字节）
+    pub public_input_digest: Option<[u8; 32]>,
+}
+
+impl ProofEnvelope {
+    /// 由验证密钥、证明与公开输入构造当前版本的封装
+    pub fn new(
+        backend: ProofSystemKind,
+        circuit_id: u32,
+        verification_key: &[u8],
+        proof: Vec<u8>,
+        public_inputs: Vec<u128>,
+    ) -> Result<Self, VerifierError> {
+        let envelope = Self {
+            version: ENVELOPE_VERSION,
+            backend,
+            curve: Curve::Bls12_381,
+            circuit_id,
+            vk_hash: vk_hash(verification_key),
+            proof,
+            public_inputs,
+            public_input_digest: None,
+        };
+        envelope.validate().map_err(VerifierError::InvalidEnvelope)?;
+        Ok(envelope)
+    }
+
+    /// 构造以公开输入摘要作为唯一公开输入的封装
+    pub fn with_digest(
+        backend: ProofSystemKind,
+        circuit_id: u32,
+        verification_key: &[u8],
+        proof: Vec<u8>,
+        digest: [u8; 32],
+    ) -> Result<Self, VerifierError> {
+        let mut envelope = Self::new(backend, circuit_id, verification_key, proof, Vec::new())?;
+        envelope.public_input_digest = Some(digest);
+        Ok(envelope)
+    }
+
+    /// 后端验证的公开语句
+    pub fn statement(&self) -> Statement {
+        match self.public_input_digest {
+            Some(digest) => Statement::Digest(digest),
+            None => Statement::Inputs(self.public_inputs.clone()),
+        }
+    }
+
+    /// SCALE 编码
+    pub fn to_scale(&self) -> Vec<u8> {
+        self.encode()
+    }
+
+    /// SCALE 解码，拒绝末尾多余的字节
+    pub fn from_scale(bytes: &[u8]) -> Result<Self, VerifierError> {
+        // parity-scale-codec 在 no_std 下不保留错误描述，统一报告为格式错误
+        Self::decode_all(&mut &bytes[..]).map_err(|_| VerifierError::InvalidEnvelope("malformed proof envelope"))
+    }
+
+    /// JSON 编码
+    #[cfg(feature = "std")]
+    pub fn to_json(&self) -> Result<alloc::string::String, VerifierError> {
+        serde_json::to_string(self).map_err(|e| VerifierError::Json(e.to_string()))
+    }
+
+    /// JSON 解码
+    #[cfg(feature = "std")]
+    pub fn from_json(json: &str) -> Result<Self, VerifierError> {
+        serde_json::from_str(json).map_err(|e| VerifierError::Json(e.to_string()))
+    }
+
+    /// 检查版本、证明长度，以及摘要与公开输入不同时出现
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.version != ENVELOPE_VERSION {
+            return Err("unsupported proof envelope version");
+        }
+        if self.public_input_digest.is_some() && !self.public_inputs.is_empty() {
+            return Err("digest envelopes carry no other public inputs");
+        }
+        let expected_len = match self.backend {
+            ProofSystemKind::Groth16 => GROTH16_PROOF_LEN,
+            ProofSystemKind::Mock => MOCK_PROOF_LEN,
+        };
+        if self.proof.len() != expected_len {
+            return Err("proof length does not match the backend");
+        }
+        Ok(())
+    }
+}
+
+impl Encode for ProofEnvelope {
+    fn size_hint(&self) -> usize {
+        4 + 3 + 4 + 32 + self.proof.size_hint() + self.public_inputs.size_hint() + self.public_input_digest.size_hint()
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        dest.write(&ENVELOPE_MAGIC);
+        self.version.encode_to(dest);
+        self.backend.id().encode_to(dest);
+        self.curve.id().encode_to(dest);
+        self.circuit_id.encode_to(dest);
+        self.vk_hash.encode_to(dest);
+        self.proof.encode_to(dest);
+        self.public_inputs.encode_to(dest);
+        self.public_input_digest.encode_to(dest);
+    }
+}
+
+impl Decode for ProofEnvelope {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, parity_scale_codec::Error> {
+        let mut magic = [0u8; 4];
+        input.read(&mut magic)?;
+        if magic != ENVELOPE_MAGIC {
+            return Err("invalid proof envelope magic".into());
+        }
+
+        let version = u8::decode(input)?;
+        if version != ENVELOPE_VERSION {
+            return Err("unsupported proof envelope version".into());
+        }
+        let backend = ProofSystemKind::from_id(u8::decode(input)?).ok_or("unknown proof system")?;
+        let curve = Curve::from_id(u8::decode(input)?).ok_or("unknown curve")?;
+
+        let envelope = Self {
+            version,
+            backend,
+            curve,
+            circuit_id: u32::decode(input)?,
+            vk_hash: <[u8; 32]>::decode(input)?,
+            proof: Vec::<u8>::decode(input)?,
+            public_inputs: Vec::<u128>::decode(input)?,
+            public_input_digest: Option::<[u8; 32]>::decode(input)?,
+        };
+        envelope.validate()?;
+        Ok(envelope)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod json {
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use serde::{Deserialize, Serialize};
+
+    use super::{Curve, ProofEnvelope, ENVELOPE_MAGIC};
+    use crate::VerifierError;
+
+    /// 封装的 JSON 表示
+    #[derive(Serialize, Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) struct JsonEnvelope {
+        magic: String,
+        version: u8,
+        backend: String,
+        curve: String,
+        circuit_id: u32,
+        vk_hash: String,
+        proof: String,
+        public_inputs: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        public_input_digest: Option<String>,
+    }
+
+    impl From<ProofEnvelope> for JsonEnvelope {
+        fn from(envelope: ProofEnvelope) -> Self {
+            Self {
+                magic: String::from_utf8_lossy(&ENVELOPE_MAGIC).into_owned(),
+                version: envelope.version,
+                backend: envelope.backend.name().to_string(),
+                curve: envelope.curve.name().to_string(),
+                circuit_id: envelope.circuit_id,
+                vk_hash: hex::encode(envelope.vk_hash),
+                proof: hex::encode(&envelope.proof),
+                public_inputs: envelope.public_inputs.iter().map(|v| v.to_string()).collect(),
+                public_input_digest: envelope.public_input_digest.map(hex::encode),
+            }
+        }
+    }
+
+    impl TryFrom<JsonEnvelope> for ProofEnvelope {
+        type Error = VerifierError;
+
+        fn try_from(json: JsonEnvelope) -> Result<Self, Self::Error> {
+            if json.magic.as_bytes() != ENVELOPE_MAGIC {
+                return Err(VerifierError::InvalidEnvelope("invalid proof envelope magic"));
+            }
+            let curve = match json.curve.as_str() {
+                "bls12-381" => Curve::Bls12_381,
+                _ => return Err(VerifierError::InvalidEnvelope("unknown curve")),
+            };
+            let vk_hash = canonical_hex(&json.vk_hash)?
+                .try_into()
+                .map_err(|_| VerifierError::InvalidEnvelope("verification key hash must be 32 bytes"))?;
+            let public_inputs = json
+                .public_inputs
+                .iter()
+                .map(|value| match value.parse::<u128>() {
+                    Ok(parsed) if parsed.to_string() == *value => Ok(parsed),
+                    _ => Err(VerifierError::InvalidEnvelope("public inputs must be canonical decimal strings")),
+                })
+                .collect::<Result<_, _>>()?;
+            let public_input_digest = json
+                .public_input_digest
+                .map(|digest| {
+                    canonical_hex(&digest)?
+                        .try_into()
+                        .map_err(|_| VerifierError::InvalidEnvelope("public input digest must be 32 bytes"))
+                })
+                .transpose()?;
+
+            let envelope = Self {
+                version: json.version,
+                backend: json.backend.parse()?,
+                curve,
+                circuit_id: json.circuit_id,
+                vk_hash,
+                proof: canonical_hex(&json.proof)?,
+                public_inputs,
+                public_input_digest,
+            };
+            envelope.validate().map_err(VerifierError::InvalidEnvelope)?;
+            Ok(envelope)
+        }
+    }
+
+    /// 解码小写十六进制字符串
+    fn canonical_hex(value: &str) -> Result<Vec<u8>, VerifierError> {
+        match hex::decode(value) {
+            Ok(bytes) if hex::encode(&bytes) == value => Ok(bytes),
+            _ => Err(VerifierError::InvalidEnvelope("byte strings must be lower-case hex")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> ProofEnvelope {
+        ProofEnvelope {
+            version: ENVELOPE_VERSION,
+            backend: ProofSystemKind::Groth16,
+            curve: Curve::Bls12_381,
+            circuit_id: 1,
+            vk_hash: [0xab; 32],
+            proof: (0..GROTH16_PROOF_LEN as u8).collect(),
+            public_inputs: vec![6050, 92, 1, u128::MAX],
+            public_input_digest: None,
+        }
+    }
+
+    fn digest_envelope() -> ProofEnvelope {
+        ProofEnvelope {
+            public_inputs: Vec::new(),
+            public_input_digest: Some([0xcd; 32]),
+            ..envelope()
+        }
+    }
+
+    #[test]
+    fn test_scale_layout_and_round_trip() {
+        let envelope = envelope();
+        let bytes = envelope.to_scale();
+
+        assert_eq!(bytes[..4], ENVELOPE_MAGIC);
+        assert_eq!(bytes[4..7], [ENVELOPE_VERSION, 1, 1]);
+        assert_eq!(bytes[7..11], 1u32.to_le_bytes());
+        assert_eq!(bytes[11..43], [0xab; 32]);
+        assert_eq!(ProofEnvelope::from_scale(&bytes).unwrap(), envelope);
+        assert_eq!(bytes.last(), Some(&0));
+
+        let digest = digest_envelope();
+        let digest_bytes = digest.to_scale();
+        assert_eq!(digest_bytes[digest_bytes.len() - 33..][..2], [1, 0xcd]);
+        assert_eq!(ProofEnvelope::from_scale(&digest_bytes).unwrap(), digest);
+        assert_eq!(digest.statement(), Statement::Digest([0xcd; 32]));
+
+        // 摘要与其他公开输入不能同时出现
+        let mixed = ProofEnvelope {
+            public_input_digest: Some([0xcd; 32]),
+            ..envelope.clone()
+        };
+        assert!(ProofEnvelope::from_scale(&mixed.to_scale()).is_err());
+
+        // 截断、多余字节、错误魔数、未知版本
+        assert!(ProofEnvelope::from_scale(&bytes[..bytes.len() - 1]).is_err());
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(ProofEnvelope::from_scale(&trailing).is_err());
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'X';
+        assert!(ProofEnvelope::from_scale(&bad_magic).is_err());
+        let mut bad_version = bytes;
+        bad_version[4] = 2;
+        assert!(ProofEnvelope::from_scale(&bad_version).is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_json_round_trip_is_canonical() {
+        let envelope = envelope();
+        let json = envelope.to_json().unwrap();
+        assert!(json.contains("\"backend\":\"groth16\""));
+        assert!(json.contains(&format!("\"{}\"", u128::MAX)));
+        assert_eq!(ProofEnvelope::from_json(&json).unwrap(), envelope);
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let reject = |field: &str, replacement: serde_json::Value| {
+            let mut value = value.clone();
+            value[field] = replacement;
+            ProofEnvelope::from_json(&value.to_string()).is_err()
+        };
+        assert!(reject("vk_hash", hex::encode_upper([0xabu8; 32]).into()));
+        assert!(reject("public_inputs", serde_json::json!(["06050"])));
+        assert!(reject("proof", "00".into()));
+        assert!(reject("curve", "bn254".into()));
+        assert!(reject("unexpected", 1.into()));
+        assert!(!json.contains("public_input_digest"));
+
+        let digest = digest_envelope();
+        let json = digest.to_json().unwrap();
+        assert!(json.contains(&format!("\"public_input_digest\":\"{}\"", hex::encode([0xcd; 32]))));
+        assert_eq!(ProofEnvelope::from_json(&json).unwrap(), digest);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let mut short = value.clone();
+        short["public_input_digest"] = hex::encode([0xcd; 31]).into();
+        assert!(ProofEnvelope::from_json(&short.to_string()).is_err());
+        let mut mixed = value;
+        mixed["public_inputs"] = serde_json::json!(["1"]);
+        assert!(ProofEnvelope::from_json(&mixed.to_string()).is_err());
+    }
+}
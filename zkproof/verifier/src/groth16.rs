@@ -0,0 +1,252 @@
+// Groth16 验证 - 解析 bellman 格式的验证密钥与压缩证明，执行单次多重配对检查
+//
+// 验证密钥格式与 bellman 的 VerifyingKey::write 一致：
+//   alpha_g1 | beta_g1 | beta_g2 | gamma_g2 | delta_g1 | delta_g2（非压缩点）| ic 数量 (u32 BE) | ic（非压缩 G1）
+// 证明为压缩点 a (G1) | b (G2) | c (G1)，共 192 字节。
+use alloc::vec::Vec;
+use bls12_381::{multi_miller_loop, pairing, G1Affine, G1Projective, G2Affine, G2Prepared, Gt, Scalar};
+
+use crate::envelope::GROTH16_PROOF_LEN;
+
+/// 非压缩 G1 点的长度
+const G1_UNCOMPRESSED_LEN: usize = 96;
+/// 非压缩 G2 点的长度
+const G2_UNCOMPRESSED_LEN: usize = 192;
+/// 压缩 G1 点的长度
+const G1_COMPRESSED_LEN: usize = 48;
+/// 压缩 G2 点的长度
+const G2_COMPRESSED_LEN: usize = 96;
+
+/// Groth16 验证密钥
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    pub alpha_g1: G1Affine,
+    pub beta_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub gamma_g2: G2Affine,
+    pub delta_g1: G1Affine,
+    pub delta_g2: G2Affine,
+    /// 公开输入（含常量 1）对应的 G1 点
+    pub ic: Vec<G1Affine>,
+}
+
+impl VerifyingKey {
+    /// 解析验证密钥，点不在曲线或子群上、ic 含无穷远点或存在多余字节时返回 None
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let mut reader = Reader(bytes);
+        let alpha_g1 = reader.g1_uncompressed()?;
+        let beta_g1 = reader.g1_uncompressed()?;
+        let beta_g2 = reader.g2_uncompressed()?;
+        let gamma_g2 = reader.g2_uncompressed()?;
+        let delta_g1 = reader.g1_uncompressed()?;
+        let delta_g2 = reader.g2_uncompressed()?;
+
+        let ic_len = u32::from_be_bytes(reader.take(4)?.try_into().ok()?) as usize;
+        // 先按剩余长度检查数量，避免按不可信的数量分配内存
+        if reader.0.len() != ic_len.checked_mul(G1_UNCOMPRESSED_LEN)? {
+            return None;
+        }
+        let ic = (0..ic_len)
+            .map(|_| reader.g1_uncompressed().filter(|point| !bool::from(point.is_identity())))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self { alpha_g1, beta_g1, beta_g2, gamma_g2, delta_g1, delta_g2, ic })
+    }
+
+    /// 预计算验证所需的配对值与取反的 G2 点
+    pub fn prepare(&self) -> PreparedVerifyingKey {
+        PreparedVerifyingKey {
+            alpha_g1_beta_g2: pairing(&self.alpha_g1, &self.beta_g2),
+            neg_gamma_g2: G2Prepared::from(-self.gamma_g2),
+            neg_delta_g2: G2Prepared::from(-self.delta_g2),
+            ic: self.ic.clone(),
+        }
+    }
+}
+
+/// 预处理的验证密钥，同一密钥验证多个证明时复用
+#[derive(Debug)]
+pub struct PreparedVerifyingKey {
+    alpha_g1_beta_g2: Gt,
+    neg_gamma_g2: G2Prepared,
+    neg_delta_g2: G2Prepared,
+    ic: Vec<G1Affine>,
+}
+
+impl PreparedVerifyingKey {
+    /// 验证证明：e(A, B) · e(Σ 输入·ic, -γ) · e(C, -δ) = e(α, β)
+    ///
+    /// 公开输入数量与验证密钥不符时证明无效。
+    pub fn verify(&self, proof: &Proof, public_inputs: &[u128]) -> bool {
+        let public_inputs: Vec<Scalar> = public_inputs.iter().map(|input| scalar_from_u128(*input)).collect();
+        self.verify_scalars(proof, &public_inputs)
+    }
+
+    /// 以域元素形式的公开输入验证证明，用于不能以 u128 表示的输入（例如公开输入摘要）
+    pub fn verify_scalars(&self, proof: &Proof, public_inputs: &[Scalar]) -> bool {
+        if public_inputs.len() + 1 != self.ic.len() {
+            return false;
+        }
+
+        let mut acc = G1Projective::from(self.ic[0]);
+        for (input, base) in public_inputs.iter().zip(&self.ic[1..]) {
+            acc += base * input;
+        }
+
+        let b = G2Prepared::from(proof.b);
+        multi_miller_loop(&[
+            (&proof.a, &b),
+            (&G1Affine::from(acc), &self.neg_gamma_g2),
+            (&proof.c, &self.neg_delta_g2),
+        ])
+        .final_exponentiation()
+            == self.alpha_g1_beta_g2
+    }
+}
+
+/// Groth16 证明
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proof {
+    pub a: G1Affine,
+    pub b: G2Affine,
+    pub c: G1Affine,
+}
+
+impl Proof {
+    /// 解析压缩证明，长度不符、点无效或为无穷远点时返回 None
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != GROTH16_PROOF_LEN {
+            return None;
+        }
+        let mut reader = Reader(bytes);
+        let proof = Self {
+            a: reader.g1_compressed()?,
+            b: reader.g2_compressed()?,
+            c: reader.g1_compressed()?,
+        };
+        let has_identity = proof.a.is_identity() | proof.b.is_identity() | proof.c.is_identity();
+        (!bool::from(has_identity)).then_some(proof)
+    }
+}
+
+/// 验证序列化的 Groth16 证明，验证密钥或证明无法解码时视为无效
+pub fn verify(verification_key: &[u8], proof: &[u8], public_inputs: &[u128]) -> bool {
+    match (VerifyingKey::from_bytes(verification_key), Proof::from_bytes(proof)) {
+        (Some(vk), Some(proof)) => vk.prepare().verify(&proof, public_inputs),
+        _ => false,
+    }
+}
+
+/// 以域元素形式的公开输入验证序列化的 Groth16 证明
+pub fn verify_scalars(verification_key: &[u8], proof: &[u8], public_inputs: &[Scalar]) -> bool {
+    match (VerifyingKey::from_bytes(verification_key), Proof::from_bytes(proof)) {
+        (Some(vk), Some(proof)) => vk.prepare().verify_scalars(&proof, public_inputs),
+        _ => false,
+    }
+}
+
+/// u128 公开输入对应的标量，与证明生成时的编码一致
+pub fn scalar_from_u128(value: u128) -> Scalar {
+    Scalar::from_raw([value as u64, (value >> 64) as u64, 0, 0])
+}
+
+/// 顺序读取字节切片
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<&'a [u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn g1_uncompressed(&mut self) -> Option<G1Affine> {
+        Option::from(G1Affine::from_uncompressed(self.array::<G1_UNCOMPRESSED_LEN>()?))
+    }
+
+    fn g2_uncompressed(&mut self) -> Option<G2Affine> {
+        Option::from(G2Affine::from_uncompressed(self.array::<G2_UNCOMPRESSED_LEN>()?))
+    }
+
+    fn g1_compressed(&mut self) -> Option<G1Affine> {
+        Option::from(G1Affine::from_compressed(self.array::<G1_COMPRESSED_LEN>()?))
+    }
+
+    fn g2_compressed(&mut self) -> Option<G2Affine> {
+        Option::from(G2Affine::from_compressed(self.array::<G2_COMPRESSED_LEN>()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bellman::groth16;
+    use bellman::{Circuit, ConstraintSystem, SynthesisError};
+    use bls12_381::Bls12;
+    use rand::rngs::OsRng;
+
+    /// 证明知道 x 使 x · x = y，y 为公开输入
+    struct SquareCircuit {
+        x: Option<u128>,
+    }
+
+    impl Circuit<Scalar> for SquareCircuit {
+        fn synthesize<CS: ConstraintSystem<Scalar>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+            let x_value = self.x.map(scalar_from_u128);
+            let x = cs.alloc(|| "x", || x_value.ok_or(SynthesisError::AssignmentMissing))?;
+            let y = cs.alloc_input(
+                || "y",
+                || x_value.map(|x| x * x).ok_or(SynthesisError::AssignmentMissing),
+            )?;
+            cs.enforce(|| "x * x = y", |lc| lc + x, |lc| lc + x, |lc| lc + y);
+            Ok(())
+        }
+    }
+
+    fn prove(params: &groth16::Parameters<Bls12>, x: u128) -> (groth16::Proof<Bls12>, Vec<u8>) {
+        let proof = groth16::create_random_proof(SquareCircuit { x: Some(x) }, params, &mut OsRng).unwrap();
+        let mut bytes = Vec::new();
+        proof.write(&mut bytes).unwrap();
+        (proof, bytes)
+    }
+
+    #[test]
+    fn test_matches_bellman_verifier() {
+        let params = groth16::generate_random_parameters::<Bls12, _, _>(SquareCircuit { x: None }, &mut OsRng).unwrap();
+        let mut vk_bytes = Vec::new();
+        params.vk.write(&mut vk_bytes).unwrap();
+        assert_eq!(VerifyingKey::from_bytes(&vk_bytes).unwrap().ic, params.vk.ic);
+
+        let y = 1u128 << 80;
+        let (proof, proof_bytes) = prove(&params, 1 << 40);
+        let pvk = groth16::prepare_verifying_key(&params.vk);
+        assert!(groth16::verify_proof(&pvk, &proof, &[scalar_from_u128(y)]).is_ok());
+        assert!(verify(&vk_bytes, &proof_bytes, &[y]));
+
+        // 错误的公开输入、输入数量或其他语句的证明
+        let (_, other_bytes) = prove(&params, 3);
+        assert!(!verify(&vk_bytes, &proof_bytes, &[y + 1]));
+        assert!(!verify(&vk_bytes, &proof_bytes, &[y, 0]));
+        assert!(!verify(&vk_bytes, &other_bytes, &[y]));
+        assert!(verify(&vk_bytes, &other_bytes, &[9]));
+        assert!(verify_scalars(&vk_bytes, &proof_bytes, &[scalar_from_u128(y)]));
+        assert!(!verify_scalars(&vk_bytes, &proof_bytes, &[-scalar_from_u128(y)]));
+
+        // 截断、带多余字节或被篡改的验证密钥与证明
+        assert!(!verify(&vk_bytes[..vk_bytes.len() - 1], &proof_bytes, &[y]));
+        let mut trailing = vk_bytes.clone();
+        trailing.push(0);
+        assert!(VerifyingKey::from_bytes(&trailing).is_none());
+        assert!(!verify(&vk_bytes, &proof_bytes[..GROTH16_PROOF_LEN - 1], &[y]));
+        let mut corrupted = proof_bytes;
+        corrupted[10] ^= 1;
+        assert!(!verify(&vk_bytes, &corrupted, &[y]));
+    }
+}
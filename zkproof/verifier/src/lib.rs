@@ -0,0 +1,233 @@
+// 证明验证核心 - 证明封装解码、Groth16 配对检查与验证密钥哈希
+//
+// 不依赖标准库，可在 ink! 合约与运行时模块中使用：关闭默认的 std 特性即为 no_std（需要 alloc）。
+// std 特性另外提供证明封装的 JSON 编解码与 std::error::Error 实现；zkproof 在此之上提供证明生成、
+// 密钥注册表与验证缓存。
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use core::fmt;
+use sha2::{Digest, Sha256};
+
+pub mod envelope;
+pub mod groth16;
+
+pub use bls12_381::Scalar;
+pub use envelope::{Curve, ProofEnvelope};
+
+/// 证明系统后端标识
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProofSystemKind {
+    /// 确定性模拟后端，只检查约束可满足性，不具备密码学安全性，仅用于测试；
+    /// 未启用 mock 特性时其证明一律无效
+    Mock,
+    /// BLS12-381 上的 Groth16
+    Groth16,
+}
+
+impl ProofSystemKind {
+    /// 后端名称，用于参数文件命名与命令行参数
+    pub fn name(&self) -> &'static str {
+        match self {
+            ProofSystemKind::Mock => "mock",
+            ProofSystemKind::Groth16 => "groth16",
+        }
+    }
+
+    /// 后端编号，用于二进制证明封装
+    pub fn id(&self) -> u8 {
+        match self {
+            ProofSystemKind::Mock => 0,
+            ProofSystemKind::Groth16 => 1,
+        }
+    }
+
+    /// 由后端编号还原后端标识
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(ProofSystemKind::Mock),
+            1 => Some(ProofSystemKind::Groth16),
+            _ => None,
+        }
+    }
+}
+
+impl core::str::FromStr for ProofSystemKind {
+    type Err = VerifierError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "mock" => Ok(ProofSystemKind::Mock),
+            "groth16" => Ok(ProofSystemKind::Groth16),
+            _ => Err(VerifierError::UnknownProofSystem),
+        }
+    }
+}
+
+/// 验证核心的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerifierError {
+    /// 证明封装格式无效
+    InvalidEnvelope(&'static str),
+    /// 未知的证明系统
+    UnknownProofSystem,
+    /// JSON 编解码失败
+    #[cfg(feature = "std")]
+    Json(alloc::string::String),
+}
+
+impl fmt::Display for VerifierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifierError::InvalidEnvelope(message) => write!(f, "{}", message),
+            VerifierError::UnknownProofSystem => write!(f, "unknown proof system"),
+            #[cfg(feature = "std")]
+            VerifierError::Json(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerifierError {}
+
+/// 证明后端验证的公开语句
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    /// 全部公开输入
+    Inputs(alloc::vec::Vec<u128>),
+    /// 公开输入摘要，摘要模式电路唯一的公开输入
+    Digest([u8; 32]),
+}
+
+/// 验证密钥哈希（SHA-256），证明封装与密钥注册表以此引用验证密钥
+pub fn vk_hash(verification_key: &[u8]) -> [u8; 32] {
+    Sha256::digest(verification_key).into()
+}
+
+/// 模拟后端的证明：SHA-256(验证密钥 || 公开输入的大端字节)
+#[cfg(any(test, feature = "mock"))]
+pub fn mock_proof(verification_key: &[u8], public_inputs: &[u128]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(verification_key);
+    for input in public_inputs {
+        hasher.update(input.to_be_bytes());
+    }
+    hasher.finalize().into()
+}
+
+/// 模拟后端对公开输入摘要的证明：SHA-256(验证密钥 || 摘要)
+#[cfg(any(test, feature = "mock"))]
+pub fn mock_digest_proof(verification_key: &[u8], digest: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(verification_key);
+    hasher.update(digest);
+    hasher.finalize().into()
+}
+
+/// 使用指定后端验证证明
+///
+/// 无法解码的验证密钥或证明视为无效证明而非错误。
+pub fn verify(system: ProofSystemKind, verification_key: &[u8], proof: &[u8], public_inputs: &[u128]) -> bool {
+    match system {
+        #[cfg(any(test, feature = "mock"))]
+        ProofSystemKind::Mock => proof == mock_proof(verification_key, public_inputs).as_slice(),
+        #[cfg(not(any(test, feature = "mock")))]
+        ProofSystemKind::Mock => false,
+        ProofSystemKind::Groth16 => groth16::verify(verification_key, proof, public_inputs),
+    }
+}
+
+/// 公开输入摘要对应的域元素（小端字节），非规范编码返回 None
+pub fn digest_scalar(digest: &[u8; 32]) -> Option<Scalar> {
+    Option::from(Scalar::from_bytes(digest))
+}
+
+/// 验证以公开输入摘要作为唯一公开输入的证明
+///
+/// 摘要模式的电路只有这一个公开输入，链上只需保存 32 字节的摘要。
+pub fn verify_digest(system: ProofSystemKind, verification_key: &[u8], proof: &[u8], digest: &[u8; 32]) -> bool {
+    match system {
+        #[cfg(any(test, feature = "mock"))]
+        ProofSystemKind::Mock => proof == mock_digest_proof(verification_key, digest).as_slice(),
+        #[cfg(not(any(test, feature = "mock")))]
+        ProofSystemKind::Mock => false,
+        ProofSystemKind::Groth16 => digest_scalar(digest)
+            .is_some_and(|digest| groth16::verify_scalars(verification_key, proof, &[digest])),
+    }
+}
+
+/// 按公开语句的形式验证证明
+pub fn verify_statement(system: ProofSystemKind, verification_key: &[u8], proof: &[u8], statement: &Statement) -> bool {
+    match statement {
+        Statement::Inputs(public_inputs) => verify(system, verification_key, proof, public_inputs),
+        Statement::Digest(digest) => verify_digest(system, verification_key, proof, digest),
+    }
+}
+
+/// 使用验证密钥验证证明封装
+///
+/// 封装声明的后端必须是调用方预期的后端，验证密钥的哈希必须与封装中记录的哈希一致；
+/// 电路ID是否符合预期由调用方检查。
+pub fn verify_envelope(verification_key: &[u8], envelope: &ProofEnvelope, expected: ProofSystemKind) -> bool {
+    envelope.backend == expected
+        && vk_hash(verification_key) == envelope.vk_hash
+        && verify_statement(envelope.backend, verification_key, &envelope.proof, &envelope.statement())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_envelope_verification() {
+        let verification_key = b"mock verification key";
+        let public_inputs = [6050, 92, 1];
+        let envelope = ProofEnvelope::new(
+            ProofSystemKind::Mock,
+            1,
+            verification_key,
+            mock_proof(verification_key, &public_inputs).to_vec(),
+            public_inputs.to_vec(),
+        )
+        .unwrap();
+        assert!(verify_envelope(verification_key, &envelope, ProofSystemKind::Mock));
+
+        // 后端与预期不一致、验证密钥与哈希不一致、公开输入被篡改
+        assert!(!verify_envelope(verification_key, &envelope, ProofSystemKind::Groth16));
+        assert!(!verify_envelope(b"other verification key", &envelope, ProofSystemKind::Mock));
+        let mut tampered = envelope;
+        tampered.public_inputs[0] += 1;
+        assert!(!verify_envelope(verification_key, &tampered, ProofSystemKind::Mock));
+
+        assert_eq!("groth16".parse::<ProofSystemKind>(), Ok(ProofSystemKind::Groth16));
+        assert_eq!("plonk".parse::<ProofSystemKind>(), Err(VerifierError::UnknownProofSystem));
+    }
+
+    #[test]
+    fn test_digest_verification() {
+        let verification_key = b"mock verification key";
+        let mut digest = [0u8; 32];
+        digest[0] = 1;
+        digest[16] = 2;
+        assert_eq!(digest_scalar(&digest), Some(Scalar::from_raw([1, 0, 2, 0])));
+        assert_eq!(digest_scalar(&[0xff; 32]), None);
+
+        let proof = mock_digest_proof(verification_key, &digest);
+        assert!(verify_digest(ProofSystemKind::Mock, verification_key, &proof, &digest));
+        assert!(verify_statement(ProofSystemKind::Mock, verification_key, &proof, &Statement::Digest(digest)));
+        assert!(!verify(ProofSystemKind::Mock, verification_key, &proof, &[1, 2]));
+        digest[31] = 1;
+        assert!(!verify_digest(ProofSystemKind::Mock, verification_key, &proof, &digest));
+
+        let envelope = ProofEnvelope::with_digest(ProofSystemKind::Mock, 1, verification_key, proof.to_vec(), [1; 32]).unwrap();
+        assert!(!verify_envelope(verification_key, &envelope, ProofSystemKind::Mock));
+        let envelope = ProofEnvelope {
+            public_input_digest: Some(digest),
+            proof: mock_digest_proof(verification_key, &digest).to_vec(),
+            ..envelope
+        };
+        assert!(verify_envelope(verification_key, &envelope, ProofSystemKind::Mock));
+    }
+}